@@ -0,0 +1,531 @@
+// the bot's lookups and some upkeep from a terminal, straight against the scraper's database
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{stdout, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sqlx::{Connection, SqliteConnection};
+
+use elodon_core::display::ElodonDisplay;
+use elodon_core::elo;
+use elodon_core::error::ElodonError;
+use elodon_core::eval::{fit_ratings, Dataset};
+use elodon_core::filters::*;
+use elodon_core::history::record_elo;
+use elodon_core::schema;
+use elodon_core::structs::*;
+
+mod site;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+const LEVELS: [DisplayLevel; 4] = [DisplayLevel::Easy, DisplayLevel::Med, DisplayLevel::Hard, DisplayLevel::OniPlus];
+const SUGGESTIONS: usize = 8;
+
+#[derive(Parser)]
+#[command(name = "elodon-cli", about = "Query and look after the elodon database")]
+struct Cli {
+    /// the database the scraper writes to
+    #[arg(long, global = true, env = "ELODON_DB", default_value = "./../taiko.db")]
+    db: String,
+    /// print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// a player's ratings and their plays at one level, like /player
+    Player {
+        #[command(flatten)]
+        who: Who,
+        #[arg(long, default_value = "Oni+")]
+        level: DisplayLevel,
+    },
+    /// everyone's plays on one chart, like /scores
+    Scores {
+        song_id: u32,
+        #[arg(long, default_value = "Oni")]
+        level: Level,
+    },
+    /// the charts on which a score is closest to a z value, like /suggest
+    Suggest {
+        #[command(flatten)]
+        who: Who,
+        /// the desired score in ks e.g miyabi = 1000
+        score_k: u32,
+        /// the desired z value
+        #[arg(long, default_value_t = 0., allow_negative_numbers = true)]
+        z: f32,
+        #[arg(long, default_value = "Oni+")]
+        level: DisplayLevel,
+    },
+    /// players by elo
+    Leaderboard {
+        #[arg(long, default_value = "Oni+")]
+        level: DisplayLevel,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// rows of a table matching a filter, like /dev sql fetch
+    Fetch {
+        table: FilterType,
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// refit every player's elo on their plays. only lists the changes without --write
+    Recompute {
+        #[arg(long)]
+        write: bool,
+    },
+    /// a whole table as JSON
+    Export {
+        table: FilterType,
+        /// by default stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// rows from an export, replacing the ones with the same key
+    Import {
+        table: FilterType,
+        file: PathBuf,
+    },
+    /// the leaderboards, chart scoreboards and player pages as a static html site
+    Site {
+        #[arg(default_value = "site")]
+        out: PathBuf,
+    },
+}
+
+impl Command {
+    // only these write to the database and need the bot's tables, the rest also work on a read-only copy
+    fn writes(&self) -> bool {
+        matches!(self, Command::Recompute { write: true } | Command::Import { .. })
+    }
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct Who {
+    /// the primary account of this discord user
+    #[arg(long)]
+    discord: Option<u64>,
+    #[arg(long)]
+    donder: Option<i64>,
+}
+
+impl Who {
+    // a discord user's primary account, picked the same way the bot does
+    async fn user(&self, conn: &mut SqliteConnection) -> Result<User, ElodonError> {
+        if let Some(donder) = self.donder {
+            return User::fetch_one(conn, UserFilter::new().user_id(Some(donder))).await;
+        }
+        let discord = self.discord.unwrap_or_default();
+        let primary: Option<(i64,)> = sqlx::query_as("SELECT user_id FROM primary_accounts WHERE discord_id = ?")
+            .bind(discord as i64)
+            .fetch_optional(&mut *conn).await?;
+        let primary = primary.map(|(user_id,)| user_id);
+        let users = User::fetch_all(conn, UserFilter::new().discord_id(Some(discord))).await?;
+        users.into_iter()
+            .min_by_key(|user| (Some(user.id) != primary, user.id))
+            .ok_or(ElodonError::NoResults { search: "users".to_string(), id: format!("discord_id is {discord}") })
+    }
+}
+
+#[derive(Args)]
+struct FilterArgs {
+    #[arg(long)]
+    donder: Option<i64>,
+    #[arg(long)]
+    discord: Option<u64>,
+    #[arg(long)]
+    song: Option<u32>,
+    /// Easy to Ura
+    #[arg(long)]
+    chart_level: Option<Level>,
+    /// Easy to Oni+
+    #[arg(long)]
+    level: Option<DisplayLevel>,
+    #[arg(long)]
+    genre: Option<Genre>,
+    /// only members of this discord server
+    #[arg(long)]
+    guild: Option<u64>,
+}
+
+impl From<&FilterArgs> for GeneralFilter {
+    fn from(args: &FilterArgs) -> Self {
+        GeneralFilter {
+            user_id: args.donder,
+            discord_id: args.discord,
+            song_id: args.song,
+            level: args.chart_level,
+            display_level: args.level,
+            genre: args.genre,
+            guild_id: args.guild,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RatedPlay {
+    #[serde(flatten)]
+    play: Play,
+    player: String,
+    song: String,
+    z: Option<f32>,
+}
+
+impl RatedPlay {
+    fn line(&self) -> String {
+        let z = self.z.map(|z| format!("{z:+.2}")).unwrap_or("  ?  ".to_string());
+        format!("{z:>6}  {:>7}  {:<13} {} ({})", self.play.score, self.player, self.song, self.play.level())
+    }
+}
+
+#[derive(Serialize)]
+struct Suggestion {
+    song_id: u32,
+    level: u32,
+    song: String,
+    z_lower: f32,
+    z_upper: f32,
+}
+
+#[derive(Serialize)]
+struct EloChange {
+    user_id: i64,
+    user_name: String,
+    level: &'static str,
+    old: Option<f32>,
+    new: f32,
+}
+
+#[derive(Serialize)]
+struct Page<R> {
+    count: usize,
+    offset: usize,
+    rows: Vec<R>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let cli = Cli::parse();
+    let mut conn = SqliteConnection::connect(&cli.db).await?;
+    if cli.command.writes() {
+        schema::create_tables(&mut conn).await?;
+    }
+    let conn = &mut conn;
+
+    match &cli.command {
+        Command::Player { who, level } => player(conn, who, *level, cli.json).await,
+        Command::Scores { song_id, level } => scores(conn, *song_id, *level, cli.json).await,
+        Command::Suggest { who, score_k, z, level } => suggest(conn, who, score_k * 1000, *z, *level, cli.json).await,
+        Command::Leaderboard { level, limit } => leaderboard(conn, *level, *limit, cli.json).await,
+        Command::Fetch { table, filter, offset, limit } => {
+            let filter = GeneralFilter::from(filter);
+            match table {
+                FilterType::User => fetch::<User>(conn, filter, *offset, *limit, cli.json).await,
+                FilterType::Song => fetch::<Song>(conn, filter, *offset, *limit, cli.json).await,
+                FilterType::Chart => fetch::<Chart>(conn, filter, *offset, *limit, cli.json).await,
+                FilterType::Play => fetch::<Play>(conn, filter, *offset, *limit, cli.json).await,
+            }
+        }
+        Command::Recompute { write } => recompute(conn, *write, cli.json).await,
+        Command::Export { table, output } => match table {
+            FilterType::User => export::<User>(conn, output).await,
+            FilterType::Song => export::<Song>(conn, output).await,
+            FilterType::Chart => export::<Chart>(conn, output).await,
+            FilterType::Play => export::<Play>(conn, output).await,
+        },
+        Command::Import { table, file } => match table {
+            FilterType::User => import::<User>(conn, file).await,
+            FilterType::Song => import::<Song>(conn, file).await,
+            FilterType::Chart => import::<Chart>(conn, file).await,
+            FilterType::Play => import::<Play>(conn, file).await,
+        },
+        Command::Site { out } => {
+            let report = site::generate(conn, out).await?;
+            println!("wrote {} player and {} chart pages to {}", report.players, report.charts, out.display());
+            Ok(())
+        }
+    }
+}
+
+fn print_json(value: &impl Serialize) -> Result<(), Error> {
+    serde_json::to_writer_pretty(stdout().lock(), value)?;
+    println!();
+    Ok(())
+}
+
+fn elo_text(elo: Option<f32>) -> String {
+    elo.map(|elo| format!("{elo:.0}")).unwrap_or("-".to_string())
+}
+
+// z values of plays against the players' elo at the play's level
+async fn rate(conn: &mut SqliteConnection, plays: Vec<Play>, users: &HashMap<i64, User>) -> Result<Vec<RatedPlay>, ElodonError> {
+    let charts: HashMap<(u32, u32), Chart> = Chart::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+        .into_iter()
+        .map(|chart| ((chart.id, chart.level), chart))
+        .collect();
+    let songs: HashMap<u32, Song> = Song::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+        .into_iter()
+        .map(|song| (song.id, song))
+        .collect();
+    Ok(plays.into_iter().map(|play| {
+        let user = users.get(&play.user);
+        let elo = user.and_then(|user| user.elo(play.level().into()));
+        RatedPlay {
+            player: user.map(|user| user.name.clone()).unwrap_or(play.user.to_string()),
+            song: songs.get(&play.song).map(Song::get_name).unwrap_or(play.song.to_string()),
+            z: charts.get(&(play.song, play.level)).and_then(|chart| elo::get_z_value(play.score, elo, chart, 0.)),
+            play,
+        }
+    }).collect())
+}
+
+async fn player(conn: &mut SqliteConnection, who: &Who, level: DisplayLevel, json: bool) -> Result<(), Error> {
+    let user = who.user(conn).await?;
+    let plays = Play::fetch_all(conn, GeneralFilter::new().user_id(Some(user.id)).display_level(Some(level))).await?;
+    let users = HashMap::from([(user.id, user.clone())]);
+    let plays = rate(conn, plays, &users).await?
+        .into_iter()
+        .sorted_by(|a, b| b.z.unwrap_or(f32::MIN).total_cmp(&a.z.unwrap_or(f32::MIN)))
+        .collect_vec();
+
+    if json {
+        #[derive(Serialize)]
+        struct Profile<'a> { user: &'a User, plays: &'a [RatedPlay] }
+        return print_json(&Profile { user: &user, plays: &plays });
+    }
+    println!("{}", user.get_display_text());
+    println!("{}", LEVELS.iter().map(|level| format!("{} {}", level.name(), elo_text(user.elo(*level)))).join(" | "));
+    println!();
+    for play in plays {
+        println!("{}", play.line());
+    }
+    Ok(())
+}
+
+async fn scores(conn: &mut SqliteConnection, song_id: u32, level: Level, json: bool) -> Result<(), Error> {
+    let chart = Chart::fetch_one(conn, GeneralFilter::new().song_id(Some(song_id)).level(Some(level))).await?;
+    let plays = Play::fetch_all(conn, chart).await?;
+    let users: HashMap<i64, User> = User::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect();
+    let plays = rate(conn, plays, &users).await?
+        .into_iter()
+        .sorted_by_key(|play| std::cmp::Reverse(play.play.score))
+        .collect_vec();
+
+    if json {
+        return print_json(&plays);
+    }
+    println!("{}", chart.full_name(conn).await?);
+    for play in plays {
+        println!("{}", play.line());
+    }
+    Ok(())
+}
+
+async fn suggest(conn: &mut SqliteConnection, who: &Who, score: u32, desired_z: f32, level: DisplayLevel, json: bool) -> Result<(), Error> {
+    let user = who.user(conn).await?;
+    let charts = Chart::fetch_all(conn, GeneralFilter::new().display_level(Some(level))).await?;
+    let mut suggestions = vec![];
+    for (z_lower, z_upper, chart) in elo::suggest(&charts, user.elo(level), score, desired_z, SUGGESTIONS) {
+        suggestions.push(Suggestion {
+            song_id: chart.id,
+            level: chart.level,
+            song: chart.full_name(conn).await?,
+            z_lower,
+            z_upper,
+        });
+    }
+
+    if json {
+        return print_json(&suggestions);
+    }
+    println!("Charts that {} has a 70% chance of getting a score of {} on with a z value of at least {}:", user.name, score, desired_z);
+    for suggestion in suggestions {
+        println!("{:+.2} to {:+.2}  {}", suggestion.z_lower, suggestion.z_upper, suggestion.song);
+    }
+    Ok(())
+}
+
+async fn leaderboard(conn: &mut SqliteConnection, level: DisplayLevel, limit: usize, json: bool) -> Result<(), Error> {
+    let users = User::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+        .into_iter()
+        .filter(|user| user.elo(level).is_some())
+        .sorted_by(|a, b| b.elo(level).unwrap_or_default().total_cmp(&a.elo(level).unwrap_or_default()))
+        .take(limit)
+        .collect_vec();
+
+    if json {
+        return print_json(&users);
+    }
+    for (rank, user) in users.iter().enumerate() {
+        println!("{:>3}. {:>6}  {}", rank + 1, elo_text(user.elo(level)), user.get_display_text());
+    }
+    Ok(())
+}
+
+async fn fetch<R: Filterable + ElodonDisplay + Serialize>(conn: &mut SqliteConnection, filter: GeneralFilter, offset: usize, limit: usize, json: bool) -> Result<(), Error> {
+    let count = R::count(conn, filter).await?;
+    let rows = R::fetch_page(conn, filter, offset, limit).await?;
+
+    if json {
+        return print_json(&Page { count, offset, rows });
+    }
+    for row in &rows {
+        println!("{}", row.get_display_text());
+    }
+    println!("{}-{} of {}", offset + 1.min(rows.len()), offset + rows.len(), count);
+    Ok(())
+}
+
+// the same least squares fit /dev eval reports as "fitted elo"
+async fn recompute(conn: &mut SqliteConnection, write: bool, json: bool) -> Result<(), Error> {
+    let fitted = fit_ratings(&Dataset::load(conn).await?.samples);
+    let users = User::fetch_all_or_empty(conn, GeneralFilter::new()).await?;
+    let changes = users.iter()
+        .flat_map(|user| LEVELS.iter().filter_map(|level| {
+            let new = *fitted.get(&(user.id, *level))?;
+            let old = user.elo(*level);
+            (old.map(|old| (old - new).abs() >= 0.5) != Some(false)).then(|| EloChange {
+                user_id: user.id,
+                user_name: user.name.clone(),
+                level: level.name(),
+                old,
+                new,
+            })
+        }))
+        .collect_vec();
+
+    if write {
+        let mut transaction = conn.begin().await?;
+        for user in &users {
+            let elo = |level| fitted.get(&(user.id, level)).copied().or(user.elo(level));
+            sqlx::query("UPDATE users SET elo1 = ?, elo2 = ?, elo3 = ?, elo4 = ? WHERE user_id = ?")
+                .bind(elo(DisplayLevel::Easy))
+                .bind(elo(DisplayLevel::Med))
+                .bind(elo(DisplayLevel::Hard))
+                .bind(elo(DisplayLevel::OniPlus))
+                .bind(user.id)
+                .execute(&mut *transaction).await?;
+        }
+        transaction.commit().await?;
+        record_elo(conn).await?;
+    }
+
+    if json {
+        return print_json(&changes);
+    }
+    for change in &changes {
+        println!("{:<13} {:<5} {:>6} -> {:.0}", change.user_name, change.level, elo_text(change.old), change.new);
+    }
+    println!("{} ratings {}", changes.len(), if write { "updated" } else { "would change, run with --write to save them" });
+    Ok(())
+}
+
+async fn export<R: Filterable + Serialize>(conn: &mut SqliteConnection, output: &Option<PathBuf>) -> Result<(), Error> {
+    let rows = R::fetch_all_or_empty(conn, GeneralFilter::new()).await?;
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(stdout().lock()),
+    };
+    serde_json::to_writer_pretty(&mut writer, &rows)?;
+    writeln!(writer)?;
+    if output.is_some() {
+        eprintln!("exported {} rows", rows.len());
+    }
+    Ok(())
+}
+
+async fn import<R: Insert>(conn: &mut SqliteConnection, file: &PathBuf) -> Result<(), Error> {
+    let rows: Vec<R> = serde_json::from_reader(BufReader::new(File::open(file)?))?;
+    let mut transaction = conn.begin().await?;
+    for row in &rows {
+        row.insert(&mut transaction).await?;
+    }
+    transaction.commit().await?;
+    println!("imported {} rows", rows.len());
+    Ok(())
+}
+
+// how a row from an export goes back into its table
+trait Insert: DeserializeOwned {
+    async fn insert(&self, conn: &mut SqliteConnection) -> Result<(), ElodonError>;
+}
+
+impl Insert for User {
+    async fn insert(&self, conn: &mut SqliteConnection) -> Result<(), ElodonError> {
+        sqlx::query("INSERT OR REPLACE INTO users (user_id, discord_id, user_name, elo1, elo2, elo3, elo4) VALUES (?,?,?,?,?,?,?)")
+            .bind(self.id)
+            .bind(self.discord)
+            .bind(&self.name)
+            .bind(self.elo1)
+            .bind(self.elo2)
+            .bind(self.elo3)
+            .bind(self.elo4)
+            .execute(conn).await?;
+        Ok(())
+    }
+}
+
+impl Insert for Song {
+    async fn insert(&self, conn: &mut SqliteConnection) -> Result<(), ElodonError> {
+        sqlx::query("INSERT OR REPLACE INTO songs (song_id, song_name_eng, song_name_jap, genre_id) VALUES (?,?,?,?)")
+            .bind(self.id)
+            .bind(&self.name_eng)
+            .bind(&self.name_jap)
+            .bind(self.genre)
+            .execute(conn).await?;
+        Ok(())
+    }
+}
+
+impl Insert for Chart {
+    async fn insert(&self, conn: &mut SqliteConnection) -> Result<(), ElodonError> {
+        sqlx::query("INSERT OR REPLACE INTO charts (song_id, level_id, score_slope, score_miyabi, sd_mean, sd_sd) VALUES (?,?,?,?,?,?)")
+            .bind(self.id)
+            .bind(self.level)
+            .bind(self.score_slope)
+            .bind(self.score_miyabi)
+            .bind(self.sd_mean)
+            .bind(self.sd_sd)
+            .execute(conn).await?;
+        Ok(())
+    }
+}
+
+impl Insert for Play {
+    async fn insert(&self, conn: &mut SqliteConnection) -> Result<(), ElodonError> {
+        sqlx::query("INSERT OR REPLACE INTO top_plays \
+            (user_id, song_id, level_id, score, good_cnt, ok_cnt, bad_cnt, combo_cnt, roll_cnt, rank, crown) \
+            VALUES (?,?,?,?,?,?,?,?,?,?,?)")
+            .bind(self.user)
+            .bind(self.song)
+            .bind(self.level)
+            .bind(self.score)
+            .bind(self.good_cnt)
+            .bind(self.ok_cnt)
+            .bind(self.bad_cnt)
+            .bind(self.combo_cnt)
+            .bind(self.roll_cnt)
+            .bind(self.rank)
+            .bind(self.crown)
+            .execute(conn).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+// the database as plain html files: elo leaderboards per level, a scoreboard per chart like
+// /scores and a page per player like /player. everything links relatively so the folder can
+// be put on any file host
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use itertools::Itertools;
+use sqlx::SqliteConnection;
+
+use elodon_core::display::{PlainSymbols, Symbols};
+use elodon_core::elo;
+use elodon_core::error::ElodonError;
+use elodon_core::filters::*;
+use elodon_core::structs::*;
+
+use crate::LEVELS;
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:60em;margin:auto;padding:1em}\
+    table{border-collapse:collapse;margin-bottom:2em}td,th{padding:.2em .8em;text-align:left}\
+    tr:nth-child(even){background:#f2f2f2}.num{text-align:right;font-variant-numeric:tabular-nums}";
+
+pub struct SiteReport {
+    pub players: usize,
+    pub charts: usize,
+}
+
+// everything the pages are built from, loaded once
+struct Site {
+    users: HashMap<i64, User>,
+    songs: HashMap<u32, Song>,
+    charts: HashMap<(u32, u32), Chart>,
+    plays: Vec<Play>,
+}
+
+impl Site {
+    async fn load(conn: &mut SqliteConnection) -> Result<Site, ElodonError> {
+        Ok(Site {
+            users: User::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+                .into_iter().map(|user| (user.id, user)).collect(),
+            songs: Song::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+                .into_iter().map(|song| (song.id, song)).collect(),
+            charts: Chart::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+                .into_iter().map(|chart| ((chart.id, chart.level), chart)).collect(),
+            plays: Play::fetch_all_or_empty(conn, GeneralFilter::new()).await?,
+        })
+    }
+
+    fn elo(&self, play: &Play) -> Option<f32> {
+        self.users.get(&play.user)?.elo(play.level().into())
+    }
+
+    fn z_value(&self, play: &Play) -> Option<f32> {
+        elo::get_z_value(play.score, self.elo(play), self.charts.get(&(play.song, play.level))?, 0.)
+    }
+
+    fn chart_name(&self, song: u32, level: Level) -> String {
+        let name = self.songs.get(&song).map(Song::get_name).unwrap_or(song.to_string());
+        format!("{} ({})", name, level)
+    }
+
+    fn player_link(&self, user_id: i64, prefix: &str) -> String {
+        let name = self.users.get(&user_id).map(|user| user.name.clone()).unwrap_or(user_id.to_string());
+        format!("<a href=\"{prefix}player/{user_id}.html\">{}</a>", escape(&name))
+    }
+
+    fn chart_link(&self, song: u32, level: Level, prefix: &str) -> String {
+        format!("<a href=\"{prefix}chart/{song}-{}.html\">{}</a>", level.id(), escape(&self.chart_name(song, level)))
+    }
+
+    fn index(&self) -> String {
+        let mut body = String::from("<h1>elodon</h1><p><a href=\"charts.html\">All charts</a></p>");
+        for level in LEVELS {
+            let ranked = self.users.values()
+                .filter_map(|user| Some((user, user.elo(level)?)))
+                .sorted_by(|(a, a_elo), (b, b_elo)| b_elo.total_cmp(a_elo).then(a.id.cmp(&b.id)));
+            let _ = write!(body, "<h2>{} leaderboard</h2><table><tr><th>#</th><th>Player</th><th class=num>ELO</th></tr>", level.name());
+            for (rank, (user, elo)) in ranked.enumerate() {
+                let _ = write!(body, "<tr><td class=num>{}</td><td>{}</td><td class=num>{:.0}</td></tr>",
+                               rank + 1, self.player_link(user.id, ""), elo);
+            }
+            body.push_str("</table>");
+        }
+        page("elodon", &body)
+    }
+
+    fn chart_list(&self) -> String {
+        let mut body = String::from("<p><a href=\"index.html\">Leaderboards</a></p><h1>Charts</h1>");
+        let by_genre = self.songs.values()
+            .sorted_by_key(|song| (song.genre, song.id))
+            .group_by(|song| song.genre());
+        for (genre, songs) in &by_genre {
+            let _ = write!(body, "<h2>{}</h2><table>", genre);
+            for song in songs {
+                let levels = self.charts.keys()
+                    .filter(|(id, _)| *id == song.id)
+                    .sorted()
+                    .filter_map(|(_, level)| Level::try_from(*level).ok())
+                    .map(|level| format!("<a href=\"chart/{}-{}.html\">{}</a>", song.id, level.id(), level))
+                    .join(" ");
+                let _ = write!(body, "<tr><td>{}</td><td>{}</td></tr>", escape(&song.get_name()), levels);
+            }
+            body.push_str("</table>");
+        }
+        page("elodon charts", &body)
+    }
+
+    fn chart(&self, chart: &Chart, plays: &[&Play]) -> String {
+        let name = self.chart_name(chart.id, chart.level());
+        let mut body = format!("<p><a href=\"../charts.html\">All charts</a></p><h1>{}</h1>", escape(&name));
+        body.push_str("<table><tr><th>#</th><th>Player</th><th class=num>Score</th><th></th><th class=num>z</th></tr>");
+        for (rank, play) in plays.iter().sorted_by_key(|play| (std::cmp::Reverse(play.score), play.user)).enumerate() {
+            let _ = write!(body, "<tr><td class=num>{}</td><td>{}</td><td class=num>{}</td><td>{} {}</td><td class=num>{}</td></tr>",
+                           rank + 1, self.player_link(play.user, "../"), play.score,
+                           PlainSymbols.crown(play.crown), PlainSymbols.rank(play.rank), z_text(self.z_value(play)));
+        }
+        body.push_str("</table>");
+        page(&name, &body)
+    }
+
+    fn player(&self, user: &User, plays: &[&Play]) -> String {
+        let mut body = format!("<p><a href=\"../index.html\">Leaderboards</a></p><h1>{}</h1><table><tr>", escape(&user.name));
+        for level in LEVELS {
+            let _ = write!(body, "<th>{}</th>", level.name());
+        }
+        body.push_str("</tr><tr>");
+        for level in LEVELS {
+            let _ = write!(body, "<td class=num>{}</td>", user.elo(level).map(|elo| format!("{elo:.0}")).unwrap_or("-".to_string()));
+        }
+        body.push_str("</tr></table>");
+
+        for level in LEVELS {
+            let plays = plays.iter()
+                .filter(|play| DisplayLevel::from(play.level()) == level)
+                .map(|play| (*play, self.z_value(play)))
+                .sorted_by(|(_, a), (_, b)| b.unwrap_or(f32::MIN).total_cmp(&a.unwrap_or(f32::MIN)))
+                .collect_vec();
+            if plays.is_empty() {
+                continue;
+            }
+
+            let stats = elo::genre_stats(plays.iter().filter_map(|(play, z)| {
+                let chart = self.charts.get(&(play.song, play.level))?;
+                let shift = elo::get_implied_elo(play.score, chart).zip(user.elo(level)).map(|(implied, elo)| implied - elo);
+                Some((self.songs.get(&play.song)?.genre(), (*z)?, shift))
+            }));
+            let _ = write!(body, "<h2>{}</h2><table><tr><th>Genre</th><th class=num>Plays</th><th class=num>mean z</th><th class=num>ELO</th></tr>", level.name());
+            for genre in stats.iter().filter(|stats| stats.count >= elo::MIN_GENRE_PLAYS) {
+                let _ = write!(body, "<tr><td>{}</td><td class=num>{}</td><td class=num>{:+.2}</td><td class=num>{}</td></tr>",
+                               genre.genre, genre.count, genre.mean_z,
+                               genre.elo_shift.map(|shift| format!("{shift:+.0}")).unwrap_or_default());
+            }
+            body.push_str("</table><table><tr><th>Chart</th><th class=num>Score</th><th></th><th class=num>z</th></tr>");
+            for (play, z) in plays {
+                let _ = write!(body, "<tr><td>{}</td><td class=num>{}</td><td>{} {}</td><td class=num>{}</td></tr>",
+                               self.chart_link(play.song, play.level(), "../"), play.score,
+                               PlainSymbols.crown(play.crown), PlainSymbols.rank(play.rank), z_text(z));
+            }
+            body.push_str("</table>");
+        }
+        page(&user.name, &body)
+    }
+}
+
+fn z_text(z: Option<f32>) -> String {
+    z.map(|z| format!("{z:+.2}")).unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!("<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title><style>{STYLE}</style></head><body>{body}</body></html>\n",
+            escape(title))
+}
+
+// writes index.html, charts.html, chart/<song>-<level>.html and player/<donder id>.html into out
+pub async fn generate(conn: &mut SqliteConnection, out: &Path) -> Result<SiteReport, Box<dyn std::error::Error + Send + Sync>> {
+    let site = Site::load(conn).await?;
+    fs::create_dir_all(out.join("chart"))?;
+    fs::create_dir_all(out.join("player"))?;
+    fs::write(out.join("index.html"), site.index())?;
+    fs::write(out.join("charts.html"), site.chart_list())?;
+
+    let by_chart = site.plays.iter().into_group_map_by(|play| (play.song, play.level));
+    for chart in site.charts.values() {
+        let plays = by_chart.get(&(chart.id, chart.level)).map(Vec::as_slice).unwrap_or_default();
+        fs::write(out.join(format!("chart/{}-{}.html", chart.id, chart.level)), site.chart(chart, plays))?;
+    }
+    let by_user = site.plays.iter().into_group_map_by(|play| play.user);
+    for user in site.users.values() {
+        let plays = by_user.get(&user.id).map(Vec::as_slice).unwrap_or_default();
+        fs::write(out.join(format!("player/{}.html", user.id)), site.player(user, plays))?;
+    }
+    Ok(SiteReport { players: site.users.len(), charts: site.charts.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use elodon_core::schema::fixture_connection;
+
+    use super::*;
+
+    // a fresh folder under the system temp dir, removed again when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> TempDir {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+            TempDir(std::env::temp_dir().join(format!("elodon-site-{}-{nanos}", std::process::id())))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // every href of the page at path, resolved against the page's folder
+    fn links(path: &Path) -> Vec<PathBuf> {
+        let html = fs::read_to_string(path).unwrap();
+        html.split("href=\"").skip(1)
+            .map(|rest| path.parent().unwrap().join(&rest[..rest.find('"').unwrap()]))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn site_pages_link_to_each_other() {
+        let mut conn = fixture_connection().await;
+        let user = User::fetch_one(&mut conn, UserFilter::new().discord_id(Some(900000000000000000))).await.unwrap();
+        sqlx::query("UPDATE users SET user_name = '<b>Don & \"Ka\"</b>' WHERE user_id = ?")
+            .bind(user.id)
+            .execute(&mut conn).await.unwrap();
+
+        let out = TempDir::new();
+        let report = generate(&mut conn, &out.0).await.unwrap();
+        assert!(report.players > 0 && report.charts > 0);
+
+        let index = out.0.join("index.html");
+        let chart = out.0.join("chart/1-4.html");
+        let player = out.0.join(format!("player/{}.html", user.id));
+        for page in [&index, &chart, &player] {
+            assert!(page.is_file(), "{} is missing", page.display());
+            for link in links(page) {
+                assert!(link.is_file(), "{} links to {}, which doesn't exist", page.display(), link.display());
+            }
+        }
+
+        let html = fs::read_to_string(&player).unwrap();
+        assert!(html.contains("<h1>&lt;b&gt;Don &amp; &quot;Ka&quot;&lt;/b&gt;</h1>"));
+        assert!(!html.contains("<b>Don"));
+        assert!(fs::read_to_string(&index).unwrap().contains("&lt;b&gt;Don &amp;"));
+    }
+}
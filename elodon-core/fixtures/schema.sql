@@ -0,0 +1,42 @@
+-- the tables the scraper owns, as far as elodon reads them. used to build test databases
+CREATE TABLE IF NOT EXISTS users (
+    user_id    INTEGER PRIMARY KEY,
+    discord_id INTEGER,
+    user_name  TEXT NOT NULL,
+    elo1       REAL,
+    elo2       REAL,
+    elo3       REAL,
+    elo4       REAL
+);
+
+CREATE TABLE IF NOT EXISTS songs (
+    song_id       INTEGER PRIMARY KEY,
+    song_name_eng TEXT,
+    song_name_jap TEXT NOT NULL,
+    genre_id      INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS charts (
+    song_id      INTEGER NOT NULL,
+    level_id     INTEGER NOT NULL,
+    score_slope  INTEGER,
+    score_miyabi INTEGER,
+    sd_mean      REAL,
+    sd_sd        REAL,
+    PRIMARY KEY (song_id, level_id)
+);
+
+CREATE TABLE IF NOT EXISTS top_plays (
+    user_id   INTEGER NOT NULL,
+    song_id   INTEGER NOT NULL,
+    level_id  INTEGER NOT NULL,
+    score     INTEGER NOT NULL,
+    good_cnt  INTEGER NOT NULL,
+    ok_cnt    INTEGER NOT NULL,
+    bad_cnt   INTEGER NOT NULL,
+    combo_cnt INTEGER NOT NULL,
+    roll_cnt  INTEGER NOT NULL,
+    rank      INTEGER NOT NULL,
+    crown     INTEGER NOT NULL,
+    PRIMARY KEY (user_id, song_id, level_id)
+);
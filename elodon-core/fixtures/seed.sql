@@ -0,0 +1,710 @@
+-- generated, deterministic. 12 players with known elo, 30 songs, plays drawn around the elo prediction
+BEGIN;
+INSERT INTO users VALUES (100000000000, 900000000000000000, 'player00', NULL, NULL, 842.5, 1092.5);
+INSERT INTO users VALUES (100000007919, 900000000000000001, 'player01', NULL, NULL, 940.1, 1190.1);
+INSERT INTO users VALUES (100000015838, 900000000000000002, 'player02', NULL, NULL, 963.9, 1213.9);
+INSERT INTO users VALUES (100000023757, 900000000000000003, 'player03', NULL, NULL, 1082.1, 1332.1);
+INSERT INTO users VALUES (100000031676, 900000000000000004, 'player04', NULL, NULL, 1128.1, 1378.1);
+INSERT INTO users VALUES (100000039595, 900000000000000005, 'player05', NULL, NULL, 1221.0, 1471.0);
+INSERT INTO users VALUES (100000047514, 900000000000000006, 'player06', NULL, NULL, 1299.0, 1549.0);
+INSERT INTO users VALUES (100000055433, 900000000000000007, 'player07', NULL, NULL, 1342.7, 1592.7);
+INSERT INTO users VALUES (100000063352, 900000000000000008, 'player08', NULL, NULL, 1397.9, 1647.9);
+INSERT INTO users VALUES (100000071271, 900000000000000009, 'player09', NULL, NULL, 1455.7, 1705.7);
+INSERT INTO users VALUES (100000079190, 900000000000000010, 'player10', NULL, NULL, 1534.1, 1784.1);
+INSERT INTO users VALUES (100000087109, 900000000000000011, 'player11', NULL, NULL, 1631.5, 1881.5);
+INSERT INTO songs VALUES (1, 'Yawaraka Tank', 'Yawaraka Tank', 2);
+INSERT INTO charts VALUES (1, 3, 84, 1180, 16954.5, 3695.4);
+INSERT INTO charts VALUES (1, 4, 75, 1777, 13750.5, 3869.3);
+INSERT INTO songs VALUES (2, 'Kagekiyo', 'Kagekiyo', 3);
+INSERT INTO charts VALUES (2, 3, 96, 1243, 25137.7, 2757.0);
+INSERT INTO charts VALUES (2, 4, 101, 1790, 23521.6, 5509.6);
+INSERT INTO songs VALUES (3, 'Hataraku Donchan', 'Hataraku Donchan', 4);
+INSERT INTO charts VALUES (3, 3, 115, 1532, 18396.6, 4430.8);
+INSERT INTO charts VALUES (3, 4, 84, 1630, 25047.1, 4519.3);
+INSERT INTO charts VALUES (3, 5, 122, 1854, 18635.6, 4682.8);
+INSERT INTO songs VALUES (4, 'Ryuusei', 'Ryuusei', 5);
+INSERT INTO charts VALUES (4, 3, 83, 1192, 21050.9, 4297.1);
+INSERT INTO charts VALUES (4, 4, 69, 1773, 21371.0, 3503.4);
+INSERT INTO songs VALUES (5, 'Senbonzakura', 'Senbonzakura', 6);
+INSERT INTO charts VALUES (5, 3, 132, 1266, 14405.6, 4082.3);
+INSERT INTO charts VALUES (5, 4, 86, 1729, 23537.2, 2468.0);
+INSERT INTO songs VALUES (6, 'Monochrome', 'Monochrome', 7);
+INSERT INTO charts VALUES (6, 3, 135, 1541, 25755.0, 5726.7);
+INSERT INTO charts VALUES (6, 4, 120, 1472, 20401.7, 5696.6);
+INSERT INTO charts VALUES (6, 5, 96, 1787, 25874.0, 2982.0);
+INSERT INTO songs VALUES (7, 'Kimi no Shiranai Monogatari', 'Kimi no Shiranai Monogatari', 8);
+INSERT INTO charts VALUES (7, 3, 119, 1537, 28339.7, 3815.8);
+INSERT INTO charts VALUES (7, 4, 62, 1541, 24508.4, 2542.3);
+INSERT INTO songs VALUES (8, 'Gurenge', 'Gurenge', 1);
+INSERT INTO charts VALUES (8, 3, 71, 1522, 22476.5, 5364.6);
+INSERT INTO charts VALUES (8, 4, 82, 1809, 13864.4, 5825.4);
+INSERT INTO songs VALUES (9, 'Mekadesu', 'Mekadesu', 2);
+INSERT INTO charts VALUES (9, 3, 89, 1172, 25297.0, 3809.3);
+INSERT INTO charts VALUES (9, 4, 98, 1748, 17779.9, 3848.6);
+INSERT INTO charts VALUES (9, 5, 63, 1605, 16471.4, 5947.3);
+INSERT INTO songs VALUES (10, 'Rotter Tarmination', 'Rotter Tarmination', 3);
+INSERT INTO charts VALUES (10, 3, 129, 1301, 16645.8, 4187.2);
+INSERT INTO charts VALUES (10, 4, 96, 1517, 14145.7, 3354.8);
+INSERT INTO songs VALUES (11, 'Ooeyama', 'Ooeyama', 4);
+INSERT INTO charts VALUES (11, 3, 101, 1174, 27562.5, 3018.8);
+INSERT INTO charts VALUES (11, 4, 75, 1635, 19573.9, 4529.3);
+INSERT INTO songs VALUES (12, 'Hoshikuzu Strike', 'Hoshikuzu Strike', 5);
+INSERT INTO charts VALUES (12, 3, 137, 1309, 26529.6, 5685.0);
+INSERT INTO charts VALUES (12, 4, 134, 1599, 28577.1, 3229.5);
+INSERT INTO charts VALUES (12, 5, 140, 1684, 27099.5, 2044.7);
+INSERT INTO songs VALUES (13, 'Nijiiro Symphony', 'Nijiiro Symphony', 6);
+INSERT INTO charts VALUES (13, 3, 123, 1433, 23200.7, 2297.4);
+INSERT INTO charts VALUES (13, 4, 84, 1652, 27101.7, 4132.4);
+INSERT INTO songs VALUES (14, 'Donder Fever', 'Donder Fever', 7);
+INSERT INTO charts VALUES (14, 3, 128, 1236, 17828.4, 2323.7);
+INSERT INTO charts VALUES (14, 4, 88, 1502, 15498.4, 2033.6);
+INSERT INTO songs VALUES (15, 'Blue Rose Ruin', 'Blue Rose Ruin', 8);
+INSERT INTO charts VALUES (15, 3, 73, 1186, 21331.3, 5069.7);
+INSERT INTO charts VALUES (15, 4, 89, 1634, 18053.8, 5279.0);
+INSERT INTO charts VALUES (15, 5, 135, 1959, 27080.0, 4111.9);
+INSERT INTO songs VALUES (16, 'Lemon', 'Lemon', 1);
+INSERT INTO charts VALUES (16, 3, 135, 1387, 12302.5, 4750.5);
+INSERT INTO charts VALUES (16, 4, 115, 1614, 29492.3, 5057.7);
+INSERT INTO songs VALUES (17, 'Koi', 'Koi', 2);
+INSERT INTO charts VALUES (17, 3, 99, 1465, 12545.9, 3366.8);
+INSERT INTO charts VALUES (17, 4, 60, 1761, 29029.0, 5962.5);
+INSERT INTO songs VALUES (18, 'Uchiage Hanabi', 'Uchiage Hanabi', 3);
+INSERT INTO charts VALUES (18, 3, 110, 1430, 12398.3, 4448.8);
+INSERT INTO charts VALUES (18, 4, 139, 1792, 15960.7, 4640.1);
+INSERT INTO charts VALUES (18, 5, 140, 1641, 17986.7, 5486.3);
+INSERT INTO songs VALUES (19, 'Night of Knights', 'Night of Knights', 4);
+INSERT INTO charts VALUES (19, 3, 75, 1309, 21111.1, 3379.6);
+INSERT INTO charts VALUES (19, 4, 103, 1594, 19023.2, 2253.1);
+INSERT INTO songs VALUES (20, 'Xa', 'Xa', 5);
+INSERT INTO charts VALUES (20, 3, 68, 1521, 27943.7, 2767.0);
+INSERT INTO charts VALUES (20, 4, 123, 1591, 27688.5, 5528.9);
+INSERT INTO songs VALUES (21, 'Hibike! Taiko no Tatsujin', 'Hibike! Taiko no Tatsujin', 6);
+INSERT INTO charts VALUES (21, 3, 82, 1469, 26140.0, 3375.7);
+INSERT INTO charts VALUES (21, 4, 76, 1717, 14030.1, 3217.3);
+INSERT INTO charts VALUES (21, 5, 123, 1781, 29973.8, 3596.8);
+INSERT INTO songs VALUES (22, 'Saitama 2000', 'Saitama 2000', 7);
+INSERT INTO charts VALUES (22, 3, 135, 1321, 27360.8, 5162.8);
+INSERT INTO charts VALUES (22, 4, 120, 1479, 28716.8, 3151.5);
+INSERT INTO songs VALUES (23, 'Kanade', 'Kanade', 8);
+INSERT INTO charts VALUES (23, 3, 63, 1186, 16989.2, 4068.6);
+INSERT INTO charts VALUES (23, 4, 129, 1536, 19660.2, 2692.7);
+INSERT INTO songs VALUES (24, 'Doshaburi', 'Doshaburi', 1);
+INSERT INTO charts VALUES (24, 3, 127, 1433, 28377.0, 3664.1);
+INSERT INTO charts VALUES (24, 4, 72, 1550, 22223.9, 3553.2);
+INSERT INTO charts VALUES (24, 5, 101, 1803, 14746.6, 2332.1);
+INSERT INTO songs VALUES (25, 'Ketsu no Ana', 'Ketsu no Ana', 2);
+INSERT INTO charts VALUES (25, 3, 138, 1321, 22301.3, 2869.5);
+INSERT INTO charts VALUES (25, 4, 130, 1611, 19173.7, 3953.3);
+INSERT INTO songs VALUES (26, 'Ninja Heart', 'Ninja Heart', 3);
+INSERT INTO charts VALUES (26, 3, 63, 1520, 13832.3, 4017.7);
+INSERT INTO charts VALUES (26, 4, 117, 1547, 12138.1, 4484.5);
+INSERT INTO songs VALUES (27, 'Zenryoku Shounen', 'Zenryoku Shounen', 4);
+INSERT INTO charts VALUES (27, 3, 95, 1170, 22496.0, 2245.5);
+INSERT INTO charts VALUES (27, 4, 119, 1741, 13635.5, 4780.1);
+INSERT INTO charts VALUES (27, 5, 99, 1613, 22704.0, 5854.6);
+INSERT INTO songs VALUES (28, 'Taiko Drum Monster', 'Taiko Drum Monster', 5);
+INSERT INTO charts VALUES (28, 3, 110, 1410, 25694.6, 3949.6);
+INSERT INTO charts VALUES (28, 4, 136, 1654, 25891.4, 3077.8);
+INSERT INTO songs VALUES (29, 'Tank Top', 'Tank Top', 6);
+INSERT INTO charts VALUES (29, 3, 79, 1293, 12669.6, 4627.4);
+INSERT INTO charts VALUES (29, 4, 95, 1708, 14154.6, 3536.1);
+INSERT INTO songs VALUES (30, 'Lightning Strike', 'Lightning Strike', 7);
+INSERT INTO charts VALUES (30, 3, 137, 1369, 21503.0, 3044.8);
+INSERT INTO charts VALUES (30, 4, 90, 1734, 24193.3, 2211.9);
+INSERT INTO charts VALUES (30, 5, 111, 1973, 25220.1, 3049.8);
+INSERT INTO top_plays VALUES (100000000000, 1, 3, 988997, 646, 10, 1, 227, 34, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 1, 4, 967605, 713, 21, 3, 452, 36, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 2, 3, 978209, 654, 17, 3, 368, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 3, 3, 929108, 650, 37, 4, 180, 39, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 3, 5, 900500, 799, 46, 6, 268, 31, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 4, 3, 984458, 697, 9, 2, 356, 28, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 4, 4, 961572, 769, 16, 3, 348, 18, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 5, 3, 944954, 694, 28, 3, 333, 31, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 5, 4, 940078, 773, 27, 5, 502, 26, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 6, 3, 933133, 702, 33, 7, 407, 34, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 6, 4, 954074, 793, 24, 5, 324, 26, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 6, 5, 938810, 865, 33, 4, 728, 39, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 7, 3, 891979, 705, 47, 7, 540, 8, 5, 1);
+INSERT INTO top_plays VALUES (100000000000, 8, 4, 942770, 820, 30, 6, 527, 21, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 9, 3, 995716, 790, 2, 1, 400, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 9, 4, 927483, 830, 38, 5, 423, 40, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 9, 5, 1000000, 947, 5, 1, 638, 2, 8, 1);
+INSERT INTO top_plays VALUES (100000000000, 10, 3, 941277, 779, 26, 5, 605, 30, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 10, 4, 966960, 872, 14, 4, 561, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 11, 3, 954846, 805, 18, 4, 462, 17, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 11, 4, 952514, 880, 21, 6, 517, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 12, 5, 916569, 957, 40, 7, 373, 11, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 13, 4, 955882, 918, 18, 5, 825, 35, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 14, 3, 930228, 834, 37, 7, 295, 1, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 14, 4, 973632, 937, 19, 2, 415, 26, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 15, 4, 946936, 948, 23, 4, 329, 0, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 15, 5, 861665, 984, 60, 11, 541, 24, 5, 1);
+INSERT INTO top_plays VALUES (100000000000, 16, 4, 971181, 976, 14, 2, 411, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 17, 3, 929300, 895, 30, 4, 279, 30, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 17, 4, 924623, 971, 33, 5, 296, 5, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 18, 4, 892186, 975, 44, 7, 644, 19, 5, 1);
+INSERT INTO top_plays VALUES (100000000000, 18, 5, 925938, 1069, 30, 7, 230, 11, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 19, 3, 963361, 943, 17, 3, 781, 23, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 19, 4, 929937, 1008, 29, 6, 554, 37, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 20, 3, 978859, 963, 13, 4, 265, 6, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 20, 4, 913696, 1008, 44, 8, 645, 29, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 21, 4, 913697, 1030, 39, 8, 844, 9, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 21, 5, 967329, 1133, 22, 2, 837, 8, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 23, 3, 969567, 1009, 19, 3, 396, 11, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 23, 4, 944485, 1080, 28, 3, 667, 36, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 24, 3, 915982, 998, 43, 7, 555, 30, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 24, 4, 994369, 1116, 10, 2, 582, 20, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 25, 3, 972137, 1042, 21, 2, 934, 33, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 26, 3, 970829, 1060, 20, 2, 266, 24, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 27, 3, 950308, 1068, 27, 4, 556, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 27, 5, 931507, 1218, 35, 6, 635, 30, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 28, 3, 945863, 1081, 30, 5, 809, 13, 6, 1);
+INSERT INTO top_plays VALUES (100000000000, 29, 3, 989806, 1124, 6, 3, 789, 16, 7, 1);
+INSERT INTO top_plays VALUES (100000000000, 30, 4, 977323, 1210, 18, 2, 797, 15, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 1, 3, 966369, 633, 21, 3, 437, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 2, 4, 952164, 724, 24, 6, 170, 19, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 3, 3, 936241, 654, 31, 6, 168, 9, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 3, 4, 886523, 709, 54, 8, 385, 28, 5, 1);
+INSERT INTO top_plays VALUES (100000007919, 3, 5, 945272, 818, 27, 6, 261, 3, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 4, 3, 970468, 691, 13, 4, 524, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 4, 4, 986271, 771, 14, 3, 510, 14, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 5, 4, 901680, 753, 45, 7, 580, 11, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 6, 3, 926546, 699, 36, 7, 284, 9, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 6, 4, 975066, 805, 15, 2, 649, 30, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 6, 5, 884368, 844, 49, 9, 341, 15, 5, 1);
+INSERT INTO top_plays VALUES (100000007919, 7, 3, 955429, 731, 23, 5, 674, 22, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 8, 3, 977705, 764, 10, 2, 616, 3, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 8, 4, 951900, 828, 25, 3, 562, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 9, 4, 966636, 853, 16, 4, 230, 16, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 9, 5, 971969, 932, 19, 2, 668, 0, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 10, 3, 976113, 796, 10, 4, 659, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 10, 4, 954026, 858, 28, 4, 679, 30, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 12, 3, 934979, 812, 26, 6, 703, 14, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 12, 5, 919632, 964, 33, 7, 705, 3, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 13, 3, 945738, 835, 23, 3, 353, 29, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 13, 4, 912737, 896, 38, 7, 230, 33, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 14, 3, 972268, 858, 16, 4, 248, 10, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 14, 4, 980078, 939, 17, 2, 479, 21, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 15, 3, 972045, 881, 12, 2, 583, 14, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 15, 4, 961694, 953, 20, 2, 552, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 16, 3, 918217, 866, 39, 7, 246, 12, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 16, 4, 983154, 975, 15, 2, 444, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 17, 3, 946865, 900, 23, 6, 325, 24, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 17, 4, 1000000, 1003, 4, 2, 386, 9, 8, 1);
+INSERT INTO top_plays VALUES (100000007919, 18, 3, 942106, 914, 28, 4, 208, 0, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 18, 5, 897110, 1054, 45, 7, 784, 6, 5, 1);
+INSERT INTO top_plays VALUES (100000007919, 19, 3, 984612, 950, 11, 2, 795, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 20, 3, 935680, 942, 32, 6, 526, 40, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 20, 4, 944132, 1032, 22, 6, 930, 35, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 21, 4, 957647, 1054, 18, 5, 548, 36, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 22, 3, 930721, 970, 37, 7, 385, 2, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 22, 4, 953011, 1066, 23, 5, 493, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 23, 4, 977785, 1092, 17, 2, 976, 36, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 24, 3, 983298, 1039, 6, 3, 449, 25, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 24, 4, 1000000, 1124, 2, 2, 714, 33, 8, 1);
+INSERT INTO top_plays VALUES (100000007919, 24, 5, 935981, 1170, 33, 5, 821, 13, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 25, 4, 968223, 1128, 14, 3, 454, 7, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 26, 3, 976211, 1061, 18, 3, 570, 8, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 26, 4, 974242, 1142, 18, 2, 436, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 27, 4, 938549, 1139, 34, 6, 575, 12, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 28, 3, 927532, 1078, 32, 6, 872, 6, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 28, 4, 914827, 1148, 43, 5, 815, 9, 6, 1);
+INSERT INTO top_plays VALUES (100000007919, 29, 4, 961344, 1187, 22, 4, 902, 27, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 30, 3, 956244, 1120, 27, 3, 273, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 30, 4, 968334, 1209, 19, 2, 522, 13, 7, 1);
+INSERT INTO top_plays VALUES (100000007919, 30, 5, 887510, 1254, 49, 7, 1051, 40, 5, 1);
+INSERT INTO top_plays VALUES (100000015838, 1, 3, 1000000, 652, 5, 0, 657, 30, 8, 2);
+INSERT INTO top_plays VALUES (100000015838, 2, 3, 973613, 655, 16, 3, 174, 11, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 2, 4, 961015, 729, 22, 3, 661, 23, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 3, 4, 912545, 727, 36, 8, 655, 21, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 3, 5, 943985, 819, 26, 6, 583, 16, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 4, 3, 977520, 695, 11, 2, 517, 32, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 4, 4, 977266, 771, 14, 3, 283, 7, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 5, 3, 965068, 706, 16, 3, 387, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 5, 4, 947372, 780, 21, 4, 342, 16, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 6, 3, 914701, 700, 36, 6, 517, 32, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 6, 4, 938974, 782, 34, 6, 683, 2, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 6, 5, 976624, 883, 17, 2, 683, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 7, 3, 933792, 724, 29, 6, 471, 9, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 7, 4, 993584, 832, 6, 1, 512, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 9, 3, 1000000, 785, 8, 0, 793, 39, 8, 2);
+INSERT INTO top_plays VALUES (100000015838, 9, 4, 931985, 837, 29, 7, 465, 34, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 9, 5, 1000000, 946, 6, 1, 774, 39, 8, 1);
+INSERT INTO top_plays VALUES (100000015838, 10, 3, 951615, 785, 20, 5, 335, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 10, 4, 979558, 873, 14, 3, 606, 23, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 11, 4, 988164, 897, 7, 3, 247, 8, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 12, 5, 946315, 972, 28, 4, 206, 34, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 13, 3, 977135, 842, 16, 3, 596, 14, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 13, 4, 914507, 893, 41, 7, 201, 8, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 14, 3, 965507, 854, 20, 4, 237, 10, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 14, 4, 968942, 934, 20, 4, 431, 21, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 15, 3, 995851, 884, 9, 2, 416, 33, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 15, 4, 965820, 949, 22, 4, 645, 31, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 16, 3, 920778, 866, 40, 6, 723, 10, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 16, 4, 994215, 978, 11, 3, 590, 15, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 17, 4, 974839, 990, 16, 3, 823, 23, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 18, 3, 955739, 926, 17, 3, 372, 0, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 18, 4, 923879, 983, 36, 7, 853, 35, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 18, 5, 954136, 1078, 23, 5, 911, 19, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 19, 3, 976816, 951, 10, 2, 389, 20, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 19, 4, 978384, 1028, 11, 4, 378, 35, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 20, 3, 1000000, 979, 1, 0, 980, 3, 8, 2);
+INSERT INTO top_plays VALUES (100000015838, 20, 4, 940931, 1022, 32, 6, 943, 13, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 21, 3, 951953, 966, 26, 5, 751, 11, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 21, 5, 870315, 1088, 59, 10, 706, 37, 5, 1);
+INSERT INTO top_plays VALUES (100000015838, 22, 4, 968705, 1069, 22, 3, 384, 7, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 23, 4, 964441, 1092, 15, 4, 316, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 24, 4, 973627, 1110, 16, 2, 269, 20, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 24, 5, 951250, 1178, 24, 6, 760, 36, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 25, 4, 949251, 1120, 21, 4, 506, 21, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 26, 3, 960278, 1054, 25, 3, 223, 28, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 26, 4, 962678, 1141, 18, 3, 802, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 27, 3, 972728, 1081, 15, 3, 947, 7, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 27, 4, 933231, 1139, 34, 6, 659, 16, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 28, 3, 946223, 1085, 28, 3, 376, 20, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 28, 4, 947620, 1162, 30, 4, 608, 13, 6, 1);
+INSERT INTO top_plays VALUES (100000015838, 29, 3, 981812, 1119, 12, 2, 767, 13, 7, 1);
+INSERT INTO top_plays VALUES (100000015838, 30, 4, 930143, 1196, 28, 6, 370, 4, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 1, 3, 997663, 653, 2, 2, 491, 14, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 1, 4, 962758, 717, 16, 4, 543, 28, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 2, 3, 1000000, 664, 8, 2, 559, 5, 8, 1);
+INSERT INTO top_plays VALUES (100000023757, 2, 4, 954607, 724, 25, 5, 516, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 3, 3, 988243, 677, 12, 2, 331, 7, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 3, 5, 942198, 816, 31, 4, 183, 21, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 4, 3, 992062, 703, 3, 2, 350, 14, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 4, 4, 967751, 766, 19, 3, 225, 23, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 5, 3, 961230, 705, 18, 2, 399, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 5, 4, 1000000, 797, 7, 1, 574, 20, 8, 1);
+INSERT INTO top_plays VALUES (100000023757, 6, 3, 951579, 715, 22, 5, 322, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 6, 4, 985140, 806, 14, 2, 708, 40, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 6, 5, 965963, 878, 22, 2, 556, 24, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 7, 3, 896264, 704, 48, 7, 401, 8, 5, 1);
+INSERT INTO top_plays VALUES (100000023757, 7, 4, 918486, 795, 36, 8, 604, 25, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 8, 3, 970695, 758, 16, 2, 174, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 8, 4, 961708, 835, 18, 3, 705, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 9, 3, 1000000, 791, 1, 1, 613, 16, 8, 1);
+INSERT INTO top_plays VALUES (100000023757, 9, 4, 973709, 858, 13, 2, 601, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 9, 5, 977298, 941, 10, 2, 294, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 10, 3, 966042, 788, 20, 2, 516, 13, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 11, 3, 1000000, 822, 4, 1, 381, 22, 8, 1);
+INSERT INTO top_plays VALUES (100000023757, 12, 3, 962309, 824, 16, 4, 594, 0, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 12, 5, 920243, 960, 37, 7, 435, 29, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 13, 3, 921218, 817, 38, 6, 390, 22, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 13, 4, 987142, 929, 9, 3, 500, 18, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 14, 3, 986028, 871, 5, 2, 274, 20, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 14, 4, 981369, 943, 13, 2, 713, 25, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 15, 4, 991713, 968, 6, 1, 423, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 15, 5, 937177, 1022, 29, 4, 948, 12, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 16, 3, 959546, 885, 22, 5, 329, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 16, 4, 1000000, 992, 0, 0, 992, 5, 8, 3);
+INSERT INTO top_plays VALUES (100000023757, 17, 3, 949892, 903, 21, 5, 804, 38, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 17, 4, 991963, 999, 7, 3, 378, 2, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 18, 3, 962214, 919, 22, 5, 310, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 18, 4, 942432, 990, 32, 4, 232, 1, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 18, 5, 952379, 1078, 24, 4, 587, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 20, 3, 981806, 967, 12, 1, 591, 34, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 20, 4, 1000000, 1055, 5, 0, 1060, 26, 8, 2);
+INSERT INTO top_plays VALUES (100000023757, 21, 3, 981027, 981, 13, 3, 533, 21, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 21, 5, 912927, 1115, 36, 6, 549, 11, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 22, 3, 961426, 994, 16, 4, 618, 26, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 22, 4, 921555, 1053, 34, 7, 384, 22, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 23, 3, 998956, 1028, 2, 1, 662, 13, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 23, 4, 975151, 1094, 14, 3, 695, 34, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 24, 5, 940016, 1172, 31, 5, 537, 15, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 25, 3, 976398, 1049, 12, 4, 224, 31, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 26, 3, 974654, 1064, 16, 2, 437, 25, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 27, 3, 987948, 1085, 12, 2, 545, 13, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 27, 5, 958542, 1234, 22, 3, 769, 15, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 28, 3, 947447, 1087, 26, 3, 366, 9, 6, 1);
+INSERT INTO top_plays VALUES (100000023757, 28, 4, 971434, 1173, 20, 3, 577, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 29, 3, 978575, 1119, 10, 4, 787, 14, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 29, 4, 955345, 1188, 22, 3, 923, 7, 7, 1);
+INSERT INTO top_plays VALUES (100000023757, 30, 5, 945153, 1274, 30, 6, 759, 29, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 1, 4, 971030, 714, 19, 4, 581, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 3, 3, 960604, 671, 17, 3, 478, 10, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 3, 4, 978414, 752, 17, 2, 168, 18, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 3, 5, 919863, 804, 40, 7, 695, 18, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 4, 4, 943161, 754, 28, 6, 292, 24, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 5, 3, 992741, 711, 11, 3, 479, 35, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 5, 4, 928354, 763, 36, 6, 193, 27, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 6, 3, 924941, 704, 33, 5, 329, 27, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 6, 4, 994457, 816, 6, 0, 822, 35, 7, 2);
+INSERT INTO top_plays VALUES (100000031676, 7, 3, 971693, 741, 16, 2, 245, 11, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 9, 3, 992824, 788, 5, 0, 793, 1, 7, 2);
+INSERT INTO top_plays VALUES (100000031676, 10, 3, 972386, 793, 14, 3, 694, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 11, 3, 1000000, 824, 2, 1, 621, 9, 8, 1);
+INSERT INTO top_plays VALUES (100000031676, 11, 4, 993504, 898, 7, 2, 564, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 12, 4, 988820, 910, 12, 2, 690, 28, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 13, 3, 971820, 844, 13, 4, 510, 24, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 13, 4, 964887, 923, 16, 2, 773, 34, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 14, 3, 1000000, 872, 5, 1, 569, 6, 8, 1);
+INSERT INTO top_plays VALUES (100000031676, 14, 4, 992797, 954, 3, 1, 713, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 15, 3, 1000000, 885, 8, 2, 291, 19, 8, 1);
+INSERT INTO top_plays VALUES (100000031676, 15, 4, 958642, 950, 21, 4, 350, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 15, 5, 926518, 1017, 33, 5, 214, 8, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 16, 3, 964406, 888, 22, 2, 808, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 16, 4, 938432, 953, 33, 6, 393, 1, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 17, 4, 950731, 984, 19, 6, 228, 13, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 18, 3, 956345, 923, 20, 3, 756, 25, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 18, 5, 952434, 1080, 21, 5, 865, 22, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 19, 3, 1000000, 954, 7, 2, 625, 8, 8, 1);
+INSERT INTO top_plays VALUES (100000031676, 20, 4, 995741, 1055, 4, 1, 406, 16, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 21, 4, 987452, 1062, 14, 1, 562, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 21, 5, 936332, 1124, 27, 6, 432, 30, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 22, 4, 966499, 1069, 21, 4, 868, 3, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 23, 3, 984623, 1015, 15, 1, 737, 40, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 23, 4, 997122, 1103, 7, 1, 458, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 24, 3, 1000000, 1039, 9, 0, 1048, 13, 8, 2);
+INSERT INTO top_plays VALUES (100000031676, 25, 4, 979286, 1136, 8, 1, 638, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 26, 3, 991332, 1074, 7, 1, 903, 6, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 26, 4, 973406, 1145, 15, 2, 826, 33, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 27, 3, 955980, 1071, 24, 4, 330, 31, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 27, 4, 948960, 1149, 25, 5, 378, 22, 6, 1);
+INSERT INTO top_plays VALUES (100000031676, 27, 5, 995037, 1254, 3, 2, 303, 7, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 28, 3, 960970, 1094, 20, 2, 426, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 28, 4, 965973, 1175, 17, 4, 579, 2, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 29, 3, 1000000, 1123, 9, 1, 954, 39, 8, 1);
+INSERT INTO top_plays VALUES (100000031676, 29, 4, 978391, 1201, 10, 2, 443, 25, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 30, 4, 974317, 1210, 18, 2, 271, 11, 7, 1);
+INSERT INTO top_plays VALUES (100000031676, 30, 5, 953111, 1279, 26, 5, 1030, 40, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 2, 3, 985148, 658, 15, 1, 593, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 2, 4, 984365, 745, 6, 3, 574, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 3, 3, 961686, 664, 24, 3, 213, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 3, 4, 976820, 749, 19, 3, 277, 8, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 3, 5, 946535, 816, 30, 5, 550, 12, 6, 1);
+INSERT INTO top_plays VALUES (100000039595, 4, 3, 1000000, 702, 4, 2, 616, 7, 8, 1);
+INSERT INTO top_plays VALUES (100000039595, 4, 4, 989927, 778, 9, 1, 605, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 5, 3, 1000000, 723, 1, 1, 546, 36, 8, 1);
+INSERT INTO top_plays VALUES (100000039595, 5, 4, 977430, 793, 10, 2, 659, 24, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 6, 3, 939060, 703, 33, 6, 254, 30, 6, 1);
+INSERT INTO top_plays VALUES (100000039595, 6, 4, 1000000, 819, 3, 0, 822, 20, 8, 2);
+INSERT INTO top_plays VALUES (100000039595, 6, 5, 979496, 890, 10, 2, 192, 34, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 7, 3, 1000000, 756, 3, 0, 759, 15, 8, 2);
+INSERT INTO top_plays VALUES (100000039595, 8, 3, 983868, 762, 11, 3, 639, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 8, 4, 975022, 842, 11, 3, 337, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 9, 4, 946555, 842, 26, 5, 296, 40, 6, 1);
+INSERT INTO top_plays VALUES (100000039595, 9, 5, 994336, 941, 10, 2, 459, 19, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 10, 3, 1000000, 799, 9, 2, 479, 0, 8, 1);
+INSERT INTO top_plays VALUES (100000039595, 10, 4, 984523, 877, 10, 3, 280, 24, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 11, 4, 995078, 897, 9, 1, 719, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 12, 5, 991089, 1000, 4, 0, 1004, 28, 7, 2);
+INSERT INTO top_plays VALUES (100000039595, 13, 4, 958926, 911, 26, 4, 524, 25, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 14, 4, 982350, 950, 7, 1, 391, 0, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 15, 3, 1000000, 890, 5, 0, 895, 29, 8, 2);
+INSERT INTO top_plays VALUES (100000039595, 15, 5, 976994, 1034, 19, 2, 791, 2, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 16, 4, 1000000, 982, 8, 2, 735, 40, 8, 1);
+INSERT INTO top_plays VALUES (100000039595, 17, 3, 982917, 914, 13, 2, 391, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 18, 3, 982818, 930, 13, 3, 735, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 18, 5, 955565, 1080, 23, 3, 514, 30, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 19, 3, 1000000, 959, 3, 1, 641, 25, 8, 1);
+INSERT INTO top_plays VALUES (100000039595, 20, 3, 1000000, 974, 5, 1, 479, 29, 8, 1);
+INSERT INTO top_plays VALUES (100000039595, 20, 4, 1000000, 1057, 3, 0, 1060, 26, 8, 2);
+INSERT INTO top_plays VALUES (100000039595, 21, 5, 947703, 1131, 22, 4, 939, 18, 6, 1);
+INSERT INTO top_plays VALUES (100000039595, 22, 4, 991121, 1090, 4, 0, 1094, 32, 7, 2);
+INSERT INTO top_plays VALUES (100000039595, 23, 3, 984604, 1021, 7, 3, 238, 16, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 23, 4, 946679, 1086, 21, 4, 563, 39, 6, 1);
+INSERT INTO top_plays VALUES (100000039595, 24, 3, 969761, 1024, 21, 3, 595, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 24, 5, 974123, 1189, 15, 4, 372, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 25, 3, 982333, 1048, 14, 3, 811, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 25, 4, 979349, 1132, 11, 2, 274, 13, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 26, 3, 991727, 1073, 7, 2, 254, 30, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 26, 4, 985881, 1149, 11, 2, 725, 36, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 27, 3, 1000000, 1090, 9, 0, 1099, 9, 8, 2);
+INSERT INTO top_plays VALUES (100000039595, 27, 4, 969123, 1155, 21, 3, 444, 28, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 27, 5, 964756, 1238, 18, 3, 1104, 26, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 28, 3, 997465, 1110, 4, 2, 864, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 29, 4, 993718, 1200, 11, 2, 360, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 30, 4, 968892, 1208, 19, 3, 546, 20, 7, 1);
+INSERT INTO top_plays VALUES (100000039595, 30, 5, 909406, 1258, 45, 7, 957, 6, 6, 1);
+INSERT INTO top_plays VALUES (100000047514, 1, 3, 1000000, 648, 9, 0, 657, 31, 8, 2);
+INSERT INTO top_plays VALUES (100000047514, 1, 4, 978355, 727, 8, 2, 495, 18, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 2, 3, 1000000, 665, 7, 2, 326, 6, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 2, 4, 940169, 717, 33, 4, 258, 12, 6, 1);
+INSERT INTO top_plays VALUES (100000047514, 3, 4, 1000000, 763, 7, 1, 387, 35, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 3, 5, 942144, 817, 29, 5, 750, 0, 6, 1);
+INSERT INTO top_plays VALUES (100000047514, 4, 3, 999034, 703, 3, 2, 479, 35, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 5, 3, 993549, 717, 7, 1, 573, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 5, 4, 949837, 776, 24, 5, 250, 35, 6, 1);
+INSERT INTO top_plays VALUES (100000047514, 7, 3, 1000000, 754, 3, 2, 518, 17, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 8, 3, 989532, 771, 4, 1, 357, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 8, 4, 968243, 835, 19, 2, 488, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 9, 3, 1000000, 784, 9, 0, 793, 38, 8, 2);
+INSERT INTO top_plays VALUES (100000047514, 10, 3, 1000000, 805, 5, 0, 810, 1, 8, 2);
+INSERT INTO top_plays VALUES (100000047514, 12, 3, 968508, 827, 13, 4, 577, 17, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 12, 4, 980167, 909, 11, 4, 788, 18, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 12, 5, 1000000, 1001, 1, 2, 235, 8, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 13, 3, 984880, 853, 6, 2, 559, 17, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 14, 3, 961775, 850, 23, 5, 669, 16, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 14, 4, 999007, 956, 2, 0, 958, 5, 7, 2);
+INSERT INTO top_plays VALUES (100000047514, 15, 3, 1000000, 888, 7, 0, 895, 1, 8, 2);
+INSERT INTO top_plays VALUES (100000047514, 15, 4, 966968, 952, 20, 3, 607, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 15, 5, 958613, 1032, 19, 4, 322, 20, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 17, 3, 981207, 917, 10, 2, 794, 22, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 18, 3, 983316, 935, 10, 1, 683, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 18, 4, 967669, 1010, 14, 2, 504, 22, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 18, 5, 980245, 1096, 9, 1, 784, 31, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 19, 3, 990850, 949, 13, 1, 484, 10, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 19, 4, 968156, 1026, 15, 2, 726, 13, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 20, 4, 983510, 1050, 8, 2, 894, 35, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 21, 3, 1000000, 993, 2, 2, 550, 8, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 21, 5, 1000000, 1156, 0, 1, 942, 10, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 22, 4, 1000000, 1088, 4, 2, 879, 36, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 23, 3, 1000000, 1027, 2, 2, 583, 18, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 23, 4, 1000000, 1108, 2, 1, 257, 36, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 24, 4, 995812, 1119, 8, 1, 958, 18, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 25, 3, 1000000, 1060, 5, 0, 1065, 22, 8, 2);
+INSERT INTO top_plays VALUES (100000047514, 26, 3, 977257, 1065, 14, 3, 640, 33, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 26, 4, 1000000, 1154, 6, 2, 310, 28, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 27, 4, 966251, 1155, 22, 2, 989, 10, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 27, 5, 972276, 1245, 11, 3, 586, 18, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 28, 3, 1000000, 1108, 6, 2, 965, 30, 8, 1);
+INSERT INTO top_plays VALUES (100000047514, 28, 4, 941991, 1166, 26, 4, 358, 36, 6, 1);
+INSERT INTO top_plays VALUES (100000047514, 29, 3, 989422, 1117, 13, 3, 528, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 30, 4, 994994, 1227, 2, 1, 785, 36, 7, 1);
+INSERT INTO top_plays VALUES (100000047514, 30, 5, 958697, 1282, 24, 4, 417, 40, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 1, 3, 1000000, 650, 5, 2, 206, 14, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 2, 4, 988263, 742, 9, 3, 573, 27, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 3, 3, 998846, 686, 4, 1, 348, 31, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 3, 4, 1000000, 764, 7, 0, 771, 39, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 3, 5, 979783, 831, 17, 3, 517, 11, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 4, 3, 1000000, 700, 6, 2, 209, 0, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 5, 3, 994711, 717, 7, 1, 375, 23, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 5, 4, 1000000, 803, 2, 0, 805, 5, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 6, 3, 1000000, 738, 3, 1, 397, 23, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 6, 4, 992460, 815, 6, 1, 459, 27, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 6, 5, 1000000, 894, 8, 0, 902, 13, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 7, 4, 976119, 821, 16, 2, 279, 2, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 8, 4, 967763, 835, 19, 2, 720, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 9, 4, 969987, 856, 13, 4, 661, 2, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 11, 3, 1000000, 819, 8, 0, 827, 28, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 11, 4, 961782, 887, 17, 3, 244, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 12, 3, 963515, 825, 16, 3, 465, 27, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 13, 3, 982451, 846, 12, 3, 659, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 14, 3, 1000000, 876, 2, 0, 878, 3, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 15, 4, 994572, 969, 4, 2, 614, 4, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 15, 5, 940999, 1021, 29, 5, 579, 16, 6, 1);
+INSERT INTO top_plays VALUES (100000055433, 16, 3, 1000000, 907, 3, 2, 581, 5, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 17, 3, 977987, 914, 12, 3, 670, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 17, 4, 1000000, 1006, 1, 2, 509, 1, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 18, 4, 977045, 1013, 11, 2, 283, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 19, 3, 998269, 955, 6, 2, 664, 1, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 19, 4, 1000000, 1037, 5, 1, 227, 31, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 21, 4, 991325, 1068, 9, 0, 1077, 34, 7, 2);
+INSERT INTO top_plays VALUES (100000055433, 22, 4, 1000000, 1086, 6, 2, 837, 17, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 23, 3, 1000000, 1030, 1, 0, 1031, 28, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 23, 4, 984686, 1101, 8, 2, 586, 11, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 24, 3, 1000000, 1043, 4, 1, 268, 29, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 24, 4, 986922, 1117, 10, 1, 562, 28, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 24, 5, 986441, 1197, 8, 3, 354, 6, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 25, 3, 1000000, 1059, 5, 1, 589, 3, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 25, 4, 1000000, 1143, 2, 0, 1145, 33, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 26, 3, 971493, 1060, 19, 3, 902, 23, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 27, 3, 1000000, 1093, 6, 0, 1099, 22, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 27, 4, 968553, 1157, 19, 3, 520, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 27, 5, 1000000, 1251, 6, 2, 525, 5, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 28, 4, 1000000, 1191, 5, 0, 1196, 35, 8, 2);
+INSERT INTO top_plays VALUES (100000055433, 29, 4, 1000000, 1209, 3, 1, 519, 13, 8, 1);
+INSERT INTO top_plays VALUES (100000055433, 30, 3, 973160, 1132, 14, 4, 908, 35, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 30, 4, 986465, 1220, 7, 3, 1078, 20, 7, 1);
+INSERT INTO top_plays VALUES (100000055433, 30, 5, 922228, 1266, 39, 5, 487, 14, 6, 1);
+INSERT INTO top_plays VALUES (100000063352, 2, 3, 1000000, 671, 2, 1, 220, 39, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 3, 3, 963425, 666, 23, 2, 293, 0, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 3, 4, 1000000, 771, 0, 0, 771, 3, 8, 3);
+INSERT INTO top_plays VALUES (100000063352, 3, 5, 1000000, 842, 7, 2, 369, 33, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 5, 4, 947807, 781, 21, 3, 254, 24, 6, 1);
+INSERT INTO top_plays VALUES (100000063352, 6, 3, 984073, 733, 7, 2, 522, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 6, 4, 980621, 804, 16, 2, 618, 32, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 6, 5, 972235, 888, 11, 3, 587, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 7, 4, 987788, 827, 11, 1, 280, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 8, 3, 1000000, 766, 9, 1, 374, 38, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 8, 4, 995722, 852, 2, 2, 185, 32, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 9, 5, 1000000, 952, 0, 1, 359, 17, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 10, 3, 1000000, 805, 5, 0, 810, 5, 8, 2);
+INSERT INTO top_plays VALUES (100000063352, 10, 4, 1000000, 887, 2, 1, 647, 11, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 11, 3, 1000000, 818, 7, 2, 408, 6, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 12, 4, 1000000, 916, 8, 0, 924, 6, 8, 2);
+INSERT INTO top_plays VALUES (100000063352, 13, 3, 1000000, 858, 2, 1, 437, 23, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 14, 3, 991904, 865, 12, 1, 255, 37, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 14, 4, 1000000, 949, 8, 1, 205, 17, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 15, 3, 1000000, 892, 3, 0, 895, 26, 8, 2);
+INSERT INTO top_plays VALUES (100000063352, 15, 4, 974630, 961, 11, 3, 723, 24, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 15, 5, 962149, 1036, 15, 4, 325, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 17, 3, 1000000, 926, 2, 1, 717, 7, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 17, 4, 959907, 983, 22, 4, 461, 19, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 18, 4, 948574, 993, 27, 6, 547, 26, 6, 1);
+INSERT INTO top_plays VALUES (100000063352, 18, 5, 987340, 1094, 10, 2, 489, 9, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 19, 3, 1000000, 961, 2, 0, 963, 2, 8, 2);
+INSERT INTO top_plays VALUES (100000063352, 20, 3, 904322, 932, 42, 6, 350, 6, 6, 1);
+INSERT INTO top_plays VALUES (100000063352, 20, 4, 1000000, 1054, 5, 1, 499, 4, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 21, 3, 1000000, 994, 2, 1, 874, 36, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 21, 5, 981224, 1141, 13, 3, 684, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 23, 3, 988037, 1024, 5, 2, 573, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 23, 4, 1000000, 1104, 5, 2, 924, 28, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 24, 3, 976446, 1031, 15, 2, 359, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 24, 4, 992553, 1122, 6, 0, 1128, 33, 7, 2);
+INSERT INTO top_plays VALUES (100000063352, 24, 5, 1000000, 1203, 5, 0, 1208, 21, 8, 2);
+INSERT INTO top_plays VALUES (100000063352, 25, 3, 1000000, 1061, 4, 0, 1065, 27, 8, 2);
+INSERT INTO top_plays VALUES (100000063352, 26, 3, 988714, 1075, 5, 2, 313, 15, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 26, 4, 999961, 1161, 1, 0, 1162, 19, 7, 2);
+INSERT INTO top_plays VALUES (100000063352, 27, 3, 1000000, 1095, 4, 0, 1099, 30, 8, 2);
+INSERT INTO top_plays VALUES (100000063352, 27, 4, 988161, 1165, 12, 2, 373, 8, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 28, 3, 1000000, 1114, 1, 1, 358, 18, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 28, 4, 985436, 1185, 9, 2, 992, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 29, 3, 984763, 1125, 6, 2, 979, 17, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 29, 4, 979101, 1199, 12, 2, 819, 2, 7, 1);
+INSERT INTO top_plays VALUES (100000063352, 30, 3, 1000000, 1140, 8, 2, 772, 38, 8, 1);
+INSERT INTO top_plays VALUES (100000063352, 30, 4, 995522, 1221, 7, 2, 726, 34, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 1, 3, 1000000, 647, 9, 1, 302, 9, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 1, 4, 993087, 733, 2, 2, 493, 40, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 2, 4, 987111, 740, 12, 2, 480, 7, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 3, 3, 963347, 668, 21, 2, 586, 17, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 3, 4, 1000000, 762, 8, 1, 507, 37, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 4, 4, 999589, 781, 7, 0, 788, 36, 7, 2);
+INSERT INTO top_plays VALUES (100000071271, 5, 3, 1000000, 720, 5, 0, 725, 2, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 5, 4, 1000000, 799, 5, 1, 697, 20, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 6, 3, 1000000, 740, 2, 0, 742, 37, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 6, 4, 1000000, 819, 2, 1, 602, 30, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 6, 5, 990044, 887, 13, 2, 811, 19, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 7, 3, 928981, 717, 36, 6, 489, 8, 6, 1);
+INSERT INTO top_plays VALUES (100000071271, 7, 4, 1000000, 837, 2, 0, 839, 11, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 8, 3, 991992, 771, 4, 1, 657, 14, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 9, 4, 998324, 871, 1, 1, 715, 2, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 10, 3, 1000000, 805, 4, 1, 621, 39, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 10, 4, 1000000, 882, 7, 1, 597, 12, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 11, 3, 1000000, 817, 8, 2, 711, 15, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 11, 4, 998701, 902, 4, 1, 682, 27, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 12, 4, 1000000, 916, 7, 1, 259, 0, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 13, 3, 1000000, 855, 5, 1, 406, 39, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 14, 3, 1000000, 876, 1, 1, 416, 40, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 14, 4, 1000000, 950, 8, 0, 958, 9, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 15, 4, 1000000, 967, 6, 2, 725, 1, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 15, 5, 962756, 1034, 19, 2, 471, 6, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 16, 3, 990809, 908, 4, 0, 912, 0, 7, 2);
+INSERT INTO top_plays VALUES (100000071271, 16, 4, 966870, 974, 16, 2, 372, 8, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 18, 4, 966139, 1000, 22, 4, 762, 11, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 19, 3, 992525, 954, 7, 2, 384, 18, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 20, 3, 993097, 972, 6, 2, 512, 25, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 21, 3, 1000000, 989, 6, 2, 676, 13, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 21, 4, 1000000, 1072, 5, 0, 1077, 1, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 22, 4, 1000000, 1093, 0, 1, 277, 39, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 23, 3, 1000000, 1024, 6, 1, 834, 5, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 23, 4, 995188, 1103, 8, 0, 1111, 24, 7, 2);
+INSERT INTO top_plays VALUES (100000071271, 24, 3, 1000000, 1041, 5, 2, 565, 9, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 24, 4, 1000000, 1120, 8, 0, 1128, 17, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 24, 5, 1000000, 1206, 2, 0, 1208, 33, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 25, 3, 1000000, 1061, 2, 2, 608, 21, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 25, 4, 1000000, 1139, 4, 2, 814, 38, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 26, 3, 972935, 1065, 15, 2, 835, 26, 7, 1);
+INSERT INTO top_plays VALUES (100000071271, 26, 4, 1000000, 1157, 5, 0, 1162, 17, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 27, 3, 1000000, 1091, 7, 1, 469, 40, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 27, 4, 1000000, 1170, 9, 0, 1179, 32, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 27, 5, 1000000, 1250, 9, 0, 1259, 37, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 28, 4, 1000000, 1195, 1, 0, 1196, 1, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 29, 3, 1000000, 1130, 1, 2, 737, 4, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 29, 4, 1000000, 1211, 0, 2, 443, 24, 8, 1);
+INSERT INTO top_plays VALUES (100000071271, 30, 3, 1000000, 1141, 9, 0, 1150, 21, 8, 2);
+INSERT INTO top_plays VALUES (100000071271, 30, 5, 986761, 1304, 5, 1, 599, 0, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 1, 3, 1000000, 652, 3, 2, 199, 35, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 2, 3, 979308, 661, 11, 2, 281, 38, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 3, 3, 973502, 671, 18, 2, 358, 34, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 3, 5, 987189, 838, 10, 3, 475, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 4, 3, 1000000, 707, 1, 0, 708, 13, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 4, 4, 1000000, 786, 2, 0, 788, 32, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 5, 3, 1000000, 718, 5, 2, 204, 25, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 5, 4, 979694, 790, 11, 4, 401, 21, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 6, 3, 976848, 724, 15, 3, 569, 32, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 7, 3, 993320, 752, 4, 3, 414, 15, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 7, 4, 993398, 834, 4, 1, 534, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 8, 3, 1000000, 773, 1, 2, 425, 10, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 8, 4, 989590, 843, 12, 1, 510, 24, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 9, 3, 1000000, 789, 4, 0, 793, 24, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 9, 4, 996702, 861, 10, 2, 246, 27, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 9, 5, 999274, 946, 6, 1, 318, 32, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 10, 3, 1000000, 802, 8, 0, 810, 31, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 10, 4, 993087, 885, 4, 1, 353, 15, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 11, 3, 988898, 817, 8, 2, 191, 21, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 12, 3, 1000000, 837, 5, 2, 427, 10, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 12, 4, 1000000, 916, 7, 1, 796, 20, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 13, 3, 1000000, 858, 3, 0, 861, 2, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 13, 4, 1000000, 934, 6, 1, 200, 20, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 14, 4, 1000000, 947, 9, 2, 486, 34, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 15, 3, 1000000, 885, 8, 2, 740, 20, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 15, 4, 1000000, 973, 2, 0, 975, 5, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 16, 4, 1000000, 985, 7, 0, 992, 20, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 17, 3, 989459, 919, 8, 2, 620, 29, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 17, 4, 1000000, 1002, 7, 0, 1009, 20, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 18, 3, 1000000, 939, 5, 2, 299, 34, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 18, 4, 996558, 1016, 9, 1, 326, 2, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 18, 5, 1000000, 1102, 3, 1, 529, 10, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 19, 3, 1000000, 963, 0, 0, 963, 1, 8, 3);
+INSERT INTO top_plays VALUES (100000079190, 19, 4, 1000000, 1033, 8, 2, 464, 30, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 20, 3, 1000000, 977, 3, 0, 980, 29, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 21, 3, 995465, 993, 4, 0, 997, 21, 7, 2);
+INSERT INTO top_plays VALUES (100000079190, 21, 4, 1000000, 1068, 7, 2, 758, 8, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 22, 3, 1000000, 1009, 4, 1, 487, 22, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 22, 4, 1000000, 1088, 5, 1, 427, 27, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 23, 3, 1000000, 1024, 6, 1, 867, 23, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 23, 4, 995929, 1104, 6, 1, 579, 30, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 24, 3, 986013, 1039, 8, 1, 654, 5, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 24, 4, 1000000, 1126, 0, 2, 461, 12, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 24, 5, 1000000, 1203, 4, 1, 343, 29, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 25, 3, 965340, 1041, 21, 3, 339, 0, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 25, 4, 1000000, 1143, 0, 2, 699, 17, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 27, 3, 1000000, 1096, 2, 1, 906, 12, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 27, 4, 1000000, 1174, 3, 2, 641, 5, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 27, 5, 1000000, 1251, 8, 0, 1259, 29, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 28, 3, 1000000, 1106, 8, 2, 977, 25, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 28, 4, 991619, 1189, 7, 0, 1196, 37, 7, 2);
+INSERT INTO top_plays VALUES (100000079190, 29, 3, 996168, 1124, 7, 2, 284, 10, 7, 1);
+INSERT INTO top_plays VALUES (100000079190, 29, 4, 1000000, 1207, 4, 2, 417, 4, 8, 1);
+INSERT INTO top_plays VALUES (100000079190, 30, 4, 1000000, 1228, 2, 0, 1230, 14, 8, 2);
+INSERT INTO top_plays VALUES (100000079190, 30, 5, 983986, 1297, 11, 2, 1054, 39, 7, 1);
+INSERT INTO top_plays VALUES (100000087109, 1, 3, 1000000, 650, 5, 2, 147, 14, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 3, 3, 1000000, 682, 8, 1, 521, 37, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 3, 4, 1000000, 766, 3, 2, 562, 7, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 3, 5, 1000000, 843, 8, 0, 851, 23, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 4, 4, 1000000, 782, 5, 1, 623, 19, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 5, 3, 1000000, 725, 0, 0, 725, 22, 8, 3);
+INSERT INTO top_plays VALUES (100000087109, 6, 3, 998949, 739, 3, 0, 742, 37, 7, 2);
+INSERT INTO top_plays VALUES (100000087109, 6, 4, 1000000, 817, 3, 2, 694, 10, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 6, 5, 995572, 892, 9, 1, 636, 6, 7, 1);
+INSERT INTO top_plays VALUES (100000087109, 7, 3, 958701, 736, 19, 4, 179, 30, 7, 1);
+INSERT INTO top_plays VALUES (100000087109, 7, 4, 1000000, 837, 0, 2, 550, 23, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 8, 4, 1000000, 849, 5, 2, 199, 18, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 9, 3, 1000000, 783, 8, 2, 678, 39, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 9, 5, 1000000, 946, 5, 2, 652, 8, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 10, 3, 1000000, 808, 2, 0, 810, 37, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 10, 4, 1000000, 883, 7, 0, 890, 5, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 11, 4, 1000000, 900, 5, 2, 422, 6, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 12, 3, 1000000, 842, 2, 0, 844, 35, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 12, 4, 1000000, 921, 3, 0, 924, 24, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 13, 3, 1000000, 854, 5, 2, 460, 32, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 13, 4, 1000000, 931, 8, 2, 527, 37, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 14, 3, 1000000, 874, 4, 0, 878, 6, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 14, 4, 1000000, 950, 8, 0, 958, 4, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 15, 4, 1000000, 970, 5, 0, 975, 26, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 15, 5, 968771, 1035, 18, 2, 451, 12, 7, 1);
+INSERT INTO top_plays VALUES (100000087109, 16, 3, 1000000, 909, 2, 1, 588, 0, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 17, 3, 1000000, 929, 0, 0, 929, 24, 8, 3);
+INSERT INTO top_plays VALUES (100000087109, 18, 4, 1000000, 1016, 8, 2, 418, 2, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 19, 4, 1000000, 1034, 7, 2, 276, 29, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 20, 4, 989020, 1053, 5, 2, 339, 3, 7, 1);
+INSERT INTO top_plays VALUES (100000087109, 21, 3, 1000000, 986, 9, 2, 627, 38, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 21, 4, 1000000, 1073, 4, 0, 1077, 8, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 22, 3, 989237, 1009, 4, 1, 552, 21, 7, 1);
+INSERT INTO top_plays VALUES (100000087109, 22, 4, 1000000, 1087, 7, 0, 1094, 6, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 23, 3, 1000000, 1027, 4, 0, 1031, 21, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 23, 4, 1000000, 1103, 8, 0, 1111, 40, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 24, 4, 1000000, 1117, 9, 2, 480, 10, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 24, 5, 1000000, 1206, 2, 0, 1208, 7, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 25, 3, 1000000, 1063, 2, 0, 1065, 12, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 26, 3, 1000000, 1076, 6, 0, 1082, 32, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 27, 4, 1000000, 1176, 1, 2, 684, 2, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 27, 5, 1000000, 1254, 5, 0, 1259, 14, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 28, 3, 1000000, 1107, 8, 1, 913, 38, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 29, 3, 1000000, 1129, 4, 0, 1133, 23, 8, 2);
+INSERT INTO top_plays VALUES (100000087109, 29, 4, 1000000, 1213, 0, 0, 1213, 26, 8, 3);
+INSERT INTO top_plays VALUES (100000087109, 30, 3, 1000000, 1146, 2, 2, 897, 36, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 30, 4, 1000000, 1224, 4, 2, 796, 22, 8, 1);
+INSERT INTO top_plays VALUES (100000087109, 30, 5, 983675, 1297, 10, 3, 739, 39, 7, 1);
+COMMIT;
@@ -0,0 +1,337 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use sqlx::{Connection, FromRow, SqliteConnection};
+
+use crate::error::ElodonError;
+use crate::events::{events_since, PlayEvent};
+use crate::filters::*;
+use crate::structs::*;
+
+// timed score attacks between the members of a server. everyone's result is their biggest
+// improvement on one of the challenge's charts, going by the play_events detected while it runs
+
+pub const MAX_DAYS: i64 = 60;
+const MIN_LENGTH: i64 = 60 * 60;
+// plays are only seen once the scraper has them and the bot has compared top_plays, which can be
+// a while after they were made. improvements detected this long after the end still count and the
+// results wait for it. top_plays has no play times, so a play made during the grace counts as well
+pub const GRACE: i64 = 60 * 30;
+
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+pub struct Challenge {
+    #[sqlx(rename = "challenge_id")]
+    pub id: i64,
+    pub guild_id: i64,
+    pub name: String,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub created_by: i64,
+    pub finished: bool,
+}
+
+impl Challenge {
+    pub fn has_started(&self, now: i64) -> bool {
+        now >= self.starts_at
+    }
+
+    pub fn has_ended(&self, now: i64) -> bool {
+        now >= self.ends_at
+    }
+
+    // when the standings are final, see GRACE
+    pub fn results_at(&self) -> i64 {
+        self.ends_at + GRACE
+    }
+}
+
+// how the charts of a new challenge are picked. listed charts are used as they are, otherwise
+// it's every chart that matches all of the set fields
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChartPick {
+    pub charts: Vec<ChartId>,
+    pub genre: Option<Genre>,
+    pub level: Option<DisplayLevel>,
+    // range of score_miyabi, the elo a chart predicts 1,000,000 at. the scraper has no star
+    // ratings, this is the difficulty it knows
+    pub min_elo: Option<f32>,
+    pub max_elo: Option<f32>,
+}
+
+impl ChartPick {
+    pub fn is_empty(&self) -> bool {
+        self.charts.is_empty() && self.genre.is_none() && self.level.is_none() && self.min_elo.is_none() && self.max_elo.is_none()
+    }
+
+    fn in_elo_range(&self, chart: &Chart) -> bool {
+        if self.min_elo.is_none() && self.max_elo.is_none() {
+            return true;
+        }
+        chart.score_miyabi.is_some_and(|elo| {
+            self.min_elo.is_none_or(|min| elo as f32 >= min) && self.max_elo.is_none_or(|max| elo as f32 <= max)
+        })
+    }
+}
+
+// the score before and the best score during the challenge, on the chart that improved the most
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Standing {
+    pub user: i64,
+    pub chart: ChartId,
+    pub before: u32,
+    pub score: u32,
+}
+
+impl Standing {
+    pub fn gain(&self) -> u32 {
+        self.score - self.before
+    }
+}
+
+// "12:oni 40:ura, 7:hard" into charts
+pub fn parse_charts(text: &str) -> Result<Vec<ChartId>, ElodonError> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (song, level) = part.split_once(':').ok_or(ElodonError::ParseError(part.to_string()))?;
+            let song = song.trim().parse().map_err(|_| ElodonError::ParseError(part.to_string()))?;
+            Ok(ChartId(song, level.parse()?))
+        })
+        .collect()
+}
+
+pub async fn pick_charts(conn: &mut SqliteConnection, pick: &ChartPick) -> Result<Vec<Chart>, ElodonError> {
+    if pick.is_empty() {
+        return Err(ElodonError::NoChallengeCharts);
+    }
+    let charts = match pick.charts.is_empty() {
+        false => {
+            let mut charts = vec![];
+            for chart in pick.charts.iter().unique() {
+                charts.push(Chart::fetch_one(conn, GeneralFilter::new().song_id(Some(chart.song_id())).level(Some(chart.level()))).await?);
+            }
+            charts
+        }
+        true => {
+            let songs: HashSet<u32> = Song::fetch_all_or_empty(conn, GeneralFilter::new().genre(pick.genre)).await?
+                .iter()
+                .map(|song| song.id)
+                .collect();
+            Chart::fetch_all_or_empty(conn, GeneralFilter::new().display_level(pick.level)).await?
+                .into_iter()
+                .filter(|chart| songs.contains(&chart.id) && pick.in_elo_range(chart))
+                .collect()
+        }
+    };
+    match charts.is_empty() {
+        true => Err(ElodonError::NoChallengeCharts),
+        false => Ok(charts)
+    }
+}
+
+pub async fn create_challenge(conn: &mut SqliteConnection, guild_id: i64, name: &str, starts_at: i64, ends_at: i64,
+                              created_by: i64, charts: &[Chart]) -> Result<Challenge, ElodonError> {
+    if !(MIN_LENGTH..=MAX_DAYS * 24 * 60 * 60).contains(&(ends_at - starts_at)) {
+        return Err(ElodonError::InvalidChallengeLength);
+    }
+    if charts.is_empty() {
+        return Err(ElodonError::NoChallengeCharts);
+    }
+    let mut transaction = conn.begin().await?;
+    let id = sqlx::query("INSERT INTO challenges (guild_id, name, starts_at, ends_at, created_by) VALUES (?,?,?,?,?)")
+        .bind(guild_id)
+        .bind(name)
+        .bind(starts_at)
+        .bind(ends_at)
+        .bind(created_by)
+        .execute(&mut *transaction).await?
+        .last_insert_rowid();
+    for chart in charts {
+        sqlx::query("INSERT OR IGNORE INTO challenge_charts (challenge_id, song_id, level_id) VALUES (?,?,?)")
+            .bind(id)
+            .bind(chart.id)
+            .bind(chart.level)
+            .execute(&mut *transaction).await?;
+    }
+    transaction.commit().await?;
+    challenge(conn, guild_id, id).await
+}
+
+pub async fn challenge(conn: &mut SqliteConnection, guild_id: i64, id: i64) -> Result<Challenge, ElodonError> {
+    let challenge: Option<Challenge> = sqlx::query_as("SELECT * FROM challenges WHERE guild_id = ? AND challenge_id = ?")
+        .bind(guild_id)
+        .bind(id)
+        .fetch_optional(conn).await?;
+    challenge.ok_or(ElodonError::NoChallenge(id))
+}
+
+// the server's challenges that haven't been announced yet, the one ending first first
+pub async fn guild_challenges(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<Challenge>, ElodonError> {
+    Ok(sqlx::query_as("SELECT * FROM challenges WHERE guild_id = ? AND NOT finished ORDER BY ends_at, challenge_id")
+        .bind(guild_id)
+        .fetch_all(conn).await?)
+}
+
+// challenges of every server that are over, grace included, but whose winners weren't announced
+pub async fn ended_challenges(conn: &mut SqliteConnection, now: i64) -> Result<Vec<Challenge>, ElodonError> {
+    Ok(sqlx::query_as("SELECT * FROM challenges WHERE ends_at + ? <= ? AND NOT finished ORDER BY ends_at")
+        .bind(GRACE)
+        .bind(now)
+        .fetch_all(conn).await?)
+}
+
+pub async fn mark_finished(conn: &mut SqliteConnection, id: i64) -> Result<(), ElodonError> {
+    sqlx::query("UPDATE challenges SET finished = 1 WHERE challenge_id = ?")
+        .bind(id)
+        .execute(conn).await?;
+    Ok(())
+}
+
+pub async fn cancel_challenge(conn: &mut SqliteConnection, guild_id: i64, id: i64) -> Result<(), ElodonError> {
+    let mut transaction = conn.begin().await?;
+    let deleted = sqlx::query("DELETE FROM challenges WHERE guild_id = ? AND challenge_id = ?")
+        .bind(guild_id)
+        .bind(id)
+        .execute(&mut *transaction).await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ElodonError::NoChallenge(id));
+    }
+    sqlx::query("DELETE FROM challenge_charts WHERE challenge_id = ?")
+        .bind(id)
+        .execute(&mut *transaction).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn challenge_charts(conn: &mut SqliteConnection, id: i64) -> Result<Vec<ChartId>, ElodonError> {
+    let charts: Vec<(u32, u32)> = sqlx::query_as("SELECT song_id, level_id FROM challenge_charts WHERE challenge_id = ? ORDER BY song_id, level_id")
+        .bind(id)
+        .fetch_all(conn).await?;
+    charts.into_iter()
+        .map(|(song, level)| Ok(ChartId(song, Level::try_from(level)?)))
+        .collect()
+}
+
+// the best improvement of every player that improved, biggest first. charts first played during
+// the challenge don't count, there's nothing to improve on
+pub fn standings(challenge: &Challenge, charts: &HashSet<ChartId>, events: &[PlayEvent], players: &HashSet<i64>) -> Vec<Standing> {
+    events.iter()
+        .filter(|event| (challenge.starts_at..challenge.results_at()).contains(&event.detected_at))
+        .filter(|event| players.contains(&event.user) && charts.contains(&ChartId(event.song, event.level())))
+        .into_group_map_by(|event| (event.user, ChartId(event.song, event.level())))
+        .into_iter()
+        .filter_map(|((user, chart), events)| {
+            let before = events.iter().min_by_key(|event| event.detected_at)?.old?.score;
+            let score = events.iter().map(|event| event.new.score).max()?;
+            (score > before).then_some(Standing { user, chart, before, score })
+        })
+        .into_group_map_by(|standing| standing.user)
+        .into_values()
+        .filter_map(|standings| standings.into_iter().max_by_key(|standing| (standing.gain(), standing.score)))
+        .sorted_by_key(|standing| (Reverse(standing.gain()), Reverse(standing.score), standing.user))
+        .collect()
+}
+
+pub async fn current_standings(conn: &mut SqliteConnection, challenge: &Challenge, players: &HashSet<i64>) -> Result<Vec<Standing>, ElodonError> {
+    let charts: HashSet<ChartId> = challenge_charts(conn, challenge.id).await?.into_iter().collect();
+    let events = events_since(conn, challenge.starts_at).await?;
+    Ok(standings(challenge, &charts, &events, players))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{detect, SeenPlay};
+    use crate::history::now;
+    use crate::schema::fixture_connection;
+
+    const GUILD: i64 = 1;
+
+    #[test]
+    fn charts_are_parsed() {
+        assert_eq!(parse_charts("12:oni 40:Ura,7:hard").unwrap(), vec![
+            ChartId(12, Level::Oni), ChartId(40, Level::Ura), ChartId(7, Level::Hard)
+        ]);
+        assert!(parse_charts("12").is_err());
+        assert!(parse_charts("oni:12").is_err());
+    }
+
+    #[tokio::test]
+    async fn charts_are_picked() {
+        let mut conn = fixture_connection().await;
+        assert!(matches!(pick_charts(&mut conn, &ChartPick::default()).await, Err(ElodonError::NoChallengeCharts)));
+
+        let listed = ChartPick { charts: vec![ChartId(1, Level::Oni), ChartId(1, Level::Oni)], ..Default::default() };
+        assert_eq!(pick_charts(&mut conn, &listed).await.unwrap().iter().map(Chart::id).collect_vec(), vec![ChartId(1, Level::Oni)]);
+
+        let all = Chart::fetch_all_or_empty(&mut conn, GeneralFilter::new()).await.unwrap();
+        let median = all.iter().filter_map(|chart| chart.score_miyabi).sorted().nth(all.len() / 2).unwrap() as f32;
+        let hard = ChartPick { level: Some(DisplayLevel::OniPlus), min_elo: Some(median), ..Default::default() };
+        let picked = pick_charts(&mut conn, &hard).await.unwrap();
+        assert!(!picked.is_empty() && picked.len() < all.len());
+        assert!(picked.iter().all(|chart| chart.level >= 4 && chart.score_miyabi.unwrap() as f32 >= median));
+    }
+
+    #[tokio::test]
+    async fn biggest_improvement_wins() {
+        let mut conn = fixture_connection().await;
+        detect(&mut conn).await.unwrap();
+        let play: Play = sqlx::query_as("SELECT * FROM top_plays WHERE level_id = 4 AND score < 900000 ORDER BY song_id LIMIT 1")
+            .fetch_one(&mut conn).await.unwrap();
+        let charts = pick_charts(&mut conn, &ChartPick { charts: vec![ChartId(play.song, Level::Oni)], ..Default::default() }).await.unwrap();
+        assert!(matches!(create_challenge(&mut conn, GUILD, "too short", now(), now() + 60, 0, &charts).await, Err(ElodonError::InvalidChallengeLength)));
+        let week = create_challenge(&mut conn, GUILD, "week", now() - 60, now() + 60 * 60 * 24 * 7, 0, &charts).await.unwrap();
+        assert_eq!(guild_challenges(&mut conn, GUILD).await.unwrap(), vec![week.clone()]);
+        assert!(guild_challenges(&mut conn, GUILD + 1).await.unwrap().is_empty());
+
+        let others: Vec<Play> = sqlx::query_as("SELECT * FROM top_plays WHERE song_id = ? AND level_id = 4 AND user_id != ? AND score < 900000 LIMIT 1")
+            .bind(play.song)
+            .bind(play.user)
+            .fetch_all(&mut conn).await.unwrap();
+        for (improved, gain) in [(play, 50000)].into_iter().chain(others.iter().map(|other| (*other, 10000))) {
+            sqlx::query("UPDATE top_plays SET score = score + ? WHERE user_id = ? AND song_id = ? AND level_id = 4")
+                .bind(gain)
+                .bind(improved.user)
+                .bind(improved.song)
+                .execute(&mut conn).await.unwrap();
+        }
+        detect(&mut conn).await.unwrap();
+
+        let players: HashSet<i64> = User::fetch_all_or_empty(&mut conn, GeneralFilter::new()).await.unwrap()
+            .iter().map(|user| user.id).collect();
+        let standings = current_standings(&mut conn, &week, &players).await.unwrap();
+        assert_eq!(standings.len(), 1 + others.len());
+        assert_eq!(standings[0], Standing { user: play.user, chart: ChartId(play.song, Level::Oni), before: play.score, score: play.score + 50000 });
+        assert!(current_standings(&mut conn, &week, &HashSet::new()).await.unwrap().is_empty());
+
+        assert!(ended_challenges(&mut conn, now()).await.unwrap().is_empty());
+        assert!(ended_challenges(&mut conn, week.ends_at).await.unwrap().is_empty());
+        assert_eq!(ended_challenges(&mut conn, week.results_at()).await.unwrap(), vec![week.clone()]);
+        mark_finished(&mut conn, week.id).await.unwrap();
+        assert!(ended_challenges(&mut conn, week.results_at()).await.unwrap().is_empty());
+
+        cancel_challenge(&mut conn, GUILD, week.id).await.unwrap();
+        assert!(matches!(challenge(&mut conn, GUILD, week.id).await, Err(ElodonError::NoChallenge(_))));
+        assert!(challenge_charts(&mut conn, week.id).await.unwrap().is_empty());
+    }
+    #[test]
+    fn late_detections_count_until_the_grace_is_over() {
+        let challenge = Challenge { id: 1, guild_id: GUILD, name: "day".to_owned(), starts_at: 1000, ends_at: 2000, created_by: 0, finished: false };
+        let charts = HashSet::from([ChartId(1, Level::Oni)]);
+        let event = |user: i64, detected_at: i64| PlayEvent {
+            user,
+            song: 1,
+            level: 4,
+            new: SeenPlay { score: 950000, crown: 0, rank: 0 },
+            old: Some(SeenPlay { score: 900000, crown: 0, rank: 0 }),
+            z: None,
+            detected_at,
+        };
+        let events = [event(1, 999), event(2, 1500), event(3, 2000 + GRACE - 1), event(4, 2000 + GRACE)];
+        let standings = standings(&challenge, &charts, &events, &HashSet::from([1, 2, 3, 4]));
+        assert_eq!(standings.iter().map(|standing| standing.user).collect_vec(), vec![2, 3]);
+    }
+}
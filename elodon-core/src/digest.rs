@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use itertools::Itertools;
+use sqlx::SqliteConnection;
+
+use crate::error::ElodonError;
+use crate::events::{events_since, rank_among, PlayEvent};
+use crate::filters::*;
+use crate::history::elo_history;
+use crate::structs::*;
+
+// what the weekly digest covers. everything is limited to the players of one server
+
+pub const DIGEST_PERIOD: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+// how many players or plays each part lists
+const LISTED: usize = 5;
+
+const LEVELS: [DisplayLevel; 4] = [DisplayLevel::Easy, DisplayLevel::Med, DisplayLevel::Hard, DisplayLevel::OniPlus];
+
+#[derive(Clone, Default)]
+pub struct Digest {
+    // biggest elo gains since the start of the period, per level with any
+    pub elo_gains: Vec<(DisplayLevel, Vec<(User, f32)>)>,
+    // most personal bests
+    pub most_improved: Vec<(User, usize)>,
+    // charts cleared for the first time
+    pub cleared: Vec<PlayEvent>,
+    // personal bests with the highest z values
+    pub top_plays: Vec<PlayEvent>,
+    // personal bests that took first place on the chart among the server's players
+    pub first_places: Vec<PlayEvent>,
+}
+
+impl Digest {
+    pub fn is_empty(&self) -> bool {
+        self.elo_gains.is_empty() && self.most_improved.is_empty() && self.cleared.is_empty()
+            && self.top_plays.is_empty() && self.first_places.is_empty()
+    }
+}
+
+// players are donder ids, since is when the period started
+pub async fn digest(conn: &mut SqliteConnection, players: &HashSet<i64>, since: i64) -> Result<Digest, ElodonError> {
+    let users: HashMap<i64, User> = User::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+        .into_iter()
+        .filter(|user| players.contains(&user.id))
+        .map(|user| (user.id, user))
+        .collect();
+    let events = events_since(conn, since).await?
+        .into_iter()
+        .filter(|event| users.contains_key(&event.user))
+        .collect_vec();
+
+    let mut gains: Vec<(DisplayLevel, User, f32)> = vec![];
+    for user in users.values() {
+        let history = elo_history(conn, user.id).await?;
+        // the last snapshot before the period, or the first one in it for new players
+        let Some(start) = history.iter().rev().find(|snapshot| snapshot.recorded_at <= since).or(history.first()) else { continue };
+        for level in LEVELS {
+            if let (Some(before), Some(now)) = (start.elo(level), user.elo(level)) {
+                if now > before {
+                    gains.push((level, user.clone(), now - before));
+                }
+            }
+        }
+    }
+    let elo_gains = LEVELS.into_iter()
+        .map(|level| (level, gains.iter()
+            .filter(|(gain_level, _, _)| *gain_level == level)
+            .sorted_by(|(_, _, a), (_, _, b)| b.total_cmp(a))
+            .take(LISTED)
+            .map(|(_, user, gain)| (user.clone(), *gain))
+            .collect_vec()))
+        .filter(|(_, gains)| !gains.is_empty())
+        .collect();
+
+    let most_improved = events.iter()
+        .counts_by(|event| event.user)
+        .into_iter()
+        .sorted_by_key(|(user, count)| (std::cmp::Reverse(*count), *user))
+        .take(LISTED)
+        .filter_map(|(user, count)| Some((users.get(&user)?.clone(), count)))
+        .collect();
+
+    let cleared = events.iter()
+        .filter(|event| event.new.crown > 0 && event.old.is_none_or(|old| old.crown == 0))
+        .copied()
+        .collect();
+
+    let top_plays = events.iter()
+        .filter(|event| event.z.is_some())
+        .sorted_by(|a, b| b.z.unwrap_or_default().total_cmp(&a.z.unwrap_or_default()))
+        .take(LISTED)
+        .copied()
+        .collect();
+
+    let mut first_places = vec![];
+    for event in &events {
+        let plays = Play::fetch_all(conn, event.chart()).await?
+            .into_iter()
+            .filter(|play| users.contains_key(&play.user))
+            .map(|play| (play.user, play.score))
+            .collect_vec();
+        let first_now = rank_among(plays.iter().copied(), event.user, event.new.score) == 1;
+        let first_before = event.old.is_some_and(|old| rank_among(plays.iter().copied(), event.user, old.score) == 1);
+        // a later personal best on the same chart is the one that counts
+        let latest = !events.iter().any(|other| (other.user, other.song, other.level) == (event.user, event.song, event.level) && other.detected_at > event.detected_at);
+        if first_now && !first_before && latest && plays.len() > 1 {
+            first_places.push(*event);
+        }
+    }
+
+    Ok(Digest { elo_gains, most_improved, cleared, top_plays, first_places })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::detect;
+    use crate::history::{now, record_elo};
+    use crate::schema::fixture_connection;
+
+    #[tokio::test]
+    async fn digest_of_a_week() {
+        let mut conn = fixture_connection().await;
+        let since = now() - DIGEST_PERIOD.as_secs() as i64;
+        record_elo(&mut conn).await.unwrap();
+        detect(&mut conn).await.unwrap();
+        let players: HashSet<i64> = User::fetch_all(&mut conn, GeneralFilter::new()).await.unwrap()
+            .iter().map(|user| user.id).collect();
+        assert!(digest(&mut conn, &players, since).await.unwrap().is_empty());
+
+        // player00 takes first place on a chart with a full combo and gains elo
+        let user = User::fetch_one(&mut conn, UserFilter::new().user_id(Some(100000000000))).await.unwrap();
+        let play: Play = sqlx::query_as("SELECT * FROM top_plays WHERE user_id = ? AND level_id = 4 AND score < 990000 LIMIT 1")
+            .bind(user.id)
+            .fetch_one(&mut conn).await.unwrap();
+        sqlx::query("UPDATE top_plays SET score = 1000000, crown = 2 WHERE user_id = ? AND song_id = ? AND level_id = 4")
+            .bind(user.id)
+            .bind(play.song)
+            .execute(&mut conn).await.unwrap();
+        sqlx::query("UPDATE users SET elo4 = elo4 + 50 WHERE user_id = ?")
+            .bind(user.id)
+            .execute(&mut conn).await.unwrap();
+        detect(&mut conn).await.unwrap();
+
+        let week = digest(&mut conn, &players, since).await.unwrap();
+        assert_eq!(week.elo_gains.len(), 1);
+        let (level, gains) = &week.elo_gains[0];
+        assert_eq!(*level, DisplayLevel::OniPlus);
+        assert_eq!(gains[0].0.id, user.id);
+        assert!((gains[0].1 - 50.).abs() < 0.01);
+        assert_eq!(week.most_improved[0].0.id, user.id);
+        assert_eq!(week.top_plays.len(), 1);
+        assert_eq!(week.first_places.len(), 1);
+        assert_eq!(week.cleared.len(), (play.crown == 0) as usize);
+
+        let others: HashSet<i64> = players.iter().copied().filter(|player| *player != user.id).collect();
+        assert!(digest(&mut conn, &others, since).await.unwrap().is_empty());
+    }
+}
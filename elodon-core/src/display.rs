@@ -0,0 +1,126 @@
+use itertools::Itertools;
+
+use crate::structs::*;
+
+// how rows are listed, by /sql fetch in discord and by the cli
+
+pub const CROWNS: [&str; 4] = ["·", "🥈", "🥇", "🌈"];
+pub const RANKS: [&str; 9] = ["", "", "白粋", "銅粋", "銀粋", "金雅", "桃雅", "紫雅", "極"];
+
+// how crowns and ranks are drawn. the bot passes its emojis, everything else gets PlainSymbols
+pub trait Symbols {
+    fn crown(&self, crown: u32) -> String;
+    fn rank(&self, rank: u32) -> String;
+}
+
+pub struct PlainSymbols;
+
+impl Symbols for PlainSymbols {
+    fn crown(&self, crown: u32) -> String {
+        CROWNS.get(crown as usize).copied().unwrap_or_default().to_string()
+    }
+
+    fn rank(&self, rank: u32) -> String {
+        RANKS.get(rank as usize).copied().unwrap_or_default().to_string()
+    }
+}
+
+pub trait ElodonDisplay{
+    fn display_with(&self, symbols: &dyn Symbols) -> String;
+
+    fn get_display_text(&self) -> String {
+        self.display_with(&PlainSymbols)
+    }
+}
+
+pub trait ElodonDisplayList<E>{
+    fn get_display_text(&self) -> String;
+}
+
+impl<E: ElodonDisplay> ElodonDisplayList<E> for Vec<E>{
+    fn get_display_text(&self) -> String {
+        return if self.is_empty() {
+            "No results".to_string()
+        } else {
+            self.iter()
+                .map(|e| e.get_display_text())
+                .join("\n")
+                .to_string()
+        }
+    }
+}
+
+impl ElodonDisplay for User{
+    fn display_with(&self, _symbols: &dyn Symbols) -> String {
+        return format!("`#{:<13}  {:<9}`<@{}>", self.id, self.name, self.discord)
+    }
+}
+
+impl ElodonDisplay for Song{
+    fn display_with(&self, _symbols: &dyn Symbols) -> String {
+        return format!("`#{:<4}  {} > {}`", self.id, self.genre(), self.get_name())
+    }
+}
+
+impl ElodonDisplay for Chart{
+    fn display_with(&self, _symbols: &dyn Symbols) -> String {
+
+        return format!("#{:<4}.{}:\n`Score/ELO={:>4} Miyabi ELO={:>4}\nsd= {} ({})`",
+            self.id, self.level,
+            self.score_slope.map(|i| format!("{i:>4}")).unwrap_or(" ?? ".to_string()),
+            self.score_miyabi.map(|i| format!("{i:>4}")).unwrap_or(" ?? ".to_string()),
+            self.sd_mean.map(|i|format!("{i:>7.0}")).unwrap_or("  ???  ".to_string()),
+            self.sd_sd.map(|i|format!("{i:>4.0}")).unwrap_or(" ?? ".to_string())
+        )
+    }
+}
+
+impl ElodonDisplay for Play{
+    fn display_with(&self, symbols: &dyn Symbols) -> String {
+        let crown_emoji = symbols.crown(self.crown);
+        let rank_emoji = symbols.rank(self.rank);
+
+        format!("{:>4}.{} {:<13} {:>7} {} {}\n` {:>4} | {:>3} | {:<3} c{:<4} r{:<4}`",
+            self.song, self.level, self.score, self.user, crown_emoji, rank_emoji,
+            self.good_cnt, self.ok_cnt, self.bad_cnt, self.combo_cnt, self.level
+        )
+
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::*;
+    use crate::schema::fixture_connection;
+
+    #[tokio::test]
+    async fn display_texts() {
+        let mut conn = fixture_connection().await;
+        let user = User::fetch_one(&mut conn, GeneralFilter::new().user_id(Some(100000000000))).await.unwrap();
+        assert_eq!(user.get_display_text(), "`#100000000000   player00 `<@900000000000000000>");
+
+        let song = Song::fetch_one(&mut conn, GeneralFilter::new().song_id(Some(1))).await.unwrap();
+        assert_eq!(song.get_display_text(), "`#1     Anime > Yawaraka Tank`");
+        assert_eq!(song.to_string(), "Yawaraka Tank (#1)");
+
+        let chart = Chart {
+            id: 1,
+            level: 4,
+            score_slope: Some(95),
+            score_miyabi: None,
+            sd_mean: Some(20150.4),
+            sd_sd: None,
+        };
+        assert_eq!(chart.get_display_text(), "#1   .4:\n`Score/ELO=  95 Miyabi ELO= ?? \nsd=   20150 ( ?? )`");
+
+        let play = Play::fetch_one(&mut conn, GeneralFilter::new().user_id(Some(100000000000)).song_id(Some(1)).level(Some(Level::Oni))).await.unwrap();
+        assert!(play.get_display_text().starts_with(&format!("   1.4 {:<13} {:>7} {}", play.score, play.user, CROWNS[play.crown as usize])));
+    }
+
+    #[test]
+    fn empty_lists_say_so() {
+        let songs: Vec<Song> = vec![];
+        assert_eq!(songs.get_display_text(), "No results");
+    }
+}
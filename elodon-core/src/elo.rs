@@ -0,0 +1,199 @@
+use itertools::Itertools;
+use std::collections::HashMap;
+use probability::distribution;
+use probability::distribution::Inverse;
+use crate::structs::*;
+
+pub fn get_predicted_score(elo: Option<f32>, chart: &Chart, elo_sd_z: f32) -> Option<u32>{
+    Some((
+        (chart.score_slope? as f32 * (elo? + elo_sd_z*chart.sd_sd?*0.001 - chart.score_miyabi? as f32)) as i32
+            + 10i32.pow(6)
+    ).max(0) as u32)
+}
+
+pub fn get_z_value(actual_score: u32, elo: Option<f32>, chart: &Chart, sd_z: f32) -> Option<f32> {
+    return Some(
+        (actual_score as i32 - get_predicted_score(elo, chart, sd_z)? as i32) as f32 / (chart.sd_mean? + sd_z * chart.sd_sd?)
+    )
+}
+
+//gets the standard deviation assuming a two-sided inverse normal
+pub fn get_sd(x: f64, p: f64) -> f64 {
+    if p >= 1. {return 0.}
+    if p <= 0. {return f64::INFINITY}
+    let z = distribution::Gaussian::new(0., 1.).inverse(0.5 + 0.5*p);
+    return x / z;
+}
+// the elo at which chart is predicted to give exactly score
+pub fn get_implied_elo(score: u32, chart: &Chart) -> Option<f32> {
+    let slope = chart.score_slope?;
+    if slope == 0 {return None}
+    Some((score as f32 - 10f32.powi(6)) / slope as f32 + chart.score_miyabi? as f32)
+}
+
+// genres with fewer plays say more about luck than skill
+pub const MIN_GENRE_PLAYS: usize = 3;
+
+#[derive(Copy, Clone, Debug)]
+pub struct GenreStats {
+    pub genre: Genre,
+    pub count: usize,
+    // mean residual. above 0 means the player does better in this genre than their elo says
+    pub mean_z: f32,
+    // how much higher their elo would be if only this genre counted
+    pub elo_shift: Option<f32>,
+}
+
+// plays as (genre, z value, implied elo - elo), best genres first
+pub fn genre_stats(plays: impl IntoIterator<Item = (Genre, f32, Option<f32>)>) -> Vec<GenreStats> {
+    plays.into_iter()
+        .into_group_map_by(|(genre, _, _)| *genre)
+        .into_iter()
+        .map(|(genre, plays)| {
+            let shifts: Vec<f32> = plays.iter().filter_map(|(_, _, shift)| *shift).collect();
+            GenreStats {
+                genre,
+                count: plays.len(),
+                mean_z: plays.iter().map(|(_, z, _)| z).sum::<f32>() / plays.len() as f32,
+                elo_shift: match shifts.is_empty() {
+                    true => None,
+                    false => Some(shifts.iter().sum::<f32>() / shifts.len() as f32)
+                },
+            }
+        })
+        .sorted_by(|a, b| b.mean_z.total_cmp(&a.mean_z))
+        .collect()
+}
+
+// the genres a player is worst at, ignoring ones with too few plays
+pub fn weakest_genres(stats: &[GenreStats], amount: usize) -> Vec<Genre> {
+    stats.iter()
+        .rev()
+        .filter(|stats| stats.count >= MIN_GENRE_PLAYS && stats.mean_z < 0.)
+        .take(amount)
+        .map(|stats| stats.genre)
+        .collect()
+}
+
+// the charts on which score is closest to a z value of desired_z. the z value is given as the
+// range between elo one sd higher and one sd lower, that's what /suggest calls a 70% chance
+pub fn suggest(charts: &[Chart], elo: Option<f32>, score: u32, desired_z: f32, amount: usize) -> Vec<(f32, f32, &Chart)> {
+    let distance = |(lower, upper, _): &(f32, f32, &Chart)| (lower - desired_z).abs() + (upper - desired_z).abs();
+    charts.iter()
+        .filter_map(|chart| {
+            if chart.score_slope? < 0 {return None}
+            let (a, b) = (get_z_value(score, elo, chart, 1.)?, get_z_value(score, elo, chart, -1.)?);
+            if !a.is_finite() || !b.is_finite() {return None}
+            Some((a.min(b), a.max(b), chart))
+        })
+        .sorted_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .take(amount)
+        .collect()
+}
+
+// LATENT FACTORS
+// one elo per level can't tell apart someone who is great at streams but bad at rolls. the latent
+// model learns a few skills per player and how much each chart asks of them from the play matrix,
+// on top of what the elo already predicts
+
+#[derive(Copy, Clone, Debug)]
+pub struct LatentConfig {
+    pub factors: usize,
+    pub epochs: usize,
+    pub learning_rate: f32,
+    pub regularization: f32,
+}
+
+impl Default for LatentConfig {
+    fn default() -> Self {
+        LatentConfig {
+            factors: 4,
+            epochs: 40,
+            learning_rate: 0.02,
+            regularization: 0.05,
+        }
+    }
+}
+
+// one play to learn from: how far the score was from the elo prediction, in sd_mean units
+#[derive(Copy, Clone, Debug)]
+pub struct Residual {
+    pub user: i64,
+    pub chart: ChartId,
+    pub z: f32,
+}
+
+impl Residual {
+    pub fn new(user: i64, chart: &Chart, score: u32, elo: Option<f32>) -> Option<Residual> {
+        Some(Residual {
+            user,
+            chart: chart.id(),
+            z: get_z_value(score, elo, chart, 0f32)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LatentModel {
+    user_bias: HashMap<i64, f32>,
+    chart_bias: HashMap<ChartId, f32>,
+    user_factors: HashMap<i64, Vec<f32>>,
+    chart_factors: HashMap<ChartId, Vec<f32>>,
+}
+
+impl LatentModel {
+    // stochastic gradient descent, same result for the same residuals
+    pub fn fit(residuals: &[Residual], config: LatentConfig) -> LatentModel {
+        let mut model = LatentModel::default();
+        let mut seed = 0x2545F4914F6CDD1Du64;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+        };
+        for residual in residuals {
+            model.user_factors.entry(residual.user)
+                .or_insert_with(|| (0..config.factors).map(|_| random() * 0.1).collect());
+            model.chart_factors.entry(residual.chart)
+                .or_insert_with(|| (0..config.factors).map(|_| random() * 0.1).collect());
+        }
+
+        let (rate, reg) = (config.learning_rate, config.regularization);
+        for _ in 0..config.epochs {
+            for residual in residuals {
+                let error = residual.z - model.predict_z(residual.user, residual.chart);
+                let user_bias = model.user_bias.entry(residual.user).or_default();
+                *user_bias += rate * (error - reg * *user_bias);
+                let chart_bias = model.chart_bias.entry(residual.chart).or_default();
+                *chart_bias += rate * (error - reg * *chart_bias);
+
+                let user_factors = model.user_factors.get_mut(&residual.user).unwrap();
+                let chart_factors = model.chart_factors.get_mut(&residual.chart).unwrap();
+                for (user_factor, chart_factor) in user_factors.iter_mut().zip(chart_factors.iter_mut()) {
+                    let (u, c) = (*user_factor, *chart_factor);
+                    *user_factor += rate * (error * c - reg * u);
+                    *chart_factor += rate * (error * u - reg * c);
+                }
+            }
+        }
+        model
+    }
+
+    // expected residual. 0 for players or charts the model hasn't seen
+    pub fn predict_z(&self, user: i64, chart: ChartId) -> f32 {
+        let bias = self.user_bias.get(&user).unwrap_or(&0.) + self.chart_bias.get(&chart).unwrap_or(&0.);
+        let interaction = match (self.user_factors.get(&user), self.chart_factors.get(&chart)) {
+            (Some(user_factors), Some(chart_factors)) => user_factors.iter().zip(chart_factors).map(|(u, c)| u * c).sum(),
+            _ => 0.
+        };
+        bias + interaction
+    }
+
+    // get_predicted_score corrected by the learned residual
+    pub fn predict_score(&self, user: i64, elo: Option<f32>, chart: &Chart) -> Option<u32> {
+        let baseline = get_predicted_score(elo, chart, 0.)?;
+        let correction = self.predict_z(user, chart.id()) * chart.sd_mean?;
+        Some((baseline as f32 + correction).clamp(0., 10f32.powi(6)) as u32)
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use sqlx::{FromRow, SqliteConnection};
+
+use crate::elo;
+use crate::elo::{LatentConfig, LatentModel, Residual};
+use crate::error::ElodonError;
+use crate::structs::*;
+
+// one in this many plays is held back for testing
+const TEST_EVERY: u64 = 5;
+
+// a play together with everything needed to predict it
+#[derive(Copy, Clone, Debug)]
+pub struct Sample {
+    pub user: i64,
+    pub chart: Chart,
+    pub score: u32,
+    pub elo: Option<f32>,
+}
+
+impl Sample {
+    pub fn level(&self) -> DisplayLevel {
+        DisplayLevel::from(self.chart.level())
+    }
+}
+
+#[derive(Clone, Debug, FromRow)]
+struct SampleRow {
+    user_id: i64,
+    score: u32,
+    #[sqlx(flatten)]
+    chart: Chart,
+    elo1: Option<f32>,
+    elo2: Option<f32>,
+    elo3: Option<f32>,
+    elo4: Option<f32>,
+}
+
+impl From<SampleRow> for Sample {
+    fn from(row: SampleRow) -> Self {
+        let elo = match DisplayLevel::from(row.chart.level()) {
+            DisplayLevel::Easy => row.elo1,
+            DisplayLevel::Med => row.elo2,
+            DisplayLevel::Hard => row.elo3,
+            DisplayLevel::OniPlus => row.elo4,
+        };
+        Sample {
+            user: row.user_id,
+            chart: row.chart,
+            score: row.score,
+            elo,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Dataset {
+    pub samples: Vec<Sample>,
+}
+
+impl Dataset {
+    pub async fn load(conn: &mut SqliteConnection) -> Result<Dataset, ElodonError> {
+        let rows: Vec<SampleRow> = sqlx::query_as(
+            "SELECT p.user_id, p.score, c.song_id, c.level_id, c.score_slope, c.score_miyabi, c.sd_mean, c.sd_sd, \
+                u.elo1, u.elo2, u.elo3, u.elo4 \
+                FROM top_plays p \
+                JOIN charts c ON c.song_id = p.song_id AND c.level_id = p.level_id \
+                JOIN users u ON u.user_id = p.user_id"
+        ).fetch_all(conn).await?;
+        Ok(Dataset { samples: rows.into_iter().map(Sample::from).collect() })
+    }
+
+    // (train, test). decided by a hash of player and chart so every run splits the same way
+    pub fn split(&self) -> (Vec<Sample>, Vec<Sample>) {
+        self.samples.iter().partition(|sample| {
+            split_hash(sample.user, sample.chart.id, sample.chart.level as u64) % TEST_EVERY != 0
+        })
+    }
+}
+
+// fnv-1a over player, song and level. fixed unlike DefaultHasher, so backtests from different
+// builds split the same plays and stay comparable
+fn split_hash(user: i64, song: u32, level: u64) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    [user.to_le_bytes(), (song as u64).to_le_bytes(), level.to_le_bytes()].iter()
+        .flatten()
+        .fold(OFFSET, |hash, byte| (hash ^ *byte as u64).wrapping_mul(PRIME))
+}
+
+// least squares elo for every player and level on the given plays. the prediction is linear in
+// elo, so the best fit is sum(slope * (score - 1e6 + slope * miyabi)) / sum(slope^2)
+pub fn fit_ratings(samples: &[Sample]) -> HashMap<(i64, DisplayLevel), f32> {
+    let mut sums: HashMap<(i64, DisplayLevel), (f64, f64)> = HashMap::new();
+    for sample in samples {
+        let (Some(slope), Some(miyabi)) = (sample.chart.score_slope, sample.chart.score_miyabi) else { continue };
+        if slope <= 0 { continue }
+        let (slope, miyabi) = (slope as f64, miyabi as f64);
+        let sum = sums.entry((sample.user, sample.level())).or_default();
+        sum.0 += slope * (sample.score as f64 - 1e6 + slope * miyabi);
+        sum.1 += slope * slope;
+    }
+    sums.into_iter().map(|(key, (numerator, denominator))| (key, (numerator / denominator) as f32)).collect()
+}
+
+// shift moves the prediction by that many points. the range suggest calls a 70% chance is moved
+// with it, which is done by moving the score the other way
+fn predict(sample: &Sample, elo: Option<f32>, shift: f64) -> Option<Prediction> {
+    let predicted = elo::get_predicted_score(elo, &sample.chart, 0.)? as f64 + shift;
+    let score = (sample.score as f64 - shift).max(0.) as u32;
+    Some(Prediction {
+        error: sample.score as f64 - predicted,
+        sd: sample.chart.sd_mean? as f64,
+        covered: elo::get_z_value(score, elo, &sample.chart, -1.)? >= -1.
+            && elo::get_z_value(score, elo, &sample.chart, 1.)? <= 1.,
+    })
+}
+
+struct Prediction {
+    error: f64,
+    sd: f64,
+    // z of at least -1 with the elo one sd lower and at most +1 with it one sd higher, the
+    // band suggest makes its 70% claims from
+    covered: bool,
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0., 0), |(sum, count), value| (sum + value, count + 1));
+    match count {
+        0 => f64::NAN,
+        count => sum / count as f64
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ModelReport {
+    pub name: &'static str,
+    pub count: usize,
+    // root mean squared error in points
+    pub rmse: f64,
+    // 0 and 1 when the z values are calibrated
+    pub mean_z: f64,
+    pub sd_z: f64,
+    // share of plays with |z| <= 1 and <= 2, 68.3% and 95.4% when calibrated
+    pub within_one: f64,
+    pub within_two: f64,
+    // share of plays in the range suggest calls a 70% chance
+    pub coverage: f64,
+}
+
+impl ModelReport {
+    fn new(name: &'static str, predictions: &[Prediction]) -> ModelReport {
+        let z = |prediction: &Prediction| prediction.error / prediction.sd;
+        let share = |hit: fn(f64, bool) -> bool| mean(predictions.iter().map(|p| hit(z(p), p.covered) as u8 as f64));
+        let mean_z = mean(predictions.iter().map(z));
+        ModelReport {
+            name,
+            count: predictions.len(),
+            rmse: mean(predictions.iter().map(|p| p.error * p.error)).sqrt(),
+            mean_z,
+            sd_z: mean(predictions.iter().map(|p| (z(p) - mean_z).powi(2))).sqrt(),
+            within_one: share(|z, _| z.abs() <= 1.),
+            within_two: share(|z, _| z.abs() <= 2.),
+            coverage: share(|_, covered| covered),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Backtest {
+    pub train: usize,
+    pub test: usize,
+    pub models: Vec<ModelReport>,
+}
+
+// fits elo and the latent model on the train split and scores them on the test split, next to the
+// scraper's elo which has seen every play. only plays all of them can predict are counted
+pub fn backtest(dataset: &Dataset, config: LatentConfig) -> Backtest {
+    let (train, test) = dataset.split();
+    let ratings = fit_ratings(&train);
+    let fitted = |sample: &Sample| ratings.get(&(sample.user, sample.level())).copied();
+
+    let residuals: Vec<Residual> = train.iter()
+        .filter_map(|sample| Residual::new(sample.user, &sample.chart, sample.score, fitted(sample)))
+        .collect();
+    let latent = LatentModel::fit(&residuals, config);
+
+    let predictions: Vec<(Prediction, Prediction, Prediction)> = test.iter()
+        .filter_map(|sample| {
+            let elo = fitted(sample);
+            let shift = latent.predict_score(sample.user, elo, &sample.chart)? as f64
+                - elo::get_predicted_score(elo, &sample.chart, 0.)? as f64;
+            Some((predict(sample, sample.elo, 0.)?, predict(sample, elo, 0.)?, predict(sample, elo, shift)?))
+        })
+        .collect();
+
+    let (scraper, fitted, latent): (Vec<_>, Vec<_>, Vec<_>) = predictions.into_iter().multiunzip();
+    Backtest {
+        train: train.len(),
+        test: test.len(),
+        models: vec![
+            ModelReport::new("scraper elo", &scraper),
+            ModelReport::new("fitted elo", &fitted),
+            ModelReport::new("latent factors", &latent),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::fixture_connection;
+
+    fn chart(id: u32, slope: i32, miyabi: i32) -> Chart {
+        Chart { id, level: 4, score_slope: Some(slope), score_miyabi: Some(miyabi), sd_mean: Some(20000.), sd_sd: Some(4000.) }
+    }
+
+    #[test]
+    fn fit_recovers_exact_elo() {
+        let samples: Vec<Sample> = [chart(1, 100, 1500), chart(2, 80, 1400), chart(3, 120, 1600)].into_iter()
+            .map(|chart| Sample {
+                user: 7,
+                chart,
+                score: elo::get_predicted_score(Some(1300.), &chart, 0.).unwrap(),
+                elo: None,
+            })
+            .collect();
+        let ratings = fit_ratings(&samples);
+        assert!((ratings[&(7, DisplayLevel::OniPlus)] - 1300.).abs() < 0.1);
+    }
+
+    #[test]
+    fn split_hash_is_fixed() {
+        assert_eq!(split_hash(0, 0, 0), 0x81d23fd7003c2305);
+        assert_ne!(split_hash(1, 2, 4), split_hash(2, 1, 4));
+    }
+
+    #[tokio::test]
+    async fn split_is_stable_and_complete() {
+        let dataset = Dataset::load(&mut fixture_connection().await).await.unwrap();
+        let (train, test) = dataset.split();
+        let (train_again, test_again) = dataset.split();
+
+        assert_eq!(train.len() + test.len(), dataset.samples.len());
+        assert_eq!(test.len(), test_again.len());
+        assert_eq!(train.len(), train_again.len());
+        assert!(!test.is_empty() && train.len() > test.len());
+    }
+
+    #[tokio::test]
+    async fn backtest_on_fixture() {
+        let dataset = Dataset::load(&mut fixture_connection().await).await.unwrap();
+        let backtest = backtest(&dataset, LatentConfig::default());
+        assert_eq!(backtest.train + backtest.test, dataset.samples.len());
+
+        // the fixture's plays are drawn around the prediction with the chart's sd, so fitted
+        // elo should come out roughly calibrated
+        let fitted = backtest.models.iter().find(|report| report.name == "fitted elo").unwrap();
+        assert!(fitted.count > 0);
+        assert!(fitted.rmse < 30000.);
+        assert!(fitted.mean_z.abs() < 0.25);
+        assert!((0.6..0.9).contains(&fitted.coverage));
+        assert!(fitted.within_one < fitted.within_two);
+        assert!(backtest.models.iter().all(|report| report.count == fitted.count));
+    }
+}
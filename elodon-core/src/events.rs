@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use sqlx::{Connection, FromRow, SqliteConnection};
+
+use crate::elo;
+use crate::error::ElodonError;
+use crate::filters::*;
+use crate::history::now;
+use crate::structs::*;
+
+// the scraper only replaces top_plays rows, so new personal bests are found by comparing them
+// with seen_plays, the rows as they were last time
+
+// plays at least this far above the player's elo are highlighted
+pub const NOTABLE_Z: f32 = 1.5;
+
+
+// the parts of a play an announcement is about
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, FromRow)]
+pub struct SeenPlay {
+    pub score: u32,
+    pub crown: u32,
+    pub rank: u32,
+}
+
+impl From<&Play> for SeenPlay {
+    fn from(play: &Play) -> Self {
+        SeenPlay { score: play.score, crown: play.crown, rank: play.rank }
+    }
+}
+
+// a top_plays row that is new or better than when it was last seen
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlayEvent {
+    pub user: i64,
+    pub song: u32,
+    pub level: u32,
+    pub new: SeenPlay,
+    // None the first time the chart is played
+    pub old: Option<SeenPlay>,
+    pub z: Option<f32>,
+    pub detected_at: i64,
+}
+
+impl PlayEvent {
+    pub fn level(&self) -> Level {
+        Level::try_from(self.level).unwrap()
+    }
+
+    pub fn chart(&self) -> GeneralFilter {
+        GeneralFilter::new().song_id(Some(self.song)).level(Some(self.level()))
+    }
+
+    pub fn better_score(&self) -> bool {
+        self.old.map_or(true, |old| self.new.score > old.score)
+    }
+
+    pub fn better_crown(&self) -> bool {
+        self.old.map_or(self.new.crown > 0, |old| self.new.crown > old.crown)
+    }
+
+    pub fn better_rank(&self) -> bool {
+        self.old.map_or(self.new.rank > 0, |old| self.new.rank > old.rank)
+    }
+
+    pub fn is_notable(&self) -> bool {
+        self.z.is_some_and(|z| z >= NOTABLE_Z)
+    }
+}
+
+#[derive(FromRow)]
+struct PlayEventRow {
+    user_id: i64,
+    song_id: u32,
+    level_id: u32,
+    score: u32,
+    crown: u32,
+    rank: u32,
+    old_score: Option<u32>,
+    old_crown: Option<u32>,
+    old_rank: Option<u32>,
+    z: Option<f32>,
+    detected_at: i64,
+}
+
+impl From<PlayEventRow> for PlayEvent {
+    fn from(row: PlayEventRow) -> Self {
+        let old = match (row.old_score, row.old_crown, row.old_rank) {
+            (Some(score), Some(crown), Some(rank)) => Some(SeenPlay { score, crown, rank }),
+            _ => None
+        };
+        PlayEvent {
+            user: row.user_id,
+            song: row.song_id,
+            level: row.level_id,
+            new: SeenPlay { score: row.score, crown: row.crown, rank: row.rank },
+            old,
+            z: row.z,
+            detected_at: row.detected_at,
+        }
+    }
+}
+
+// plays that are new, or better in score, crown or rank than what was seen
+pub fn changed_plays(seen: &HashMap<(i64, u32, u32), SeenPlay>, current: &[Play]) -> Vec<(Play, Option<SeenPlay>)> {
+    current.iter()
+        .filter_map(|play| {
+            let old = seen.get(&(play.user, play.song, play.level)).copied();
+            let improved = match old {
+                None => true,
+                Some(old) => play.score > old.score || play.crown > old.crown || play.rank > old.rank
+            };
+            improved.then_some((*play, old))
+        })
+        .collect()
+}
+
+// place of score on a chart among (user, score) of everyone else on it
+pub fn rank_among(plays: impl IntoIterator<Item = (i64, u32)>, user: i64, score: u32) -> usize {
+    1 + plays.into_iter().filter(|(other, other_score)| *other != user && *other_score > score).count()
+}
+
+// compares top_plays with seen_plays, stores the differences in play_events and returns them.
+// the first run only takes note of every play, so the whole table isn't announced at once
+pub async fn detect(conn: &mut SqliteConnection) -> Result<Vec<PlayEvent>, ElodonError> {
+    let seen: Vec<(i64, u32, u32, u32, u32, u32)> = sqlx::query_as(
+        "SELECT user_id, song_id, level_id, score, crown, rank FROM seen_plays"
+    ).fetch_all(&mut *conn).await?;
+    let first_run = seen.is_empty();
+    let seen: HashMap<(i64, u32, u32), SeenPlay> = seen.into_iter()
+        .map(|(user, song, level, score, crown, rank)| ((user, song, level), SeenPlay { score, crown, rank }))
+        .collect();
+    let current = Play::fetch_all_or_empty(conn, GeneralFilter::new()).await?;
+    let changed = changed_plays(&seen, &current);
+
+    let detected_at = now();
+    let mut events = Vec::new();
+    if !first_run {
+        for (play, old) in &changed {
+            let chart = play.fetch_one_other::<Chart>(conn).await.ok();
+            let user = play.fetch_one_other::<User>(conn).await.ok();
+            let elo = user.and_then(|user| user.elo(play.level().into()));
+            events.push(PlayEvent {
+                user: play.user,
+                song: play.song,
+                level: play.level,
+                new: SeenPlay::from(play),
+                old: *old,
+                z: chart.and_then(|chart| elo::get_z_value(play.score, elo, &chart, 0.)),
+                detected_at,
+            });
+        }
+    }
+
+    let mut transaction = conn.begin().await?;
+    for (play, _) in &changed {
+        sqlx::query("INSERT OR REPLACE INTO seen_plays (user_id, song_id, level_id, score, crown, rank) VALUES (?,?,?,?,?,?)")
+            .bind(play.user)
+            .bind(play.song)
+            .bind(play.level)
+            .bind(play.score)
+            .bind(play.crown)
+            .bind(play.rank)
+            .execute(&mut *transaction).await?;
+    }
+    for event in &events {
+        sqlx::query(
+            "INSERT INTO play_events (user_id, song_id, level_id, score, crown, rank, old_score, old_crown, old_rank, z, detected_at) \
+                VALUES (?,?,?,?,?,?,?,?,?,?,?)"
+        ).bind(event.user)
+            .bind(event.song)
+            .bind(event.level)
+            .bind(event.new.score)
+            .bind(event.new.crown)
+            .bind(event.new.rank)
+            .bind(event.old.map(|old| old.score))
+            .bind(event.old.map(|old| old.crown))
+            .bind(event.old.map(|old| old.rank))
+            .bind(event.z)
+            .bind(event.detected_at)
+            .execute(&mut *transaction).await?;
+    }
+    transaction.commit().await?;
+    Ok(events)
+}
+
+pub async fn events_since(conn: &mut SqliteConnection, since: i64) -> Result<Vec<PlayEvent>, ElodonError> {
+    let rows: Vec<PlayEventRow> = sqlx::query_as(
+        "SELECT user_id, song_id, level_id, score, crown, rank, old_score, old_crown, old_rank, z, detected_at \
+            FROM play_events WHERE detected_at >= ? ORDER BY detected_at"
+    ).bind(since)
+        .fetch_all(conn).await?;
+    Ok(rows.into_iter().map(PlayEvent::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::fixture_connection;
+
+    #[test]
+    fn only_better_plays_change() {
+        let play = Play { user: 1, song: 2, level: 4, score: 900000, rank: 5, crown: 1, good_cnt: 0, ok_cnt: 0, bad_cnt: 0, combo_cnt: 0, roll_cnt: 0 };
+        let mut seen = HashMap::new();
+        assert_eq!(changed_plays(&seen, &[play]), vec![(play, None)]);
+
+        seen.insert((1, 2, 4), SeenPlay::from(&play));
+        assert!(changed_plays(&seen, &[play]).is_empty());
+
+        let crowned = Play { crown: 2, ..play };
+        assert_eq!(changed_plays(&seen, &[crowned]), vec![(crowned, Some(SeenPlay::from(&play)))]);
+        assert_eq!(rank_among([(1, 900000), (2, 950000), (3, 800000)], 1, 900000), 2);
+    }
+
+    #[tokio::test]
+    async fn personal_bests_are_found_once() {
+        let mut conn = fixture_connection().await;
+        assert!(detect(&mut conn).await.unwrap().is_empty());
+
+        let play: Play = sqlx::query_as("SELECT * FROM top_plays WHERE crown < 3 ORDER BY score LIMIT 1")
+            .fetch_one(&mut conn).await.unwrap();
+        sqlx::query("UPDATE top_plays SET score = ?, crown = 3 WHERE user_id = ? AND song_id = ? AND level_id = ?")
+            .bind(play.score + 1000)
+            .bind(play.user)
+            .bind(play.song)
+            .bind(play.level)
+            .execute(&mut conn).await.unwrap();
+
+        let events = detect(&mut conn).await.unwrap();
+        assert_eq!(events.len(), 1);
+        let event = events[0];
+        assert_eq!(event.old, Some(SeenPlay::from(&play)));
+        assert_eq!(event.new.score, play.score + 1000);
+        assert!(event.better_score() && event.better_crown() && !event.better_rank());
+        assert!(event.z.is_some());
+
+        assert!(detect(&mut conn).await.unwrap().is_empty());
+        assert_eq!(events_since(&mut conn, 0).await.unwrap(), events);
+    }
+}
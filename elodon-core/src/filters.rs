@@ -0,0 +1,467 @@
+use sqlx::{FromRow, SqliteConnection};
+use crate::error::ElodonError;
+use crate::structs::*;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use paste::paste;
+
+#[macro_export]
+macro_rules! map_no_rows {
+    ($result: ident : Vec[$return_type:literal], $id: expr) => {
+        match $result {
+            Ok(list) => {
+                if list.is_empty(){
+                    return Err(ElodonError::NoResults {
+                        search: format!("{}", $return_type),
+                        id: stringify!($id).to_string(),
+                    });
+                } else {
+                    return Ok(list);
+                }
+            },
+            Err(sqlx::Error::RowNotFound) => Err(ElodonError::NoResults {
+                    search: stringify!($return_type).to_string(),
+                    id: stringify!($id).to_string(),
+                }),
+            Err(err) => {Err(ElodonError::DatabaseError(err))}
+        }
+    };
+    ($result: ident : $return_type:literal, $id: expr) => {
+        $result.map_err(|err| {
+            match err {
+                sqlx::Error::RowNotFound => ElodonError::NoResults {
+                    search: stringify!($return_type).to_string(),
+                    id: $id.to_string(),
+                },
+                _ => {ElodonError::DatabaseError(err)}
+            }
+        })
+    };
+}
+
+macro_rules! create_search_filter {
+($filter:ident,
+    $($field:ident: $field_type:ty =>
+        ($query_format_string:literal, $($query_values:expr),*),
+        ($display_format_string:literal, $($display_values:expr),*)
+    ),+
+) => {
+paste!{
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct $filter {
+    $(
+    pub $field: Option<$field_type>,
+    )*
+}
+
+impl $filter {
+
+    pub fn new() ->$filter {
+        Self::default()
+    }
+
+    fn query_string(&self) -> Option<String>{
+        let mut response = String::new();
+        $(
+        if let Some($field) = self.$field {
+            let processed_query = format!($query_format_string, $($query_values),+);
+            response.push_str(&*format!("{} AND ", processed_query))
+        }
+        )*
+        if response == ""{
+            None
+        } else {
+            Some(response.strip_suffix(" AND ").unwrap().to_string())
+        }
+    }
+
+    $(
+    // the chaining query creation
+    pub fn [<set_ $field>](&mut self, $field: Option<$field_type>) {
+        self.$field = $field;
+    }
+    // the chaining query creation
+    pub fn $field(&self, $field: Option<$field_type>) -> $filter {
+        $filter{
+        $field: $field,
+        ..*self
+        }
+    }
+    )*
+}
+
+//the formatted search query
+impl Display for $filter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut response = " where ".to_string();
+        $(
+        if let Some($field) = self.$field {
+            let processed_query = format!($display_format_string, $($display_values),*);
+            response.push_str(&*format!("{} and ", processed_query))
+        }
+        )*
+        write!(f, "{}",
+            if response == " where "{
+                String::new()
+            } else {
+                response.strip_suffix(" and ").unwrap().to_string()
+            }
+        )
+    }
+}
+}};}
+macro_rules! create_search_filter_with_query_commands {
+($row:ident $filter: ident $table_name:literal $columns: literal $order: literal,
+    $($field:ident: $field_type:ty =>
+        ($query_format_string:literal, $($query_values:expr),*),
+        ($display_format_string:literal, $($display_values:expr),*)
+    ),+
+) => {
+paste!{
+
+create_search_filter!(
+    $filter,
+        $($field: $field_type =>
+            ($query_format_string,$($query_values),*),
+            ($display_format_string,$($display_values),*)
+        ),*
+);
+
+impl $filter{
+
+    //formatting into appending to and an sql query
+    fn get_search(&self, columns: &str) -> String {
+        match self.query_string(){
+            None =>
+                format!("SELECT {} FROM {}", columns, $table_name),
+            Some(query_string) =>
+                format!("SELECT {} FROM {} WHERE {}", columns, $table_name, query_string)
+        }
+    }
+}
+
+impl Filter<$row> for $filter {
+    async fn fetch_one(&self, conn: &mut SqliteConnection) -> Result<$row, ElodonError> {
+        let final_query = self.get_search($columns);
+        let value: Result<$row, sqlx::Error> = sqlx::query_as(&*final_query).fetch_one(conn).await;
+        map_no_rows!(value: $table_name, self)
+    }
+    async fn fetch_all(&self, conn: &mut SqliteConnection) -> Result<Vec<$row>, ElodonError>{
+        let final_query = self.get_search($columns);
+        let values: Result<Vec<$row>, sqlx::Error> = sqlx::query_as(&*final_query).fetch_all(conn).await;
+        map_no_rows!(values: $table_name, self)
+    }
+    async fn fetch_page(&self, conn: &mut SqliteConnection, offset: usize, limit: usize) -> Result<Vec<$row>, ElodonError>{
+        let final_query = format!("{} ORDER BY {} LIMIT ? OFFSET ?", self.get_search($columns), $order);
+        Ok(sqlx::query_as(&*final_query)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(conn).await?)
+    }
+    async fn fetch_all_or_empty(&self, conn: &mut SqliteConnection) -> Result<Vec<$row>, ElodonError>{
+        let final_query = format!("{} ORDER BY {}", self.get_search($columns), $order);
+        Ok(sqlx::query_as(&*final_query).fetch_all(conn).await?)
+    }
+    async fn count(&self, conn: &mut SqliteConnection) -> Result<usize, ElodonError>{
+        let final_query = self.get_search("COUNT(*)");
+        let (count,): (i64,) = sqlx::query_as(&*final_query).fetch_one(conn).await?;
+        Ok(count as usize)
+    }
+}
+
+impl Filterable for $row {
+    async fn fetch_one(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>) -> Result<Self, ElodonError>{
+        let general_filter: GeneralFilter = filter.into();
+        let specific_filter: $filter = general_filter.into();
+        specific_filter.fetch_one(conn).await
+    }
+    async fn fetch_all(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>) -> Result<Vec<Self>, ElodonError>{
+        let general_filter: GeneralFilter = filter.into();
+        let specific_filter: $filter = general_filter.into();
+        specific_filter.fetch_all(conn).await
+    }
+    async fn fetch_page(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>, offset: usize, limit: usize) -> Result<Vec<Self>, ElodonError>{
+        let general_filter: GeneralFilter = filter.into();
+        let specific_filter: $filter = general_filter.into();
+        specific_filter.fetch_page(conn, offset, limit).await
+    }
+    async fn fetch_all_or_empty(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>) -> Result<Vec<Self>, ElodonError>{
+        let general_filter: GeneralFilter = filter.into();
+        let specific_filter: $filter = general_filter.into();
+        specific_filter.fetch_all_or_empty(conn).await
+    }
+    async fn count(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>) -> Result<usize, ElodonError>{
+        let general_filter: GeneralFilter = filter.into();
+        let specific_filter: $filter = general_filter.into();
+        specific_filter.count(conn).await
+    }
+    fn unsupported(filter: impl Into<GeneralFilter>) -> GeneralFilter {
+        let mut rest: GeneralFilter = filter.into();
+        $(
+        rest.$field = None;
+        )*
+        rest
+    }
+}
+
+impl From<$filter> for GeneralFilter{
+    fn from(value: $filter) -> GeneralFilter {
+        GeneralFilter{
+            $(
+            $field: value.$field,
+            )*
+            ..Default::default()
+        }
+    }
+}
+
+impl From<GeneralFilter> for $filter{
+    fn from(value: GeneralFilter) -> $filter {
+        $filter{
+            $(
+            $field: value.$field,
+            )*
+            ..Default::default()
+        }
+    }
+}
+}};}
+
+
+trait Filter<R> {
+    async fn fetch_one(&self, conn: &mut SqliteConnection) -> Result<R, ElodonError>;
+    async fn fetch_all(&self, conn: &mut SqliteConnection) -> Result<Vec<R>, ElodonError>;
+    async fn fetch_page(&self, conn: &mut SqliteConnection, offset: usize, limit: usize) -> Result<Vec<R>, ElodonError>;
+    async fn fetch_all_or_empty(&self, conn: &mut SqliteConnection) -> Result<Vec<R>, ElodonError>;
+    async fn count(&self, conn: &mut SqliteConnection) -> Result<usize, ElodonError>;
+}
+
+pub trait Filterable: Clone + Sized{
+    async fn fetch_one(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>) -> Result<Self, ElodonError>;
+    async fn fetch_all(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>) -> Result<Vec<Self>, ElodonError>;
+    // in a stable order so pages don't overlap. unlike fetch_all an empty page is not an error
+    async fn fetch_page(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>, offset: usize, limit: usize) -> Result<Vec<Self>, ElodonError>;
+    // every row in the order of fetch_page, for callers that go through the whole table
+    async fn fetch_all_or_empty(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>) -> Result<Vec<Self>, ElodonError>;
+    async fn count(conn: &mut SqliteConnection, filter: impl Into<GeneralFilter>) -> Result<usize, ElodonError>;
+    // the fields of filter this table has no column for, which the fetches above leave out
+    fn unsupported(filter: impl Into<GeneralFilter>) -> GeneralFilter;
+}
+
+
+pub trait FetchOne<R: Filterable>: Into<GeneralFilter> {
+    async fn fetch_one_other<B: From<R>>(self, conn: &mut SqliteConnection) -> Result<B, ElodonError>{
+        R::fetch_one(conn, self).await.map(|r| B::from(r))
+    }
+}
+
+pub trait FetchAll<R: Filterable>: Into<GeneralFilter> {
+    async fn fetch_all_other<B: From<R>>(self, conn: &mut SqliteConnection) -> Result<Vec<B>, ElodonError>{
+        Ok(R::fetch_all(conn, self).await?.iter().cloned().map(|r| B::from(r)).collect())
+    }
+}
+
+//used for printing the filter. sort of a dud class
+
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "poise", derive(poise::ChoiceParameter))]
+pub enum FilterType{
+    User,
+    Song,
+    Chart,
+    Play
+}
+
+impl FilterType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterType::User => "users",
+            FilterType::Song => "songs",
+            FilterType::Chart => "charts",
+            FilterType::Play => "plays",
+        }
+    }
+}
+
+impl FromStr for FilterType {
+    type Err = ElodonError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let types = [FilterType::User, FilterType::Song, FilterType::Chart, FilterType::Play];
+        parse_named(text, &types, FilterType::name, |table| *table as u32 + 1)
+    }
+}
+
+create_search_filter! (
+    GeneralFilter,
+    user_id: i64 =>
+        ("user_id = {}", user_id),
+        ("[user ids hidden]",),
+    discord_id: u64  =>
+        ("discord_id = {}", discord_id),
+        ("discord <@{}>", discord_id),
+    song_id: u32 =>
+        ("song_id = {}", song_id),
+        ("song_id is {}", song_id),
+    level: Level =>
+        ("level_id = {}", level.id()),
+        ("level_id is {}", level.id()),
+    display_level: DisplayLevel =>
+        ("level_id BETWEEN {} AND {}", display_level.min_value(), display_level.max_value()),
+        ("level is {}", display_level.name()),
+    genre: Genre =>
+        ("genre_id = {}", genre.id()),
+        ("genre is {}", genre.name()),
+    guild_id: u64 =>
+        ("user_id IN (SELECT users.user_id FROM users JOIN guild_members ON guild_members.discord_id = users.discord_id WHERE guild_members.guild_id = {})", guild_id),
+        ("members of server {}", guild_id)
+);
+
+create_search_filter_with_query_commands!(
+    User UserFilter "users" "user_id, discord_id, user_name, elo1, elo2, elo3, elo4" "user_id",
+    user_id: i64 =>
+        ("user_id = {}", user_id),
+        ("[user ids hidden]",),
+    discord_id: u64  =>
+        ("discord_id = {}", discord_id),
+        ("discord <@{}>", discord_id),
+    guild_id: u64 =>
+        ("discord_id IN (SELECT discord_id FROM guild_members WHERE guild_id = {})", guild_id),
+        ("members of server {}", guild_id)
+);
+
+create_search_filter_with_query_commands!(
+    Song SongFilter "songs" "song_id, song_name_eng, song_name_jap, genre_id" "song_id",
+    song_id: u32 =>
+        ("song_id = {}", song_id),
+        ("song_id is {}", song_id),
+    genre: Genre =>
+        ("genre_id = {}", genre.id()),
+        ("genre is {}", genre.name())
+);
+
+create_search_filter_with_query_commands!(
+    Chart ChartFilter "charts" "song_id, level_id, score_slope, score_miyabi, sd_mean, sd_sd" "song_id, level_id",
+    song_id: u32 =>
+        ("song_id = {}", song_id),
+        ("song_id is {}", song_id),
+    level: Level =>
+        ("level_id = {}", level.id()),
+        ("level_id is {}", level.id()),
+    display_level: DisplayLevel =>
+        ("level_id BETWEEN {} AND {}", display_level.min_value(), display_level.max_value()),
+        ("level is {}", display_level.name()),
+    genre: Genre =>
+        ("song_id IN (SELECT song_id FROM songs WHERE genre_id = {})", genre.id()),
+        ("genre is {}", genre.name())
+);
+
+create_search_filter_with_query_commands!(
+    Play PlayFilter "top_plays" "user_id, song_id, level_id, score, good_cnt, ok_cnt, bad_cnt, combo_cnt, roll_cnt, rank, crown" "song_id, level_id, score DESC, user_id",
+    user_id: i64 =>
+        ("user_id = {}", user_id),
+        ("[user ids hidden]",),
+    song_id: u32 =>
+        ("song_id = {}", song_id),
+        ("song_id is {}", song_id),
+    level: Level =>
+        ("level_id = {}", level.id()),
+        ("level_id is {}", level.id()),
+    display_level: DisplayLevel =>
+        ("level_id BETWEEN {} AND {}", display_level.min_value(), display_level.max_value()),
+        ("level is {}", display_level.name()),
+    genre: Genre =>
+        ("song_id IN (SELECT song_id FROM songs WHERE genre_id = {})", genre.id()),
+        ("genre is {}", genre.name()),
+    guild_id: u64 =>
+        ("user_id IN (SELECT users.user_id FROM users JOIN guild_members ON guild_members.discord_id = users.discord_id WHERE guild_members.guild_id = {})", guild_id),
+        ("members of server {}", guild_id)
+);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::fixture_connection;
+
+    #[test]
+    fn filters_describe_themselves() {
+        assert_eq!(GeneralFilter::new().to_string(), "");
+        let filter = GeneralFilter::new()
+            .song_id(Some(3))
+            .display_level(Some(DisplayLevel::OniPlus))
+            .user_id(Some(5));
+        assert_eq!(filter.to_string(), " where [user ids hidden] and song_id is 3 and level is Oni+");
+    }
+
+    #[tokio::test]
+    async fn specific_filters_ignore_other_fields() {
+        let mut conn = fixture_connection().await;
+        // songs have no level, so only song_id applies
+        let songs = Song::fetch_all(&mut conn, GeneralFilter::new().song_id(Some(3)).level(Some(Level::Ura))).await.unwrap();
+        assert_eq!(songs.len(), 1);
+        let charts = Chart::fetch_all(&mut conn, GeneralFilter::new().song_id(Some(3)).display_level(Some(DisplayLevel::OniPlus))).await.unwrap();
+        assert_eq!(charts.iter().map(|chart| chart.level).collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(Song::unsupported(GeneralFilter::new().song_id(Some(3)).level(Some(Level::Ura))), GeneralFilter::new().level(Some(Level::Ura)));
+        assert_eq!(Chart::unsupported(GeneralFilter::new().song_id(Some(3)).genre(Some(Genre::Anime))), GeneralFilter::new());
+    }
+
+    #[tokio::test]
+    async fn charts_and_plays_filter_by_genre() {
+        let mut conn = fixture_connection().await;
+        let filter = GeneralFilter::new().genre(Some(Genre::Anime));
+        let songs: Vec<u32> = Song::fetch_all(&mut conn, filter).await.unwrap().iter().map(|song| song.id).collect();
+        let charts = Chart::fetch_all(&mut conn, filter).await.unwrap();
+        assert!(!charts.is_empty());
+        assert!(charts.iter().all(|chart| songs.contains(&chart.id)));
+        let plays = Play::fetch_all(&mut conn, filter).await.unwrap();
+        assert!(!plays.is_empty());
+        assert!(plays.iter().all(|play| songs.contains(&play.song)));
+        assert!(Play::count(&mut conn, filter).await.unwrap() < Play::count(&mut conn, GeneralFilter::new()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn missing_rows() {
+        let mut conn = fixture_connection().await;
+        let filter = GeneralFilter::new().display_level(Some(DisplayLevel::Easy));
+        assert!(matches!(Play::fetch_one(&mut conn, filter).await, Err(ElodonError::NoResults { .. })));
+        assert!(Play::fetch_all(&mut conn, filter).await.unwrap().is_empty());
+        assert_eq!(Play::count(&mut conn, filter).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn guild_scope_is_filtered_in_sql() {
+        let mut conn = fixture_connection().await;
+        // fixture players 0 and 1
+        for discord_id in [900000000000000000i64, 900000000000000001] {
+            sqlx::query("INSERT INTO guild_members (guild_id, discord_id) VALUES (1, ?)")
+                .bind(discord_id)
+                .execute(&mut conn).await.unwrap();
+        }
+        let members = User::fetch_all(&mut conn, UserFilter::new().guild_id(Some(1))).await.unwrap();
+        assert_eq!(members.iter().map(|user| user.discord_id()).collect::<Vec<_>>(), vec![900000000000000000, 900000000000000001]);
+
+        let filter = GeneralFilter::new().song_id(Some(1)).guild_id(Some(1));
+        let plays = Play::fetch_all(&mut conn, filter).await.unwrap();
+        assert!(!plays.is_empty());
+        assert!(plays.iter().all(|play| members.iter().any(|user| user.id == play.user)));
+        assert!(Play::fetch_all(&mut conn, filter.guild_id(Some(2))).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pages_add_up_to_everything() {
+        let mut conn = fixture_connection().await;
+        let filter = GeneralFilter::new().song_id(Some(2));
+        let all = Play::fetch_all(&mut conn, filter).await.unwrap();
+        assert_eq!(Play::count(&mut conn, filter).await.unwrap(), all.len());
+
+        let mut paged = vec![];
+        for offset in (0..all.len() + 5).step_by(5) {
+            paged.extend(Play::fetch_page(&mut conn, filter, offset, 5).await.unwrap());
+        }
+        assert_eq!(paged.len(), all.len());
+        assert!(paged.windows(2).all(|pair| (pair[0].level, -(pair[0].score as i64)) <= (pair[1].level, -(pair[1].score as i64))));
+        assert!(Play::fetch_page(&mut conn, filter, all.len(), 5).await.unwrap().is_empty());
+        assert_eq!(Play::fetch_all_or_empty(&mut conn, filter).await.unwrap(), paged);
+        assert!(Play::fetch_all_or_empty(&mut conn, filter.song_id(Some(0))).await.unwrap().is_empty());
+    }
+}
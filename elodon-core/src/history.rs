@@ -0,0 +1,64 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sqlx::{FromRow, SqliteConnection};
+
+use crate::error::ElodonError;
+use crate::structs::DisplayLevel;
+
+// the scraper overwrites elo1..elo4, so the bot keeps its own history of them
+pub const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60 * 60 * 6);
+
+#[derive(Clone, Debug, FromRow)]
+pub struct EloSnapshot {
+    pub recorded_at: i64,
+    pub elo1: Option<f32>,
+    pub elo2: Option<f32>,
+    pub elo3: Option<f32>,
+    pub elo4: Option<f32>,
+}
+
+impl EloSnapshot {
+    pub fn elo(&self, level: DisplayLevel) -> Option<f32> {
+        match level {
+            DisplayLevel::Easy => self.elo1,
+            DisplayLevel::Med => self.elo2,
+            DisplayLevel::Hard => self.elo3,
+            DisplayLevel::OniPlus => self.elo4,
+        }
+    }
+}
+
+pub fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs() as i64).unwrap_or(0)
+}
+
+// adds a row for every user whose elo changed since their last one
+pub async fn record_elo(conn: &mut SqliteConnection) -> Result<u64, ElodonError> {
+    let result = sqlx::query(
+        "INSERT INTO elo_history (user_id, recorded_at, elo1, elo2, elo3, elo4) \
+            SELECT u.user_id, ?, u.elo1, u.elo2, u.elo3, u.elo4 FROM users u \
+            WHERE NOT EXISTS (SELECT 1 FROM elo_history h WHERE h.user_id = u.user_id \
+                AND h.recorded_at = (SELECT MAX(recorded_at) FROM elo_history WHERE user_id = u.user_id) \
+                AND h.elo1 IS u.elo1 AND h.elo2 IS u.elo2 AND h.elo3 IS u.elo3 AND h.elo4 IS u.elo4)"
+    ).bind(now())
+        .execute(conn).await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn elo_history(conn: &mut SqliteConnection, user_id: i64) -> Result<Vec<EloSnapshot>, ElodonError> {
+    Ok(sqlx::query_as("SELECT recorded_at, elo1, elo2, elo3, elo4 FROM elo_history WHERE user_id = ? ORDER BY recorded_at")
+        .bind(user_id)
+        .fetch_all(conn).await?)
+}
+
+// (days ago, level, elo) for every known elo, what render::elo_history draws
+pub fn history_points(history: &[EloSnapshot]) -> Vec<(f64, DisplayLevel, f32)> {
+    let now = now();
+    let levels = [DisplayLevel::Easy, DisplayLevel::Med, DisplayLevel::Hard, DisplayLevel::OniPlus];
+    history.iter()
+        .flat_map(|snapshot| levels.into_iter().filter_map(move |level| {
+            let days = (now - snapshot.recorded_at) as f64 / (60. * 60. * 24.);
+            Some((days, level, snapshot.elo(level)?))
+        }))
+        .collect()
+}
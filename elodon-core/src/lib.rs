@@ -0,0 +1,18 @@
+// the data layer shared by the bot and anything else built on taiko.db: the scraper's tables as
+// structs, the filters to query them, and the rating maths. nothing in here talks to discord
+
+// the Filterable futures are only ever awaited where they're made, they don't need to be Send
+#![allow(async_fn_in_trait)]
+
+pub mod challenges;
+pub mod digest;
+pub mod display;
+pub mod elo;
+pub mod error;
+pub mod eval;
+pub mod events;
+pub mod filters;
+pub mod history;
+pub mod records;
+pub mod schema;
+pub mod structs;
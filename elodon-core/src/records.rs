@@ -0,0 +1,156 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{Connection, FromRow, SqliteConnection};
+
+use crate::error::ElodonError;
+use crate::filters::*;
+use crate::history::now;
+use crate::structs::*;
+
+// first place on each chart among the players of one server. the scraper doesn't know about
+// servers, so records are worked out from top_plays and kept in guild_records to see them change hands
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, FromRow)]
+pub struct Record {
+    pub guild_id: i64,
+    pub song_id: u32,
+    pub level_id: u32,
+    pub user_id: i64,
+    pub score: u32,
+    // when user_id took it, improving your own record doesn't change it
+    pub held_since: i64,
+}
+
+impl Record {
+    pub fn level(&self) -> Level {
+        Level::try_from(self.level_id).unwrap()
+    }
+
+    pub fn chart(&self) -> GeneralFilter {
+        GeneralFilter::new().song_id(Some(self.song_id)).level(Some(self.level()))
+    }
+}
+
+// a record someone else beat
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TakenRecord {
+    pub record: Record,
+    pub previous: Record,
+}
+
+// (user, score) of the best play per (song, level) among players. on a tie whoever holds the
+// record keeps it, otherwise the lower donder id wins so the holder doesn't flip between runs
+pub fn holders(plays: &[Play], players: &HashSet<i64>, held: &HashMap<(u32, u32), Record>) -> HashMap<(u32, u32), (i64, u32)> {
+    let mut best: HashMap<(u32, u32), (i64, u32)> = HashMap::new();
+    for play in plays.iter().filter(|play| players.contains(&play.user)) {
+        let chart = (play.song, play.level);
+        let holder = held.get(&chart).map(|record| record.user_id);
+        let key = |user: i64, score: u32| (score, Some(user) == holder, Reverse(user));
+        match best.get(&chart) {
+            Some(&(user, score)) if key(user, score) >= key(play.user, play.score) => {}
+            _ => {
+                best.insert(chart, (play.user, play.score));
+            }
+        }
+    }
+    best
+}
+
+pub async fn guild_records(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<Record>, ElodonError> {
+    Ok(sqlx::query_as(
+        "SELECT guild_id, song_id, level_id, user_id, score, held_since FROM guild_records \
+            WHERE guild_id = ? ORDER BY song_id, level_id"
+    ).bind(guild_id)
+        .fetch_all(conn).await?)
+}
+
+// brings the records of one server up to date with plays, the whole top_plays table, and returns
+// the ones that were beaten. the first run for a server only fills in its records
+pub async fn update_records(conn: &mut SqliteConnection, guild_id: i64, plays: &[Play], players: &HashSet<i64>) -> Result<Vec<TakenRecord>, ElodonError> {
+    let held: HashMap<(u32, u32), Record> = guild_records(conn, guild_id).await?
+        .into_iter()
+        .map(|record| ((record.song_id, record.level_id), record))
+        .collect();
+    let best = holders(plays, players, &held);
+    let now = now();
+
+    let mut taken = vec![];
+    let mut transaction = conn.begin().await?;
+    for (&(song_id, level_id), &(user_id, score)) in &best {
+        let previous = held.get(&(song_id, level_id));
+        let record = match previous {
+            Some(previous) if previous.user_id == user_id && previous.score == score => continue,
+            Some(previous) if previous.user_id == user_id => Record { score, ..*previous },
+            _ => Record { guild_id, song_id, level_id, user_id, score, held_since: now },
+        };
+        // a holder leaving the server hands the record on without anyone beating it
+        if let Some(previous) = previous.filter(|previous| previous.user_id != user_id && score > previous.score) {
+            taken.push(TakenRecord { record, previous: *previous });
+        }
+        sqlx::query("INSERT OR REPLACE INTO guild_records (guild_id, song_id, level_id, user_id, score, held_since) VALUES (?,?,?,?,?,?)")
+            .bind(record.guild_id)
+            .bind(record.song_id)
+            .bind(record.level_id)
+            .bind(record.user_id)
+            .bind(record.score)
+            .bind(record.held_since)
+            .execute(&mut *transaction).await?;
+    }
+    // nobody in the server has played these anymore
+    for (song_id, level_id) in held.keys().filter(|chart| !best.contains_key(chart)) {
+        sqlx::query("DELETE FROM guild_records WHERE guild_id = ? AND song_id = ? AND level_id = ?")
+            .bind(guild_id)
+            .bind(song_id)
+            .bind(level_id)
+            .execute(&mut *transaction).await?;
+    }
+    transaction.commit().await?;
+    Ok(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::fixture_connection;
+
+    const GUILD: i64 = 1;
+
+    #[tokio::test]
+    async fn records_change_hands() {
+        let mut conn = fixture_connection().await;
+        let plays = Play::fetch_all_or_empty(&mut conn, GeneralFilter::new()).await.unwrap();
+        let players: HashSet<i64> = plays.iter().map(|play| play.user).collect();
+        assert!(update_records(&mut conn, GUILD, &plays, &players).await.unwrap().is_empty());
+        let records = guild_records(&mut conn, GUILD).await.unwrap();
+        let charts: HashSet<(u32, u32)> = plays.iter().map(|play| (play.song, play.level)).collect();
+        assert_eq!(records.len(), charts.len());
+        assert!(update_records(&mut conn, GUILD, &plays, &players).await.unwrap().is_empty());
+
+        // someone else beats a record
+        let (record, challenger) = records.iter()
+            .find_map(|record| plays.iter()
+                .find(|play| (play.song, play.level) == (record.song_id, record.level_id) && play.user != record.user_id)
+                .map(|play| (*record, *play)))
+            .unwrap();
+        let beaten: Vec<Play> = plays.iter()
+            .map(|play| if *play == challenger { Play { score: record.score + 10, ..*play } } else { *play })
+            .collect();
+        let taken = update_records(&mut conn, GUILD, &beaten, &players).await.unwrap();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].previous, record);
+        assert_eq!(taken[0].record.user_id, challenger.user);
+        assert_eq!(taken[0].record.score, record.score + 10);
+
+        // the new holder leaving gives it back without a notification
+        let remaining: HashSet<i64> = players.iter().copied().filter(|player| *player != challenger.user).collect();
+        assert!(update_records(&mut conn, GUILD, &beaten, &remaining).await.unwrap().is_empty());
+        let back = guild_records(&mut conn, GUILD).await.unwrap().into_iter()
+            .find(|back| (back.song_id, back.level_id) == (record.song_id, record.level_id))
+            .unwrap();
+        assert_eq!((back.user_id, back.score), (record.user_id, record.score));
+
+        // other servers are separate
+        assert!(guild_records(&mut conn, GUILD + 1).await.unwrap().is_empty());
+    }
+}
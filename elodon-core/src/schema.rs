@@ -0,0 +1,194 @@
+use sqlx::{Executor, SqliteConnection};
+
+use crate::error::ElodonError;
+
+// tables owned by the bot. users/songs/charts/top_plays are owned by the scraper
+const BOT_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS song_aliases (
+    song_id  INTEGER NOT NULL,
+    alias    TEXT    NOT NULL COLLATE NOCASE,
+    added_by INTEGER NOT NULL,
+    PRIMARY KEY (song_id, alias)
+);
+
+-- pending /register and /relink requests. the scraper fills profile_name with the
+-- in-game name of donder_id, which has to contain code for the link to be confirmed
+CREATE TABLE IF NOT EXISTS link_requests (
+    donder_id    INTEGER NOT NULL,
+    discord_id   INTEGER NOT NULL,
+    code         TEXT    NOT NULL,
+    relink       INTEGER NOT NULL DEFAULT 0,
+    profile_name TEXT,
+    created_at   INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (donder_id, discord_id)
+);
+
+-- /config. NULL columns mean the default is used
+CREATE TABLE IF NOT EXISTS guild_settings (
+    guild_id            INTEGER PRIMARY KEY,
+    prefix              TEXT,
+    reaction_trigger    TEXT,
+    default_level       INTEGER,
+    leaderboard_scope   INTEGER,
+    alias_role_id       INTEGER,
+    announce_channel_id INTEGER,
+    digest_channel_id   INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS guild_bans (
+    guild_id   INTEGER NOT NULL,
+    discord_id INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, discord_id)
+);
+
+-- emoji registry. guild_id 0 overrides the built-in default everywhere.
+-- either emoji_id (custom emoji) or text (unicode/plain text) is set
+CREATE TABLE IF NOT EXISTS emojis (
+    guild_id INTEGER NOT NULL DEFAULT 0,
+    name     TEXT    NOT NULL,
+    emoji_id INTEGER,
+    animated INTEGER NOT NULL DEFAULT 0,
+    text     TEXT,
+    PRIMARY KEY (guild_id, name)
+);
+
+-- elo1..elo4 whenever they changed, the scraper only keeps the current values
+CREATE TABLE IF NOT EXISTS elo_history (
+    user_id     INTEGER NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    elo1        REAL,
+    elo2        REAL,
+    elo3        REAL,
+    elo4        REAL,
+    PRIMARY KEY (user_id, recorded_at)
+);
+
+-- which of several linked donder ids is used by default
+CREATE TABLE IF NOT EXISTS primary_accounts (
+    discord_id INTEGER PRIMARY KEY,
+    user_id    INTEGER NOT NULL
+);
+
+-- top_plays as it was when the bot last compared, see events.rs
+CREATE TABLE IF NOT EXISTS seen_plays (
+    user_id  INTEGER NOT NULL,
+    song_id  INTEGER NOT NULL,
+    level_id INTEGER NOT NULL,
+    score    INTEGER NOT NULL,
+    crown    INTEGER NOT NULL,
+    rank     INTEGER NOT NULL,
+    PRIMARY KEY (user_id, song_id, level_id)
+);
+
+-- every new or improved play. old_* are NULL for first plays of a chart
+CREATE TABLE IF NOT EXISTS play_events (
+    user_id     INTEGER NOT NULL,
+    song_id     INTEGER NOT NULL,
+    level_id    INTEGER NOT NULL,
+    score       INTEGER NOT NULL,
+    crown       INTEGER NOT NULL,
+    rank        INTEGER NOT NULL,
+    old_score   INTEGER,
+    old_crown   INTEGER,
+    old_rank    INTEGER,
+    z           REAL,
+    detected_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS play_events_detected_at ON play_events (detected_at);
+
+-- discord members of each server the bot is in, kept up to date from gateway events so scoped
+-- queries can filter in sql instead of needing the whole member list in the cache. synced_at is
+-- the member list request (or the join) the row was last seen in, see members.rs
+CREATE TABLE IF NOT EXISTS guild_members (
+    guild_id   INTEGER NOT NULL,
+    discord_id INTEGER NOT NULL,
+    synced_at  INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (guild_id, discord_id)
+);
+
+-- who holds first place on each chart among a server's players, see records.rs
+CREATE TABLE IF NOT EXISTS guild_records (
+    guild_id   INTEGER NOT NULL,
+    song_id    INTEGER NOT NULL,
+    level_id   INTEGER NOT NULL,
+    user_id    INTEGER NOT NULL,
+    score      INTEGER NOT NULL,
+    held_since INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, song_id, level_id)
+);
+CREATE INDEX IF NOT EXISTS guild_records_user ON guild_records (guild_id, user_id);
+
+-- /challenge. the charts are picked when it's created so they don't change during it
+CREATE TABLE IF NOT EXISTS challenges (
+    challenge_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id     INTEGER NOT NULL,
+    name         TEXT    NOT NULL,
+    starts_at    INTEGER NOT NULL,
+    ends_at      INTEGER NOT NULL,
+    created_by   INTEGER NOT NULL,
+    -- set once the winners were announced
+    finished     INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS challenge_charts (
+    challenge_id INTEGER NOT NULL,
+    song_id      INTEGER NOT NULL,
+    level_id     INTEGER NOT NULL,
+    PRIMARY KEY (challenge_id, song_id, level_id)
+);
+
+-- when each of the bot's scheduled jobs last ran, so restarts don't repeat them
+CREATE TABLE IF NOT EXISTS scheduled_runs (
+    job      TEXT PRIMARY KEY,
+    last_run INTEGER NOT NULL
+);
+";
+
+// columns added to a table after it was first created, which CREATE TABLE IF NOT EXISTS
+// doesn't do for databases that already have the table
+const ADDED_COLUMNS: [(&str, &str, &str); 3] = [
+    ("guild_settings", "announce_channel_id", "INTEGER"),
+    ("guild_settings", "digest_channel_id", "INTEGER"),
+    ("guild_members", "synced_at", "INTEGER NOT NULL DEFAULT 0"),
+];
+
+pub async fn create_tables(conn: &mut SqliteConnection) -> Result<(), ElodonError> {
+    conn.execute(BOT_TABLES).await?;
+    for (table, column, definition) in ADDED_COLUMNS {
+        let (exists,): (bool,) = sqlx::query_as("SELECT COUNT(*) > 0 FROM pragma_table_info(?) WHERE name = ?")
+            .bind(table)
+            .bind(column)
+            .fetch_one(&mut *conn).await?;
+        if !exists {
+            conn.execute(&*format!("ALTER TABLE {table} ADD COLUMN {column} {definition}")).await?;
+        }
+    }
+    Ok(())
+}
+
+// an in-memory database with the scraper's tables, the bot's tables and the bundled fixture data
+#[cfg(any(test, feature = "fixtures"))]
+pub async fn fixture_connection() -> SqliteConnection {
+    use sqlx::Connection;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+    conn.execute(include_str!("../fixtures/schema.sql")).await.unwrap();
+    create_tables(&mut conn).await.unwrap();
+    conn.execute(include_str!("../fixtures/seed.sql")).await.unwrap();
+    conn
+}
+
+// fixture_connection as a pool. it has the one connection, an in-memory database is gone once that closes
+#[cfg(any(test, feature = "fixtures"))]
+pub async fn fixture_pool() -> sqlx::SqlitePool {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:").await.unwrap();
+    let mut conn = pool.acquire().await.unwrap();
+    conn.execute(include_str!("../fixtures/schema.sql")).await.unwrap();
+    create_tables(&mut conn).await.unwrap();
+    conn.execute(include_str!("../fixtures/seed.sql")).await.unwrap();
+    pool
+}
@@ -1,45 +1,22 @@
 use std::default::Default;
 use std::fmt::{Display, Formatter};
 use std::num::NonZeroU64;
-use itertools::Itertools;
+use std::str::FromStr;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 
-use poise::ChoiceParameter;
-use poise::serenity_prelude::UserId;
 use sqlx::{Connection, Error, FromRow, Row, SqliteConnection};
 
 use paste::paste;
-use crate::emoji::{CROWN_IDS, RANK_IDS};
 
 use crate::error::ElodonError;
 use crate::filters::*;
 use crate::map_no_rows;
 
-pub trait ElodonDisplay{
-    fn get_display_text(&self) -> String;
-
-}
-
-pub trait ElodonDisplayList<E>{
-    fn get_display_text(&self) -> String;
-}
-
-impl<E: ElodonDisplay> ElodonDisplayList<E> for Vec<E>{
-    fn get_display_text(&self) -> String {
-        return if self.is_empty() {
-            "No results".to_string()
-        } else {
-            self.iter()
-                .map(|e| e.get_display_text())
-                .join("\n")
-                .to_string()
-        }
-    }
-}
 
 // USER
 
 #[derive(Clone, FromRow)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct User{
     #[sqlx(rename = "user_id")]
     pub id: i64,
@@ -54,8 +31,8 @@ pub struct User{
 }
 
 impl User {
-    pub fn discord_id(&self) -> UserId{
-        return UserId::new(self.discord as u64)
+    pub fn discord_id(&self) -> u64{
+        return self.discord as u64
     }
 
     pub fn elo(&self, level: DisplayLevel) -> Option<f32>{
@@ -69,11 +46,6 @@ impl User {
 
 }
 
-impl ElodonDisplay for User{
-    fn get_display_text(&self) -> String {
-        return format!("`#{:<13}  {:<9}`<@{}>", self.id, self.name, self.discord)
-    }
-}
 
 impl FetchAll<Play> for User{}
 
@@ -87,7 +59,10 @@ impl From<User> for GeneralFilter{
 
 // SONG
 
+pub const MAX_ALIAS_LENGTH: usize = 50;
+
 #[derive(Clone, Eq, PartialEq, Hash, FromRow)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Song{
     #[sqlx(rename = "song_id")]
     pub id: u32,
@@ -111,28 +86,66 @@ impl Song {
         }
     }
 
-    pub async fn get_matching(conn: &mut SqliteConnection, fragment: &str, genre: Option<Genre>) -> Result<Vec<Song>, ElodonError> {
+    pub async fn get_matching(conn: &mut SqliteConnection, fragment: &str, genre: Option<Genre>, level: Option<Level>) -> Result<Vec<Song>, ElodonError> {
         let wrapped_fragment = format!("%{fragment}%");
 
-        let songs: Result<Vec<Song>, Error> = match genre {
-            None => {
-                sqlx::query_as(
-                    "SELECT song_id, song_name_eng, song_name_jap, genre_id FROM songs WHERE song_name_eng like ?\
-                         UNION \
-                         SELECT song_id, song_name_eng, song_name_jap, genre_id FROM songs WHERE song_name_jap like ?"
-                ).bind(&wrapped_fragment).bind(&wrapped_fragment)
-            }
-            Some(genre) => {
-                sqlx::query_as(
-                    "SELECT song_id, song_name_eng, song_name_jap, genre_id FROM songs WHERE song_name_eng like ? AND genre_id = ?\
-                          UNION \
-                         SELECT song_id, song_name_eng, song_name_jap, genre_id FROM songs WHERE song_name_jap like ? AND genre_id = ?"
-                ).bind(&wrapped_fragment).bind(genre.id()).bind(&wrapped_fragment).bind(genre.id())
-            }
-        }.fetch_all(conn).await;
+        let mut query = "SELECT song_id, song_name_eng, song_name_jap, genre_id FROM songs \
+            WHERE (song_name_eng like ? OR song_name_jap like ? \
+                OR song_id IN (SELECT song_id FROM song_aliases WHERE alias like ?))".to_string();
+        if let Some(genre) = genre {
+            query.push_str(&*format!(" AND genre_id = {}", genre.id()));
+        }
+        // only songs that actually have the requested difficulty (e.g. an ura)
+        if let Some(level) = level {
+            query.push_str(&*format!(" AND song_id IN (SELECT song_id FROM charts WHERE level_id = {})", level.id()));
+        }
+        query.push_str(" ORDER BY song_id");
+
+        let songs: Result<Vec<Song>, Error> = sqlx::query_as(&*query)
+            .bind(&wrapped_fragment)
+            .bind(&wrapped_fragment)
+            .bind(&wrapped_fragment)
+            .fetch_all(conn).await;
         map_no_rows!(songs: Vec["song"], fragment)
     }
 
+    pub async fn get_aliases(&self, conn: &mut SqliteConnection) -> Result<Vec<String>, ElodonError> {
+        let aliases: Vec<(String,)> = sqlx::query_as("SELECT alias FROM song_aliases WHERE song_id = ? ORDER BY alias")
+            .bind(self.id)
+            .fetch_all(conn).await?;
+        Ok(aliases.into_iter().map(|(alias,)| alias).collect())
+    }
+
+    pub async fn add_alias(&self, conn: &mut SqliteConnection, alias: &str, added_by: u64) -> Result<(), ElodonError> {
+        let result = sqlx::query("INSERT INTO song_aliases (song_id, alias, added_by) VALUES (?,?,?)")
+            .bind(self.id)
+            .bind(alias)
+            .bind(added_by as i64)
+            .execute(conn).await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(err)) if err.is_unique_violation() => Err(ElodonError::AliasExists {
+                alias: alias.to_string(),
+                song: self.get_name(),
+            }),
+            Err(err) => Err(ElodonError::DatabaseError(err))
+        }
+    }
+
+    pub async fn remove_alias(&self, conn: &mut SqliteConnection, alias: &str) -> Result<(), ElodonError> {
+        let result = sqlx::query("DELETE FROM song_aliases WHERE song_id = ? AND alias = ?")
+            .bind(self.id)
+            .bind(alias)
+            .execute(conn).await?;
+        if result.rows_affected() == 0 {
+            return Err(ElodonError::NoResults {
+                search: "song aliases".to_string(),
+                id: format!("alias is \"{alias}\""),
+            });
+        }
+        Ok(())
+    }
+
     pub fn genre(&self) -> Genre {
         return Genre::try_from(self.genre).unwrap();
     }
@@ -145,11 +158,6 @@ impl Display for Song {
     }
 }
 
-impl ElodonDisplay for Song{
-    fn get_display_text(&self) -> String {
-        return format!("`#{:<4}  {} > {}`", self.id, self.genre(), self.get_name())
-    }
-}
 
 impl FetchAll<Chart> for Song{}
 impl FetchAll<Play> for Song{}
@@ -165,7 +173,8 @@ impl From<Song> for GeneralFilter{
 
 // LEVEL
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, ChoiceParameter, TryFromPrimitive, IntoPrimitive)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "poise", derive(poise::ChoiceParameter))]
 #[num_enum(error_type(name = ElodonError, constructor = ElodonError::WrongLevelId))]
 #[repr(u32)]
 pub enum Level{
@@ -180,27 +189,52 @@ impl Level {
     pub fn id(&self) -> u32{
         (*self).into()
     }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Level::Easy => "Easy",
+            Level::Med => "Med",
+            Level::Hard => "Hard",
+            Level::Oni => "Oni",
+            Level::Ura => "Ura",
+        }
+    }
     pub fn decrease(&self) -> Option<Self> {
         return Level::try_from(self.id()-1).ok();
     }
 }
 
+impl FromStr for Level {
+    type Err = ElodonError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        parse_named(text, &[Level::Easy, Level::Med, Level::Hard, Level::Oni, Level::Ura], Level::name, Level::id)
+    }
+}
+
 impl Display for Level {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name())
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, ChoiceParameter)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "poise", derive(poise::ChoiceParameter))]
 pub enum DisplayLevel{
     Easy,
     Med,
     Hard,
-    #[name = "Oni+"]
+    #[cfg_attr(feature = "poise", name = "Oni+")]
     OniPlus,
 }
 
 impl DisplayLevel{
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayLevel::Easy => "Easy",
+            DisplayLevel::Med => "Med",
+            DisplayLevel::Hard => "Hard",
+            DisplayLevel::OniPlus => "Oni+",
+        }
+    }
     pub fn min_value(&self) -> u32 {
         match &self {
             DisplayLevel::Easy => 1,
@@ -218,6 +252,14 @@ impl DisplayLevel{
         }
     }
 }
+impl FromStr for DisplayLevel {
+    type Err = ElodonError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let levels = [DisplayLevel::Easy, DisplayLevel::Med, DisplayLevel::Hard, DisplayLevel::OniPlus];
+        parse_named(text, &levels, DisplayLevel::name, DisplayLevel::min_value)
+    }
+}
+
 impl From<Level> for DisplayLevel {
     fn from(value: Level) -> Self {
         match value {
@@ -232,7 +274,8 @@ impl From<Level> for DisplayLevel {
 
 // GENRE
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, ChoiceParameter, TryFromPrimitive, IntoPrimitive)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "poise", derive(poise::ChoiceParameter))]
 #[num_enum(error_type(name = ElodonError, constructor = ElodonError::WrongGenreId))]
 #[repr(u32)]
 pub enum Genre{
@@ -240,9 +283,9 @@ pub enum Genre{
     Anime = 2,
     Kids = 3,
     Vocaloid = 4,
-    #[name = "Game Music"]
+    #[cfg_attr(feature = "poise", name = "Game Music")]
     GameMusic = 5,
-    #[name = "Namco Original"]
+    #[cfg_attr(feature = "poise", name = "Namco Original")]
     NamcoOriginal = 6,
     Variety = 7,
     Classical = 8,
@@ -252,6 +295,27 @@ impl Genre {
     pub fn id(&self) -> u32{
         (*self).into()
     }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Genre::Pop => "Pop",
+            Genre::Anime => "Anime",
+            Genre::Kids => "Kids",
+            Genre::Vocaloid => "Vocaloid",
+            Genre::GameMusic => "Game Music",
+            Genre::NamcoOriginal => "Namco Original",
+            Genre::Variety => "Variety",
+            Genre::Classical => "Classical",
+        }
+    }
+}
+
+impl FromStr for Genre {
+    type Err = ElodonError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let genres = [Genre::Pop, Genre::Anime, Genre::Kids, Genre::Vocaloid,
+            Genre::GameMusic, Genre::NamcoOriginal, Genre::Variety, Genre::Classical];
+        parse_named(text, &genres, Genre::name, Genre::id)
+    }
 }
 
 impl Display for Genre {
@@ -260,9 +324,23 @@ impl Display for Genre {
     }
 }
 
+// for everything that isn't a slash command: the name in any case, with or without spaces, or the id
+pub(crate) fn parse_named<T: Copy + std::fmt::Debug>(text: &str, all: &[T], name: fn(&T) -> &'static str, id: fn(&T) -> u32) -> Result<T, ElodonError> {
+    let wanted = text.replace(' ', "").to_lowercase();
+    all.iter()
+        .find(|value| {
+            name(value).replace(' ', "").to_lowercase() == wanted
+                || format!("{:?}", value).to_lowercase() == wanted
+                || id(value).to_string() == wanted
+        })
+        .copied()
+        .ok_or(ElodonError::ParseError(text.to_string()))
+}
+
 // CHART
 
 #[derive(Copy, Clone, Debug, FromRow)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Chart {
     #[sqlx(rename = "song_id")]
     pub id: u32,
@@ -287,18 +365,6 @@ impl Chart {
     }
 }
 
-impl ElodonDisplay for Chart{
-    fn get_display_text(&self) -> String {
-
-        return format!("#{:<4}.{}:\n`Score/ELO={:>4} Miyabi ELO={:>4}\nsd= {} ({})`",
-            self.id, self.level,
-            self.score_slope.map(|i| format!("{i:>4}")).unwrap_or(" ?? ".to_string()),
-            self.score_miyabi.map(|i| format!("{i:>4}")).unwrap_or(" ?? ".to_string()),
-            self.sd_mean.map(|i|format!("{i:>7.0}")).unwrap_or("  ???  ".to_string()),
-            self.sd_sd.map(|i|format!("{i:>4.0}")).unwrap_or(" ?? ".to_string())
-        )
-    }
-}
 
 impl FetchOne<Song> for Chart{}
 impl FetchAll<Play> for Chart{}
@@ -332,6 +398,7 @@ impl Display for ChartId {
 // PLAY
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, FromRow)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Play{
     #[sqlx(rename = "user_id")]
     pub user: i64,
@@ -355,20 +422,6 @@ impl Play{
     }
 }
 
-impl ElodonDisplay for Play{
-    fn get_display_text(&self) -> String {
-        let crown_emoji = format!("<:crown_{}:{}>", self.crown, CROWN_IDS.get(self.crown as usize).expect("invalid crown id"));
-        let rank_emoji = if self.rank < 2 {String::new()} else {
-            format!("<:rank_{}:{}>", self.rank, RANK_IDS.get(self.rank as usize).expect("invalid rank id"))
-        };
-
-        format!("{:>4}.{} {:<13} {:>7} {} {}\n` {:>4} | {:>3} | {:<3} c{:<4} r{:<4}`",
-            self.song, self.level, self.score, self.user, crown_emoji, rank_emoji,
-            self.good_cnt, self.ok_cnt, self.bad_cnt, self.combo_cnt, self.level
-        )
-
-    }
-}
 
 impl FetchOne<User> for Play{}
 impl FetchOne<Song> for Play{}
@@ -383,6 +436,20 @@ impl From<Play> for GeneralFilter{
     }
 }
 
-
-
-
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_and_genres_are_named() {
+        assert_eq!(Level::Oni.to_string(), "Oni");
+        assert_eq!(DisplayLevel::from(Level::Ura).name(), "Oni+");
+        assert_eq!(Genre::try_from(5).unwrap().to_string(), "Game Music");
+        assert_eq!(ChartId(3, Level::Hard).to_string(), "3(Hard)");
+        assert_eq!("ura".parse::<Level>().unwrap(), Level::Ura);
+        assert_eq!("Oni+".parse::<DisplayLevel>().unwrap(), DisplayLevel::OniPlus);
+        assert_eq!("gamemusic".parse::<Genre>().unwrap(), Genre::GameMusic);
+        assert_eq!("6".parse::<Genre>().unwrap(), Genre::NamcoOriginal);
+        assert!("extreme".parse::<Level>().is_err());
+    }
+}
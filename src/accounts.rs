@@ -0,0 +1,224 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use itertools::Itertools;
+use poise::serenity_prelude::UserId;
+use sqlx::{Connection, FromRow, SqliteConnection};
+
+use crate::error::{CoreError, ElodonError};
+use crate::filters::*;
+use crate::structs::*;
+
+#[derive(Clone, Debug, FromRow)]
+pub struct LinkRequest {
+    pub donder_id: i64,
+    pub discord_id: i64,
+    pub code: String,
+    pub relink: bool,
+    pub profile_name: Option<String>,
+}
+
+pub enum LinkStatus {
+    Linked(i64),
+    // the scraper hasn't looked at this donder id yet
+    Pending(LinkRequest),
+    // scraped, but the code isn't in the profile name
+    CodeMissing(LinkRequest),
+}
+
+impl LinkRequest {
+    pub fn is_verified(&self) -> bool {
+        match &self.profile_name {
+            Some(profile_name) => profile_name.to_ascii_uppercase().contains(&self.code),
+            None => false
+        }
+    }
+}
+
+//short enough to fit in an in-game name next to the actual name
+fn new_link_code() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+    format!("EL{:04X}", hasher.finish() & 0xFFFF)
+}
+
+//all donder ids linked to discord_id, the primary one first
+pub async fn linked_users(conn: &mut SqliteConnection, discord_id: UserId) -> Result<Vec<User>, ElodonError> {
+    let users = match User::fetch_all(conn, UserFilter::new().discord_id(Some(discord_id.get()))).await {
+        Ok(users) => users,
+        Err(CoreError::NoResults { .. }) => return Ok(vec![]),
+        Err(err) => return Err(err.into())
+    };
+    let primary: Option<(i64,)> = sqlx::query_as("SELECT user_id FROM primary_accounts WHERE discord_id = ?")
+        .bind(discord_id.get() as i64)
+        .fetch_optional(&mut *conn).await?;
+    let primary = primary.map(|(user_id,)| user_id);
+    Ok(users.into_iter()
+        .sorted_by_key(|user| (Some(user.id) != primary, user.id))
+        .collect())
+}
+
+//the primary account, or the lowest Donder ID if none was picked
+pub async fn primary_user(conn: &mut SqliteConnection, discord_id: UserId) -> Result<Option<User>, ElodonError> {
+    Ok(linked_users(conn, discord_id).await?.into_iter().next())
+}
+
+pub async fn has_primary(conn: &mut SqliteConnection, discord_id: UserId) -> Result<bool, ElodonError> {
+    let primary: Option<(i64,)> = sqlx::query_as(
+        "SELECT primary_accounts.user_id FROM primary_accounts \
+            JOIN users ON users.user_id = primary_accounts.user_id AND users.discord_id = primary_accounts.discord_id \
+            WHERE primary_accounts.discord_id = ?"
+    ).bind(discord_id.get() as i64)
+        .fetch_optional(conn).await?;
+    Ok(primary.is_some())
+}
+
+pub async fn set_primary(conn: &mut SqliteConnection, discord_id: UserId, donder_id: i64) -> Result<(), ElodonError> {
+    if !linked_users(conn, discord_id).await?.iter().any(|user| user.id == donder_id) {
+        return Err(ElodonError::NotLinkedAccount(donder_id));
+    }
+    sqlx::query("INSERT OR REPLACE INTO primary_accounts (discord_id, user_id) VALUES (?,?)")
+        .bind(discord_id.get() as i64)
+        .bind(donder_id)
+        .execute(conn).await?;
+    Ok(())
+}
+
+async fn ensure_unclaimed(conn: &mut SqliteConnection, donder_id: i64, discord_id: UserId) -> Result<(), ElodonError> {
+    match User::fetch_one(conn, UserFilter::new().user_id(Some(donder_id))).await {
+        Ok(owner) if owner.discord_id() == discord_id.get() => Err(ElodonError::AlreadyRegistered(donder_id)),
+        Ok(_) => Err(ElodonError::DonderIdClaimed(donder_id)),
+        Err(CoreError::NoResults { .. }) => Ok(()),
+        Err(err) => Err(err.into())
+    }
+}
+
+//starts linking donder_id to discord_id. the returned request holds the code the user has to put in their name.
+//a relink replaces the primary account once confirmed, otherwise the id is added next to the existing ones
+pub async fn request_link(conn: &mut SqliteConnection, donder_id: i64, discord_id: UserId, relink: bool) -> Result<LinkRequest, ElodonError> {
+    ensure_unclaimed(conn, donder_id, discord_id).await?;
+    if relink && linked_users(conn, discord_id).await?.is_empty() {
+        return Err(ElodonError::NotRegistered);
+    }
+
+    let request = LinkRequest {
+        donder_id,
+        discord_id: discord_id.get() as i64,
+        code: new_link_code(),
+        relink,
+        profile_name: None,
+    };
+    // only one pending request per discord account
+    sqlx::query("DELETE FROM link_requests WHERE discord_id = ?")
+        .bind(request.discord_id)
+        .execute(&mut *conn).await?;
+    sqlx::query("INSERT INTO link_requests (donder_id, discord_id, code, relink) VALUES (?,?,?,?)")
+        .bind(request.donder_id)
+        .bind(request.discord_id)
+        .bind(&request.code)
+        .bind(request.relink)
+        .execute(&mut *conn).await?;
+    Ok(request)
+}
+
+//links the pending request of discord_id if the scraper found the code in the profile name
+pub async fn confirm_link(conn: &mut SqliteConnection, discord_id: UserId) -> Result<LinkStatus, ElodonError> {
+    let request: Option<LinkRequest> = sqlx::query_as(
+        "SELECT donder_id, discord_id, code, relink, profile_name FROM link_requests WHERE discord_id = ?"
+    ).bind(discord_id.get() as i64)
+        .fetch_optional(&mut *conn).await?;
+    let request = request.ok_or(ElodonError::NoLinkRequest)?;
+
+    if request.profile_name.is_none() {
+        return Ok(LinkStatus::Pending(request));
+    }
+    if !request.is_verified() {
+        return Ok(LinkStatus::CodeMissing(request));
+    }
+    // someone else may have verified the same id in the meantime
+    ensure_unclaimed(conn, request.donder_id, discord_id).await?;
+
+    let replaced = match request.relink {
+        true => primary_user(conn, discord_id).await?.map(|user| user.id),
+        false => None
+    };
+
+    let mut transaction = conn.begin().await?;
+    if let Some(replaced) = replaced {
+        delete_linked_users(&mut transaction, discord_id, Some(replaced)).await?;
+        sqlx::query("INSERT OR REPLACE INTO primary_accounts (discord_id, user_id) VALUES (?,?)")
+            .bind(request.discord_id)
+            .bind(request.donder_id)
+            .execute(&mut *transaction).await?;
+    }
+    sqlx::query("INSERT INTO users (user_id, discord_id, user_name) VALUES (?,?,?)")
+        .bind(request.donder_id)
+        .bind(request.discord_id)
+        .bind(request.profile_name.as_deref().unwrap_or("temp_name"))
+        .execute(&mut *transaction).await?;
+    sqlx::query("DELETE FROM link_requests WHERE discord_id = ?")
+        .bind(request.discord_id)
+        .execute(&mut *transaction).await?;
+    transaction.commit().await?;
+
+    Ok(LinkStatus::Linked(request.donder_id))
+}
+
+//removes the link (one donder id or all of them). returns the donder ids that were unlinked.
+//the plays stay in top_plays, which belongs to the scraper
+pub async fn unlink(conn: &mut SqliteConnection, discord_id: UserId, donder_id: Option<i64>) -> Result<Vec<i64>, ElodonError> {
+    let mut transaction = conn.begin().await?;
+    let donder_ids = delete_linked_users(&mut transaction, discord_id, donder_id).await?;
+    if donder_id.is_none() {
+        sqlx::query("DELETE FROM link_requests WHERE discord_id = ?")
+            .bind(discord_id.get() as i64)
+            .execute(&mut *transaction).await?;
+    }
+    transaction.commit().await?;
+
+    match (donder_ids.is_empty(), donder_id) {
+        (true, Some(donder_id)) => Err(ElodonError::NotLinkedAccount(donder_id)),
+        (true, None) => Err(ElodonError::NotRegistered),
+        (false, _) => Ok(donder_ids)
+    }
+}
+
+async fn delete_linked_users(conn: &mut SqliteConnection, discord_id: UserId, donder_id: Option<i64>) -> Result<Vec<i64>, ElodonError> {
+    let donder_ids: Vec<i64> = sqlx::query_as::<_, (i64,)>("SELECT user_id FROM users WHERE discord_id = ?")
+        .bind(discord_id.get() as i64)
+        .fetch_all(&mut *conn).await?
+        .into_iter()
+        .map(|(user_id,)| user_id)
+        .filter(|user_id| donder_id.is_none_or(|donder_id| donder_id == *user_id))
+        .collect();
+
+    for user_id in &donder_ids {
+        sqlx::query("DELETE FROM users WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *conn).await?;
+        sqlx::query("DELETE FROM primary_accounts WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *conn).await?;
+    }
+    Ok(donder_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::fixture_connection;
+
+    #[tokio::test]
+    async fn unlinking_keeps_the_scraped_plays() {
+        let mut conn = fixture_connection().await;
+        let discord_id = UserId::new(900000000000000000);
+        let user = primary_user(&mut conn, discord_id).await.unwrap().unwrap();
+        let plays = Play::fetch_all(&mut conn, PlayFilter::new().user_id(Some(user.id))).await.unwrap();
+
+        assert_eq!(unlink(&mut conn, discord_id, None).await.unwrap(), vec![user.id]);
+        assert!(linked_users(&mut conn, discord_id).await.unwrap().is_empty());
+        let kept = Play::fetch_all(&mut conn, PlayFilter::new().user_id(Some(user.id))).await.unwrap();
+        assert_eq!(kept.len(), plays.len());
+    }
+}
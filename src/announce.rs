@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use itertools::Itertools;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{ChannelId, GuildId};
+use sqlx::SqliteConnection;
+
+use crate::commands::get_connection;
+use crate::display::Symbols;
+use crate::error::ElodonError;
+use crate::events::{detect, rank_among, PlayEvent};
+use crate::filters::*;
+use crate::paginate::EMBED_DESCRIPTION_LIMIT;
+use crate::structs::*;
+use crate::{emoji, members, settings};
+
+// how often top_plays is compared with what was seen last
+pub const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(60 * 5);
+
+const NOTABLE_COLOUR: u32 = 0xD4AF37;
+
+// posts every new personal best in the servers the player is in that have an announcement channel
+pub async fn announce(ctx: &serenity::Context) -> Result<(), ElodonError> {
+    let mut conn = get_connection().await?;
+    let events = detect(&mut conn).await?;
+    if events.is_empty() {
+        return Ok(());
+    }
+    for (guild_id, channel_id) in settings::announce_channels(&mut conn).await? {
+        let players = members::server_players(&mut conn, guild_id).await?;
+        let guild_events = events.iter().filter(|event| players.contains(&event.user)).collect_vec();
+        if guild_events.is_empty() {
+            continue;
+        }
+
+        let mut lines = Vec::with_capacity(guild_events.len());
+        for event in guild_events {
+            // one play that can't be described doesn't hold back the others
+            match describe(&mut conn, guild_id, event, &players).await {
+                Ok(line) => lines.push(line),
+                Err(err) => println!("Describing a play of {} on song {} for {guild_id} failed: {err}", event.user, event.song),
+            }
+        }
+        if lines.is_empty() {
+            continue;
+        }
+        post(ctx, channel_id, "New personal bests", lines).await;
+    }
+    Ok(())
+}
+
+// one line per play: who, what, by how much, and where that puts them in the server
+async fn describe(conn: &mut SqliteConnection, guild_id: GuildId, event: &PlayEvent, players: &HashSet<i64>) -> Result<(bool, String), ElodonError> {
+    let user = User::fetch_one(conn, UserFilter::new().user_id(Some(event.user))).await?;
+    let song = Song::fetch_one(conn, GeneralFilter::new().song_id(Some(event.song))).await?;
+    let symbols = emoji::emojis(Some(guild_id));
+
+    let server_plays = Play::fetch_all(conn, event.chart()).await?
+        .into_iter()
+        .filter(|play| players.contains(&play.user))
+        .map(|play| (play.user, play.score))
+        .collect_vec();
+    let rank = rank_among(server_plays.iter().copied(), event.user, event.new.score);
+    let old_rank = event.old.map(|old| rank_among(server_plays.iter().copied(), event.user, old.score));
+
+    let mut line = format!("**{}** <@{}> {} ({}) {} {} {}",
+                           user.name, user.discord_id(), song.get_name(), event.level(), event.new.score,
+                           symbols.crown(event.new.crown), symbols.rank(event.new.rank));
+    match event.old {
+        Some(old) if event.better_score() => line.push_str(&format!(" (+{})", event.new.score - old.score)),
+        None => line.push_str(" (first play)"),
+        _ => {}
+    }
+    match old_rank {
+        Some(old_rank) if old_rank != rank => line.push_str(&format!(" · #{rank} in the server, was #{old_rank}")),
+        None => line.push_str(&format!(" · #{rank} in the server")),
+        _ => {}
+    }
+    if let Some(z) = event.z {
+        line.push_str(&format!(" · `z {z:+.2}`"));
+    }
+    if event.is_notable() {
+        line = format!("✨ {line}");
+    }
+    Ok((event.is_notable(), line))
+}
+
+// notable lines first. split into several messages when there are too many for one embed
+pub async fn post(ctx: &serenity::Context, channel_id: ChannelId, title: &str, lines: Vec<(bool, String)>) {
+    let notable = lines.iter().any(|(notable, _)| *notable);
+    let mut descriptions: Vec<String> = vec![String::new()];
+    for (_, line) in lines.into_iter().sorted_by_key(|(notable, _)| !notable) {
+        let description = descriptions.last_mut().unwrap();
+        if !description.is_empty() && description.chars().count() + line.chars().count() + 1 > EMBED_DESCRIPTION_LIMIT {
+            descriptions.push(line);
+        } else {
+            if !description.is_empty() {
+                description.push('\n');
+            }
+            description.push_str(&line);
+        }
+    }
+
+    for description in descriptions {
+        let mut embed = serenity::CreateEmbed::new()
+            .title(title)
+            .description(description);
+        if notable {
+            embed = embed.colour(NOTABLE_COLOUR);
+        }
+        let message = serenity::CreateMessage::new()
+            .embed(embed)
+            .allowed_mentions(serenity::CreateAllowedMentions::new());
+        if let Err(err) = channel_id.send_message(&ctx.http, message).await {
+            println!("Posting \"{title}\" in {channel_id} failed: {err}");
+        }
+    }
+}
@@ -0,0 +1,314 @@
+// read only JSON over the score data, for dashboards. only built with the http-api feature, and
+// listens on ELODON_API_ADDR
+use std::collections::hash_map::DefaultHasher;
+use std::env::var;
+use std::hash::{Hash, Hasher};
+
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use poise::serenity_prelude::UserId;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+
+use crate::{accounts, elo};
+use crate::error::{CoreError, ElodonError};
+use crate::filters::*;
+use crate::structs::*;
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:3000";
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+// the fields of GeneralFilter and a page. levels and genres can be names or ids
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ApiQuery {
+    user_id: Option<i64>,
+    discord_id: Option<u64>,
+    song_id: Option<u32>,
+    level: Option<String>,
+    display_level: Option<String>,
+    genre: Option<String>,
+    guild_id: Option<u64>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+impl ApiQuery {
+    fn filter(&self) -> Result<GeneralFilter, CoreError> {
+        Ok(GeneralFilter {
+            user_id: self.user_id,
+            discord_id: self.discord_id,
+            song_id: self.song_id,
+            level: self.level.as_deref().map(str::parse).transpose()?,
+            display_level: self.display_level.as_deref().map(str::parse).transpose()?,
+            genre: self.genre.as_deref().map(str::parse).transpose()?,
+            guild_id: self.guild_id,
+        })
+    }
+
+    // (offset, limit)
+    fn page(&self) -> (usize, usize) {
+        (self.offset.unwrap_or(0), self.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT))
+    }
+}
+
+#[derive(Serialize)]
+struct Page<R> {
+    count: usize,
+    offset: usize,
+    rows: Vec<R>,
+}
+
+// the score expected at the player's elo, and one sd of elo below and above it
+#[derive(Serialize)]
+struct Prediction {
+    song_id: u32,
+    level: u32,
+    elo: f32,
+    score: u32,
+    low: Option<u32>,
+    high: Option<u32>,
+}
+
+// the bot's errors and the bad requests only the api has. boxed, as discord errors make them large
+#[derive(Error, Debug)]
+enum ApiError {
+    #[error(transparent)]
+    Elodon(Box<ElodonError>),
+    #[error("Predictions are for one player, give a user_id or discord_id")]
+    NoPlayerGiven,
+    #[error("These rows can't be filtered{0}")]
+    UnsupportedFilter(GeneralFilter),
+}
+
+impl From<ElodonError> for ApiError {
+    fn from(error: ElodonError) -> Self {
+        ApiError::Elodon(Box::new(error))
+    }
+}
+
+impl From<CoreError> for ApiError {
+    fn from(error: CoreError) -> Self {
+        ApiError::Elodon(Box::new(error.into()))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::Elodon(error) => match **error {
+                ElodonError::Core(CoreError::NoResults { .. }) => StatusCode::NOT_FOUND,
+                ElodonError::Core(CoreError::ParseError(_) | CoreError::WrongLevelId(_) | CoreError::WrongGenreId(_)) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            ApiError::NoPlayerGiven | ApiError::UnsupportedFilter(_) => StatusCode::BAD_REQUEST,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+// the etag is a hash of the body, so a client that already has this page gets a 304 instead.
+// it's only stable for as long as the bot runs, which is all a browser cache needs
+fn cached(headers: &HeaderMap, body: &impl Serialize) -> Response {
+    let Ok(bytes) = serde_json::to_vec(body) else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+    let unchanged = headers.get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+    let etag = HeaderValue::from_str(&etag).expect("quoted hex is a valid header");
+    if unchanged {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+    ([(header::ETAG, etag), (header::CONTENT_TYPE, HeaderValue::from_static("application/json"))], bytes).into_response()
+}
+
+// a field R has no column for is a bad request, rather than being left out of the search
+fn supported<R: Filterable>(filter: GeneralFilter) -> Result<GeneralFilter, ApiError> {
+    let unsupported = R::unsupported(filter);
+    if unsupported != GeneralFilter::new() {
+        return Err(ApiError::UnsupportedFilter(unsupported));
+    }
+    Ok(filter)
+}
+
+async fn table<R: Filterable + Serialize>(State(pool): State<SqlitePool>, headers: HeaderMap, Query(query): Query<ApiQuery>) -> Result<Response, ApiError> {
+    let filter = supported::<R>(query.filter()?)?;
+    let (offset, limit) = query.page();
+    let mut conn = pool.acquire().await.map_err(CoreError::from)?;
+    let count = R::count(&mut conn, filter).await?;
+    let rows = R::fetch_page(&mut conn, filter, offset, limit).await?;
+    Ok(cached(&headers, &Page { count, offset, rows }))
+}
+
+// predicted scores of one player (user_id or discord_id) on every chart matching the rest of the filter
+async fn predictions(State(pool): State<SqlitePool>, headers: HeaderMap, Query(query): Query<ApiQuery>) -> Result<Response, ApiError> {
+    let filter = query.filter()?;
+    let charts = supported::<Chart>(filter.user_id(None).discord_id(None))?;
+    let (offset, limit) = query.page();
+    let mut conn = pool.acquire().await.map_err(CoreError::from)?;
+    let user = match (filter.user_id, filter.discord_id) {
+        (None, None) => return Err(ApiError::NoPlayerGiven),
+        // a discord account with several donder ids is its primary one, as in the bot's commands
+        (None, Some(discord_id)) if discord_id != 0 => accounts::primary_user(&mut conn, UserId::new(discord_id)).await?
+            .ok_or(CoreError::NoResults { search: "users".to_owned(), id: format!("discord <@{discord_id}>") })?,
+        _ => User::fetch_one(&mut conn, UserFilter::from(filter)).await?,
+    };
+    let predictions: Vec<Prediction> = Chart::fetch_all_or_empty(&mut conn, charts).await?
+        .iter()
+        .filter_map(|chart| {
+            let elo = user.elo(chart.level().into())?;
+            Some(Prediction {
+                song_id: chart.id,
+                level: chart.level,
+                elo,
+                score: elo::get_predicted_score(Some(elo), chart, 0.)?,
+                low: elo::get_predicted_score(Some(elo), chart, -1.),
+                high: elo::get_predicted_score(Some(elo), chart, 1.),
+            })
+        })
+        .collect();
+    let count = predictions.len();
+    let rows: Vec<Prediction> = predictions.into_iter().skip(offset).take(limit).collect();
+    Ok(cached(&headers, &Page { count, offset, rows }))
+}
+
+// pool is the bot's, see commands::pool
+pub fn router(pool: SqlitePool) -> Router {
+    Router::new()
+        .route("/users", get(table::<User>))
+        .route("/songs", get(table::<Song>))
+        .route("/charts", get(table::<Chart>))
+        .route("/plays", get(table::<Play>))
+        .route("/predictions", get(predictions))
+        .with_state(pool)
+}
+
+// runs for as long as the bot does
+pub async fn serve(pool: SqlitePool) {
+    let addr = var("ELODON_API_ADDR").unwrap_or(DEFAULT_ADDR.to_owned());
+    let listener = match tokio::net::TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(err) => return println!("HTTP API failed to listen on {addr}: {err}"),
+    };
+    println!("HTTP API listening on {addr}");
+    if let Err(err) = axum::serve(listener, router(pool)).await {
+        println!("HTTP API stopped: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(uri: &str) -> ApiQuery {
+        Query::try_from_uri(&uri.parse().unwrap()).unwrap().0
+    }
+
+    #[tokio::test]
+    async fn predictions_need_a_player() {
+        let pool = crate::schema::fixture_pool().await;
+        let response = match predictions(State(pool.clone()), HeaderMap::new(), Query(parse("/predictions?level=oni"))).await {
+            Ok(response) => response,
+            Err(err) => err.into_response(),
+        };
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = predictions(State(pool), HeaderMap::new(), Query(parse("/predictions?discord_id=900000000000000000"))).await.ok().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // the status and, when it's a page, the row count
+    async fn respond(response: Result<Response, ApiError>) -> (StatusCode, Option<u64>) {
+        let response = response.unwrap_or_else(IntoResponse::into_response);
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let page: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        (status, page["count"].as_u64())
+    }
+
+    #[tokio::test]
+    async fn endpoints_filter_by_every_field_or_refuse_it() {
+        let pool = crate::schema::fixture_pool().await;
+        let get = |uri: &str| Query(parse(uri));
+        let state = || State(pool.clone());
+
+        assert_eq!(respond(table::<User>(state(), HeaderMap::new(), get("/users?song_id=3")).await).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(respond(table::<User>(state(), HeaderMap::new(), get("/users?display_level=oni")).await).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(respond(table::<Song>(state(), HeaderMap::new(), get("/songs?level=oni")).await).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(respond(table::<Chart>(state(), HeaderMap::new(), get("/charts?user_id=100000000000")).await).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(respond(table::<Play>(state(), HeaderMap::new(), get("/plays?discord_id=900000000000000000")).await).await.0, StatusCode::BAD_REQUEST);
+        let player = "discord_id=900000000000000000";
+        assert_eq!(respond(predictions(state(), HeaderMap::new(), get(&format!("/predictions?{player}&guild_id=1"))).await).await.0, StatusCode::BAD_REQUEST);
+
+        for (all, anime) in [
+            (respond(table::<Song>(state(), HeaderMap::new(), get("/songs")).await).await,
+             respond(table::<Song>(state(), HeaderMap::new(), get("/songs?genre=anime")).await).await),
+            (respond(table::<Chart>(state(), HeaderMap::new(), get("/charts")).await).await,
+             respond(table::<Chart>(state(), HeaderMap::new(), get("/charts?genre=anime")).await).await),
+            (respond(table::<Play>(state(), HeaderMap::new(), get("/plays")).await).await,
+             respond(table::<Play>(state(), HeaderMap::new(), get("/plays?genre=anime")).await).await),
+            (respond(predictions(state(), HeaderMap::new(), get(&format!("/predictions?{player}"))).await).await,
+             respond(predictions(state(), HeaderMap::new(), get(&format!("/predictions?{player}&genre=anime"))).await).await),
+        ] {
+            assert_eq!((all.0, anime.0), (StatusCode::OK, StatusCode::OK));
+            assert!(0 < anime.1.unwrap() && anime.1 < all.1);
+        }
+        let (status, users) = respond(table::<User>(state(), HeaderMap::new(), get("/users?guild_id=1")).await).await;
+        assert_eq!((status, users), (StatusCode::OK, Some(0)));
+    }
+
+    #[tokio::test]
+    async fn predictions_are_for_the_primary_account() {
+        let pool = crate::schema::fixture_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+        // a second, stronger donder id for fixture player 0
+        sqlx::query("INSERT INTO users (user_id, discord_id, user_name, elo1, elo2, elo3, elo4) VALUES (100000000001, 900000000000000000, 'second', 2000, 2000, 2000, 2000)")
+            .execute(&mut *conn).await.unwrap();
+        accounts::set_primary(&mut conn, UserId::new(900000000000000000), 100000000001).await.unwrap();
+        drop(conn);
+
+        let response = predictions(State(pool), HeaderMap::new(), Query(parse("/predictions?discord_id=900000000000000000"))).await.ok().unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let page: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let rows = page["rows"].as_array().unwrap();
+        assert!(!rows.is_empty());
+        assert!(rows.iter().all(|row| row["elo"] == 2000.));
+    }
+
+    #[test]
+    fn queries_become_filters() {
+        let query = parse("/plays?user_id=5&display_level=oni%2B&genre=2&limit=100000");
+        let filter = query.filter().unwrap();
+        assert_eq!(filter.user_id, Some(5));
+        assert_eq!(filter.display_level, Some(DisplayLevel::OniPlus));
+        assert_eq!(filter.genre, Some(Genre::Anime));
+        assert_eq!(query.page(), (0, MAX_LIMIT));
+
+        assert!(parse("/plays?level=extreme").filter().is_err());
+    }
+
+    #[test]
+    fn unchanged_pages_are_not_sent_again() {
+        let body = Page { count: 1, offset: 0, rows: vec![1, 2, 3] };
+        let first = cached(&HeaderMap::new(), &body);
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers().get(header::ETAG).unwrap().clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+        assert_eq!(cached(&headers, &body).status(), StatusCode::NOT_MODIFIED);
+
+        let changed = Page { count: 1, offset: 0, rows: vec![1, 2, 4] };
+        let response = cached(&headers, &changed);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers().get(header::ETAG), Some(&etag));
+    }
+}
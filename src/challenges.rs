@@ -0,0 +1,63 @@
+pub use elodon_core::challenges::*;
+
+use std::collections::HashMap;
+
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{ChannelId, GuildId};
+use sqlx::SqliteConnection;
+
+use crate::commands::get_connection;
+use crate::error::ElodonError;
+use crate::filters::*;
+use crate::history::now;
+use crate::structs::*;
+use crate::{members, settings, views};
+
+// how many places the results post lists
+const PODIUM: usize = 3;
+
+// posts the winners of every challenge that ended in its server's announcement channel. servers
+// without one still get their challenges closed, and so do challenges whose results can't be posted
+pub async fn announce_winners(ctx: &serenity::Context) -> Result<(), ElodonError> {
+    let mut conn = get_connection().await?;
+    let channels: HashMap<_, _> = settings::announce_channels(&mut conn).await?.into_iter().collect();
+    for challenge in ended_challenges(&mut conn, now()).await? {
+        if let Some(channel_id) = channels.get(&GuildId::new(challenge.guild_id as u64)) {
+            if let Err(err) = post_results(ctx, &mut conn, &challenge, *channel_id).await {
+                println!("Posting the results of challenge #{} failed: {err}", challenge.id);
+            }
+        }
+        mark_finished(&mut conn, challenge.id).await?;
+    }
+    Ok(())
+}
+
+// a standing that can't be described is left out rather than holding back the rest
+async fn post_results(ctx: &serenity::Context, conn: &mut SqliteConnection, challenge: &Challenge,
+                      channel_id: ChannelId) -> Result<(), ElodonError> {
+    let players = members::server_players(conn, GuildId::new(challenge.guild_id as u64)).await?;
+    let standings = current_standings(conn, challenge, &players).await?;
+    let names: HashMap<i64, String> = User::fetch_all_or_empty(conn, GeneralFilter::new()).await?
+        .into_iter()
+        .map(|user| (user.id, user.name))
+        .collect();
+
+    let mut lines = vec![];
+    for (index, standing) in standings.iter().take(PODIUM).enumerate() {
+        match views::standing_line(conn, index, standing, &names).await {
+            Ok(line) => lines.push(line),
+            Err(err) => println!("Describing place {} of challenge #{} failed: {err}", index + 1, challenge.id),
+        }
+    }
+    let description = match lines.is_empty() {
+        true => "Nobody improved on any of the charts".to_owned(),
+        false => format!("{}\n\n{} player{} improved", lines.join("\n"), standings.len(), if standings.len() == 1 { "" } else { "s" }),
+    };
+    let message = serenity::CreateMessage::new()
+        .embed(serenity::CreateEmbed::new()
+            .title(format!("Challenge \"{}\" is over", challenge.name))
+            .description(description))
+        .allowed_mentions(serenity::CreateAllowedMentions::new());
+    channel_id.send_message(&ctx.http, message).await?;
+    Ok(())
+}
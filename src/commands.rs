@@ -1,26 +1,30 @@
+use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::num::{NonZeroU32, NonZeroU8};
-use crate::{Data, elo, emoji};
+use std::marker::PhantomData;
+use crate::{accounts, challenges, elo, emoji, eval, history, members, provision, records, render, views};
 use poise::builtins::create_application_commands;
 use futures::Stream;
 use futures::StreamExt;
 use itertools::Itertools;
-use noisy_float::types::{R32, r32};
-use num_traits::real::Real;
 use num_traits::Signed;
 use poise::serenity_prelude as serenity;
 use poise::serenity_prelude::{GuildId, Mentionable, UserId};
-use sqlx::{Connection, Executor, Row, SqliteConnection};
-use sqlx::sqlite::SqliteQueryResult;
+use once_cell::sync::Lazy;
+use sqlx::{Executor, Row, Sqlite, SqliteConnection, SqlitePool};
+use sqlx::pool::PoolConnection;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteQueryResult};
 
-use crate::error::{ElodonError, ElodonErrorList};
+use crate::error::{CoreError, ElodonError};
 use crate::filters::*;
 use crate::Error;
 use crate::Context;
 use crate::elo::get_predicted_score;
-use crate::emoji::{COMBO_IDS, CROWN_IDS, JUDGEMENT_IDS, RANK_IDS, ROLLS_IDS};
 use crate::structs::*;
-use crate::paginate::paginate;
+use crate::display::*;
+use crate::paginate::{Control, EntrySource, LazyPages, Page, PageSource, Paginator};
+use crate::views::ScoreSort;
+use crate::settings::{GuildSettings, LeaderboardScope};
 
 macro_rules! return_err {
     ($err:expr) => {
@@ -30,12 +34,12 @@ macro_rules! return_err {
 
 macro_rules! ok_or_say_error {
     ($ctx: ident, $search: expr) => {
-        match $search.await {
+        match $search.await.map_err(ElodonError::from) {
             Ok(value) => {
                 value
             },
-            Err(err @ ElodonError::NoResults {..}) => {
-                $ctx.say(err).await?;
+            Err(err @ ElodonError::Core(CoreError::NoResults {..})) => {
+                $ctx.say(err.to_string()).await?;
                 return Ok(());
             },
             Err(err) => {
@@ -45,13 +49,42 @@ macro_rules! ok_or_say_error {
     };
 }
 
+const MAX_AUTOCOMPLETE_LENGTH: usize = 100;
+
 async fn autocomplete_song<'a>(
     ctx: Context<'a>,
     partial: &'a str,
 ) -> impl Stream<Item = String> + 'a {
-    futures::stream::iter(&ctx.data().songs_autocomplete[..])
-        .filter(move |name| futures::future::ready(name.to_ascii_lowercase().contains(&partial.to_ascii_lowercase())))
-        .map(|name| name.to_string())
+    let partial = partial.to_ascii_lowercase();
+    let matching: Vec<String> = ctx.data().songs_autocomplete.read().unwrap()
+        .iter()
+        .filter(|name| name.to_ascii_lowercase().contains(&partial))
+        .cloned()
+        .collect();
+    futures::stream::iter(matching)
+}
+
+//"id: genre > name (alias, alias)". the id is what extract_song_id reads back
+pub(crate) async fn load_song_autocomplete(conn: &mut SqliteConnection) -> Result<Vec<String>, ElodonError> {
+    let songs = Song::fetch_all(conn, SongFilter::new()).await?;
+    let aliases: Vec<(u32, String)> = sqlx::query_as("SELECT song_id, alias FROM song_aliases ORDER BY alias")
+        .fetch_all(&mut *conn).await?;
+    let aliases = aliases.into_iter().into_group_map();
+
+    Ok(songs.iter().map(|song| {
+        let name = format!("{}: {} > {}", song.id, song.genre(), song.get_name());
+        let name = match aliases.get(&song.id) {
+            Some(aliases) => format!("{name} ({})", aliases.join(", ")),
+            None => name
+        };
+        name.chars().take(MAX_AUTOCOMPLETE_LENGTH).collect()
+    }).collect())
+}
+
+async fn refresh_song_autocomplete(ctx: Context<'_>, conn: &mut SqliteConnection) -> Result<(), ElodonError> {
+    let song_names = load_song_autocomplete(conn).await?;
+    *ctx.data().songs_autocomplete.write().unwrap() = song_names;
+    Ok(())
 }
 
 /// Show this help menu
@@ -85,7 +118,7 @@ pub async fn song_info(
     let mut conn = get_connection().await?;
     let song: Song = match sqlx::query_as("SELECT song_id, song_name_eng, song_name_jap, genre_id FROM songs WHERE song_id=?")
         .bind(song_id)
-        .fetch_one(&mut conn)
+        .fetch_one(&mut *conn)
         .await {
             Err(err) => { return_err!(err) }
             Ok(song) => { song }
@@ -97,228 +130,233 @@ pub async fn song_info(
 
 }
 
+const SONGS_PER_PAGE: usize = 5;
+// the buttons only make sense right after searching
+const SONG_SEARCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60 * 5);
+
+// song search results with buttons to open the scores or chart of each song on the page
+struct SongResults {
+    header: String,
+    songs: Vec<Song>,
+}
+
+impl PageSource for SongResults {
+    async fn page_count(&mut self) -> Result<usize, ElodonError> {
+        Ok(self.songs.len().div_ceil(SONGS_PER_PAGE))
+    }
+
+    async fn page(&mut self, index: usize) -> Result<Page, ElodonError> {
+        let mut page = String::from("```\n");
+        for song in self.songs.chunks(SONGS_PER_PAGE).nth(index).unwrap_or_default() {
+            page.push_str(&*format!("#{:<5}: {} > {}\n", song.id, song.genre(), song.get_name()));
+        }
+        page.push_str("```");
+        Ok(Page::description(&*self.header, page))
+    }
+
+    fn components(&self, id_prefix: &str, page: usize, disabled: bool) -> Vec<serenity::CreateActionRow> {
+        let Some(page_songs) = self.songs.chunks(SONGS_PER_PAGE).nth(page) else {
+            return vec![];
+        };
+        let buttons = |action: &str, label: &str| page_songs.iter().map(|song| {
+            serenity::CreateButton::new(format!("{id_prefix}{action}{}", song.id))
+                .style(serenity::ButtonStyle::Secondary)
+                .label(format!("{label} #{}", song.id))
+                .disabled(disabled)
+        }).collect();
+        vec![
+            serenity::CreateActionRow::Buttons(buttons("scores", "Scores")),
+            serenity::CreateActionRow::Buttons(buttons("chart", "Chart")),
+        ]
+    }
+
+    async fn handle(&mut self, action: &str, _press: &serenity::ComponentInteraction) -> Result<Control, ElodonError> {
+        match action.starts_with("scores") || action.starts_with("chart") {
+            true => Ok(Control::Finish),
+            false => Ok(Control::Ignore)
+        }
+    }
+}
+
 ///search songs to find the id
 #[poise::command(slash_command)]
 pub async fn song(
     ctx: Context<'_>,
     #[description="search keyphrase"] search: String,
-    #[description="genre "] genre: Option<Genre>
+    #[description="genre "] genre: Option<Genre>,
+    #[description="only songs that have this difficulty"] level: Option<Level>
 ) -> Result<(), Error> {
 
     let mut conn = get_connection().await?;
     let songs: Vec<Song> = ok_or_say_error!(
         ctx,
-        Song::get_matching(&mut conn, &search, genre)
+        Song::get_matching(&mut conn, &search, genre, level)
     );
 
-    let mut warnings = ElodonErrorList::new();
-    let mut response: String = String::new();
-    match genre {
-        Some(genre) => response.push_str(&*format!("### Results for \"{search}\" in {genre}:\n```\n")),
-        None => response.push_str(&*format!("### Results for \"{search}\":\n```\n"))
+    let mut header = format!("### Results for \"{search}\"");
+    if let Some(genre) = genre {
+        header.push_str(&*format!(" in {genre}"));
+    }
+    if let Some(level) = level {
+        header.push_str(&*format!(" with {level}"));
+    }
+    header.push_str(&*format!(" ({} songs):", songs.len()));
+    drop(conn);
+
+    let mut results = SongResults { header, songs };
+    let Some(press) = Paginator::new().timeout(SONG_SEARCH_TIMEOUT).run(ctx, &mut results).await? else {
+        return Ok(());
     };
 
-    for song in songs {
-        response.push_str(&*format!("#{:<5}: {} > {}\n", song.id, song.genre(), song.get_name()));
-    }
-    response.push_str("```");
-    ctx.say(response).await?;
+    // leave the results up but drop the buttons, the chosen command replies below
+    press.create_response(ctx.serenity_context(), serenity::CreateInteractionResponse::UpdateMessage(
+        serenity::CreateInteractionResponseMessage::new().components(vec![])
+    )).await?;
 
-    if !warnings.is_empty() {
-        return_err!(ElodonError::List(warnings))
+    let action = press.data.custom_id.trim_start_matches(&ctx.id().to_string());
+    if let Some(song_id) = action.strip_prefix("scores").and_then(|id| id.parse().ok()) {
+        scores_inner(ctx, song_id, level, None, None, None, None, None).await
+    } else if let Some(song_id) = action.strip_prefix("chart").and_then(|id| id.parse().ok()) {
+        chart_inner(ctx, song_id, level).await
     } else {
         Ok(())
     }
 }
 
-///get scoreboard of chart via song id and difficulty
+///see the rating info of a chart and your predicted score on it
 #[poise::command(slash_command)]
-pub async fn scores(
+pub async fn chart(
     ctx: Context<'_>,
     #[autocomplete = "autocomplete_song"]
     #[description="song"]
     song: String,
-    #[description="difficulty"]
+    #[description="difficulty (by default the hardest)"]
+    level: Option<Level>,
+) -> Result<(), Error> {
+    chart_inner(ctx, extract_song_id(song)?, level).await
+}
+
+async fn chart_inner(
+    ctx: Context<'_>,
+    song_id: u32,
     level_input: Option<Level>,
-    #[description="should good/ok/bad be included or not"]
-    detailed_input: Option<bool>,
-    #[description="exclude self estimates"]
-    exclude_estimates: Option<bool>
 ) -> Result<(), Error> {
-    let mut response = String::new();
-    let mut warnings = ElodonErrorList::new();
     let mut conn = get_connection().await?;
+    let song_filter = GeneralFilter::new().song_id(Some(song_id));
 
-    let detailed = detailed_input.unwrap_or(true);
-    ctx.defer().await?;
-
-    let author_user = User::fetch_one(&mut conn, UserFilter{
-        discord_id: Some(ctx.author().id),
-        user_id: None
-    }).await.ok();
-
-    let song_id: u32 = extract_song_id(song)?;
-
-    let mut level = level_input.unwrap_or(Level::Ura);
-
-    let (mut filter, song, chart, mut plays) = loop {
-
-        let mut filter = GeneralFilter::new()
-            .song_id(Some(song_id))
-            .level(Some(level));
-
-        let song = ok_or_say_error!(ctx,
-            Song::fetch_one(&mut conn, filter)
-        );
-
-        let chart: Chart = ok_or_say_error!(ctx,
-            Chart::fetch_one(&mut conn, filter)
-        );
-        let mut plays: Vec<(Option<u32>, Play)> = ok_or_say_error!(ctx,
-            Play::fetch_all(&mut conn, filter)
-        ).iter()
-                .copied()
-                .sorted_by_key(|play| -(play.score as i32))
-                .enumerate()
-                .map(|(index, play)| (Some(index as u32), play))
-                .collect();
-
-        if plays.is_empty() && level != Level::Easy{
-            level = level.decrease().unwrap(); //is fine b/c is not easy
-        } else {
-            break (filter, song, chart, plays);
-        }
-
-    };
+    let song: Song = ok_or_say_error!(ctx,
+        Song::fetch_one(&mut conn, song_filter)
+    );
+    let charts: Vec<Chart> = ok_or_say_error!(ctx,
+        Chart::fetch_all(&mut conn, song_filter.level(level_input))
+    );
+    let chart = *charts.iter().max_by_key(|chart| chart.level).unwrap(); //fetch_all never returns empty
+    let level = chart.level();
 
-    let server_players: Vec<u64> = ctx.guild()
-        .ok_or(ElodonError::NoGuild)?
-        .members.keys()
-        .map(|user_id| user_id.get())
-        .collect();
+    let plays: Vec<Play> = Play::fetch_all(&mut conn, chart).await.unwrap_or_default();
+    let author_user = accounts::primary_user(&mut conn, ctx.author().id).await?;
 
-    if exclude_estimates.filter(|exclude_estimates| !exclude_estimates).is_some(){
-        if let Some(ref caller) = author_user{
-            if let Some(sd) = chart.sd_mean {
-                // get approximations
-                const Z_SCORES: [f32; 4] = [-1., 0., 1., 2.];
-                let average_score = get_predicted_score(caller.elo(level.into()), &chart, 0.).unwrap();
-                for z in Z_SCORES {
-                    let estimated_play: Play = Play {
-                        score: (average_score as f32 + sd * z) as u32,
-                        user: 0,
-                        level: u32::from(level),
-                        song: song_id,
-                        crown: 0,
-                        good_cnt: 0,
-                        ok_cnt: 0,
-                        bad_cnt: 0,
-                        roll_cnt: 0,
-                        combo_cnt: 0,
-                        rank: 0
-                    };
-                    plays.push((None, estimated_play));
-                }
-            }
+    let mut response = format!("### {} ({})\n{}\n", song, level, chart.get_display_text());
+    response.push_str(&*format!("{} plays", plays.len()));
+    if let Some(best) = plays.iter().map(|play| play.score).max() {
+        response.push_str(&*format!(", best score {best}"));
+    }
+    response.push('\n');
+
+    let mut predicted_score = None;
+    let mut your_score = None;
+    if let Some(author_user) = author_user {
+        let elo = author_user.elo(level.into());
+        if let Some(predicted) = get_predicted_score(elo, &chart, 0.) {
+            response.push_str(&*format!("Predicted score for {}: `{:>7}`\n", author_user.name, predicted));
+            predicted_score = Some(predicted);
+        }
+        if let Some(play) = plays.iter().find(|play| play.user == author_user.id) {
+            let z_value_txt = match elo::get_z_value(play.score, elo, &chart, 1f32) {
+                Some(z_value) => format!("{:+.1}", z_value),
+                None => "????".to_owned()
+            };
+            response.push_str(&*format!("Your score: `{:>7}` (z {})\n", play.score, z_value_txt));
+            your_score = Some(play.score);
         }
     }
 
-    plays.sort_by_key(|(_, play)| -(play.score as i32));
-
-    let info_filter = filter.user_id(None).discord_id(None);
-
-    let header = &*format!("### Results for {} ({:?}):\n", song, level);
-
-
-    for (index, play) in plays {
-        let ranking = match index {
-            None => {format!("")}
-            Some(index) => { format!("#{})", index + 1) }
-        };
-        let user = match play.fetch_one_other::<User>(&mut conn).await {
-            Ok(user) => {
-                user
-            }
-            Err(_) => {//is a generated play
-                match author_user {
-                    Some(ref author_user) => {
-                        User{
-                            name: format!("{} (estimated)", author_user.name),
-                            ..author_user.clone()
-                        }
-                    }
-                    None => {
-                        panic!("expected user to exist")
-                    }
-                }
-            }
-        };
-
-        // don't include users not in this server
-        if !server_players.contains(&(user.discord as u64)) {continue}
-
-        match detailed{
-            true => {
-                let z_value_txt = match elo::get_z_value(play.score, user.elo(level.into()), &chart, 1f32) {
-                    Some(z_value) => format!("{:+.1}", z_value),
-                    None => "????".to_string()
-                };
-
-                let ur = elo::get_sd(25., play.good_cnt as f64/ (play.good_cnt + play.ok_cnt + 1) as f64);
-
-                let crown_emoji = format!("<:crown_{}:{}>", play.crown, CROWN_IDS.get(play.crown as usize).expect("invalid crown id"));
-                let rank_emoji = if play.rank < 2 {String::new()} else {
-                    format!("<:rank_{}:{}>", play.rank, RANK_IDS.get(play.rank as usize).expect("invalid rank id"))
-                };
-                let good_emoji = format!("<:good:{}>", JUDGEMENT_IDS[3]);
-                let ok_emoji = format!("<:ok:{}>", JUDGEMENT_IDS[2]);
-                let bad_emoji = format!("<:bad_0:{}><:bad_1:{}>", JUDGEMENT_IDS[0], JUDGEMENT_IDS[1]);
-                let combo_emoji = format!("<:combo_0:{}><:combo_1:{}>", COMBO_IDS[0], COMBO_IDS[1]);
-                let rolls_emoji = format!("<:rolls_0:{}><:rolls_1:{}><:rolls_2:{}>", ROLLS_IDS[0], ROLLS_IDS[1], ROLLS_IDS[2]);
-                response.push_str(&*format!("`{:>4}` `{}` **{}**\n⮱`{:>7}` `{:>4}`{} {} {}\t σ<`{:+>3.1}ms`\n⮱`{:>4}`{} `{:>3}`{}` {:>3}`{} `{:>3}`{}\n",
-                                            ranking, z_value_txt, user.name,
-                                            play.score,
-                                            play.combo_cnt, combo_emoji,
-                                            crown_emoji, rank_emoji, ur,
-                                            play.good_cnt, good_emoji,
-                                            play.ok_cnt, ok_emoji,
-                                            play.bad_cnt, bad_emoji,
-                                            play.roll_cnt, rolls_emoji
-                ));
-            }
-            false => {
-                response.push_str(&*format!("`{:>4} {:>7}` by {}\n",
-                    ranking, play.score, user.name
-                ))
-            }
+    let mut embed = serenity::CreateEmbed::default().description(&response);
+    let mut reply = poise::CreateReply::default();
+    let scores: Vec<u32> = plays.iter().map(|play| play.score).collect();
+    match render::score_histogram(&format!("{} ({})", song.get_name(), level), &scores, your_score, predicted_score) {
+        Ok(png) => {
+            reply = reply.attachment(render::attachment(png, "scores"));
+            embed = embed.image("attachment://scores.png");
         }
+        Err(err) => println!("Couldn't draw score histogram: {err}")
     }
+    ctx.send(reply.embed(embed)).await?;
+    Ok(())
+}
 
-    let pages_owned: Vec<String> = response.split("\n")
-        .chunks(27)
-        .into_iter()
-        .map(|mut lines|{
-            lines.join("\n")
-        })
-        .filter(|lines| !lines.is_empty())
-        .collect();
+///get scoreboard of chart via song id and difficulty
+#[poise::command(slash_command)]
+pub async fn scores(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_song"]
+    #[description="song"]
+    song: String,
+    #[description="difficulty"]
+    level_input: Option<Level>,
+    #[description="should good/ok/bad be included or not"]
+    detailed_input: Option<bool>,
+    #[description="exclude self estimates"]
+    exclude_estimates: Option<bool>,
+    #[description="only show the best play of people with several Donder IDs"]
+    merged: Option<bool>,
+    #[description="order of the leaderboard (by default score)"]
+    sort: Option<ScoreSort>,
+    #[description="only this server's members, or everyone (by default the server's setting)"]
+    scope: Option<LeaderboardScope>
+) -> Result<(), Error> {
+    scores_inner(ctx, extract_song_id(song)?, level_input, detailed_input, exclude_estimates, merged, sort, scope).await
+}
 
+async fn scores_inner(
+    ctx: Context<'_>,
+    song_id: u32,
+    level_input: Option<Level>,
+    detailed_input: Option<bool>,
+    exclude_estimates: Option<bool>,
+    merged: Option<bool>,
+    sort: Option<ScoreSort>,
+    scope: Option<LeaderboardScope>
+) -> Result<(), Error> {
+    let mut conn = get_connection().await?;
 
-    let pages: Vec<&str> = pages_owned.iter().map(|s| &**s).collect();
+    ctx.defer().await?;
 
-    paginate::<Data, Error>(ctx, &header, &*pages).await?;
+    let invocation = views::Invocation::new(ctx, &mut conn).await?;
+    let options = views::ScoreOptions {
+        level: level_input,
+        detailed: detailed_input.unwrap_or(true),
+        // estimates are opt-in
+        estimates: exclude_estimates == Some(false),
+        merged: merged.unwrap_or(false),
+        sort: sort.unwrap_or_default(),
+        scope,
+    };
+    // the pages take connections from the pool as they need them, this one isn't held while paging
+    drop(conn);
+    let entries = ok_or_say_error!(ctx,
+        views::ScoreEntries::new(pool(), &invocation, song_id, options)
+    );
 
-    if !warnings.is_empty() {
-        return_err!(ElodonError::List(warnings))
-    } else {
-        Ok(())
-    }
+    // the histogram comes with the entries, they redraw it when the level changes
+    Paginator::new().run(ctx, &mut LazyPages::new("", entries)).await?;
+    Ok(())
 }
 
 fn extract_song_id(song: String) -> Result<u32, ElodonError> {
     song.split(":")
-        .next().ok_or(ElodonError::ParseError(song.clone()))?
-        .parse().map_err(|_| ElodonError::ParseError(song.clone()))
+        .next().ok_or(CoreError::ParseError(song.clone()))?
+        .parse().map_err(|_| CoreError::ParseError(song.clone()).into())
 }
 
 ///get a player via discord id
@@ -327,79 +365,73 @@ pub async fn player(
     ctx: Context<'_>,
     #[description="discord (by default self)"] discord_user_input: Option<UserId>,
     level: Option<DisplayLevel>,
+    #[description="which Donder ID (by default the primary one)"] account: Option<i64>,
+    #[description="combine the best plays of every linked Donder ID"] merged: Option<bool>,
 ) -> Result<(), Error> {
 
     ctx.defer().await?;
 
     let discord_user = discord_user_input.unwrap_or(ctx.author().id);
     let mut conn = get_connection().await?;
-    let mut filter = GeneralFilter::new()
-        .discord_id(Some(discord_user.clone()))
+    let filter = GeneralFilter::new()
+        .discord_id(Some(discord_user.get()))
         .display_level(level);
 
-    let user: User = ok_or_say_error!(ctx,
-        User::fetch_one(&mut conn, filter)
-    );
-    filter.set_user_id(Some(user.id));
-    let songs: Vec<Song> = ok_or_say_error!(ctx,
-        Song::fetch_all(&mut conn, filter)
-    );
-    let plays: Vec<Play> = ok_or_say_error!(ctx,
-        Play::fetch_all(&mut conn, filter)
-    );
-    let discord_user = discord_user.to_user(ctx).await?;
-
-    let info_filter = filter.discord_id(None).user_id(None);
-
+    let users: Vec<User> = match merged.unwrap_or(false) {
+        true => ok_or_say_error!(ctx,
+            User::fetch_all(&mut conn, filter)
+        ),
+        false => vec![ok_or_say_error!(ctx,
+            choose_user(ctx, &mut conn, discord_user, account)
+        )]
+    };
+    let mut profile = views::PlayerProfile::new(&mut conn, &users, discord_user, level).await?;
+    let user_name = profile.name.clone();
+
+    profile.card.avatar = avatar_png(ctx, &discord_user.to_user(ctx).await?).await;
+    let mut paginator = Paginator::new();
+    match render::profile_card(&profile.card) {
+        Ok(png) => paginator = paginator.attachment(render::attachment(png, "profile")),
+        Err(err) => println!("Couldn't draw profile card: {err}")
+    }
 
-    let mut ranked_plays: Vec<(R32, &Play, Genre, String)> = vec![];
-    for play in &plays {
-        if let Some(ranked_play) = get_play_info(&mut conn, user.elo(play.level().into()), &play).await {
-            match level {
-                Some(level) if (level != ranked_play.1.level().into()) => continue,
-                _ => {}
-            }
-            ranked_plays.push(ranked_play);
+    let genre_z: Vec<(String, f32)> = profile.genre_stats.iter()
+        .sorted_by_key(|stats| stats.genre.id())
+        .map(|stats| (stats.genre.name().to_string(), stats.mean_z))
+        .collect();
+    if genre_z.len() >= 3 {
+        match render::radar(&format!("{user_name} by genre (mean z)"), &genre_z) {
+            Ok(png) => paginator = paginator.attachment(render::attachment(png, "genres")),
+            Err(err) => println!("Couldn't draw genre radar: {err}")
         }
     }
-    ranked_plays.sort_by_key(|(z,_,_,_)| *z * r32(-1f32));
-
-    let mut pages_owned: Vec<String> = vec![];
-    let page_count = 1 + (plays.len() / 10);
-    for (i, plays) in plays.chunks(10).enumerate(){
-        let mut response: String = format!("## User <@{}> ({})\n Showing plays{}.\n### Most notable plays\n",
-                                         discord_user.id, user.name, info_filter);
-
-        if ranked_plays.is_empty() {
-            response.push_str("No plays found. Player has no ELO\n");
-        } else {
-            for (z, play, genre, chart_name) in ranked_plays.iter().take(5) {
-                let z_value: f32 = z.raw();
-                response.push_str(&*format!("{:+.2}. {:>7} on {}\n", z_value, play.score, chart_name));
-            }
+    let history = history::elo_history(&mut conn, users[0].id).await?;
+    if history.len() >= 2 {
+        match render::elo_history(&format!("ELO of {}", users[0].name), &history::history_points(&history)) {
+            Ok(png) => paginator = paginator.attachment(render::attachment(png, "elo_history")),
+            Err(err) => println!("Couldn't draw elo history: {err}")
         }
-
-        response.push_str(&*format!("### Filtered plays ({}/{})\n\n```",
-                                    i+1, page_count));
-        for play in plays {
-            match chart_name(&mut conn, play).await{
-                Ok(chart_name) => {
-                    response.push_str(&*format!("{:>7} on {}\n", play.score, chart_name));
-                }
-                Err(_) => {
-                    response.push_str(&*format!("{:>7} on song_id={}\n", play.score, play.song));}
-            }
-        }
-        response.push_str("```");
-        pages_owned.push(response)
     }
 
-    let pages: Vec<&str> = pages_owned.iter().map(|s| &**s).collect();
-    paginate::<Data, Error>(ctx, &"", &pages).await;
+    drop(conn);
+    let mut pages = LazyPages::new("", views::PlayerEntries::new(pool(), users.iter().map(|user| user.id).collect(), level))
+        .preamble(profile.preamble)
+        .code_block(true);
+    paginator.run(ctx, &mut pages).await?;
 
     Ok(())
 }
 
+// png of the avatar for the profile card. None if discord can't be reached
+async fn avatar_png(ctx: Context<'_>, user: &serenity::User) -> Option<Vec<u8>> {
+    // avatar_url() is webp, which the card can't decode
+    let url = match &user.avatar {
+        Some(hash) => format!("https://cdn.discordapp.com/avatars/{}/{}.png?size=256", user.id, hash),
+        None => user.default_avatar_url(),
+    };
+    serenity::CreateAttachment::url(ctx.http(), &url).await.ok().map(|attachment| attachment.data)
+}
+
 //suggest new maps for player
 #[poise::command(track_edits, slash_command)]
 pub async fn suggest(
@@ -408,123 +440,698 @@ pub async fn suggest(
     #[description="the desired z value"] z_input: Option<f32>,
     level_input: Option<DisplayLevel>,
     #[description="discord (by default self)"] discord_user_input: Option<UserId>,
-    dev_info_input: Option<bool>
+    dev_info_input: Option<bool>,
+    #[description="which Donder ID (by default the primary one)"] account: Option<i64>,
+    #[description="only suggest charts from the genres you do worst in"] weak_genres: Option<bool>,
 ) -> Result<(), Error> {
     let score = score_k * 1000;
     let discord_user = discord_user_input.unwrap_or(ctx.author().id);
-    let level = level_input.unwrap_or(DisplayLevel::OniPlus);
+    let level = match level_input {
+        Some(level) => level,
+        None => ctx.data().guild_settings.get(ctx.guild_id()).await?.default_display_level()
+    };
     let desired_z = z_input.unwrap_or(0.);
     let dev_info = dev_info_input.unwrap_or(false);
 
     let mut conn = get_connection().await?;
-    let mut filter = GeneralFilter::new()
-        .discord_id(Some(discord_user.clone()))
-        .display_level(Some(level));
     let user: User = ok_or_say_error!(ctx,
-        User::fetch_one(&mut conn, filter)
+        choose_user(ctx, &mut conn, discord_user, account)
     );
-    filter.set_user_id(Some(user.id));
-
-    let charts: Vec<Chart> = ok_or_say_error!(ctx,
-        Chart::fetch_all(&mut conn, filter)
+    let response_text = ok_or_say_error!(ctx,
+        views::suggestions(&mut conn, &user, level, score, desired_z, weak_genres.unwrap_or(false), dev_info)
     );
 
-    let matching_charts: Vec<(R32, R32, &Chart)> = charts.iter().filter_map(|chart| {
+    ctx.send(poise::CreateReply::default()
+        .embed(serenity::CreateEmbed::default().description(&response_text))
+    ).await?;
 
-        if chart.score_slope? < 0 {return None}
+    Ok(())
+}
 
-        let (z_lower, z_upper) = lower_to_higher(
-            R32::try_new(elo::get_z_value(score, user.elo(level), chart, 1f32)?)?,
-            R32::try_new(elo::get_z_value(score, user.elo(level), chart,-1f32)?)?
-        );
+///list the charts a player holds first place on in this server
+#[poise::command(slash_command, guild_only)]
+pub async fn records(
+    ctx: Context<'_>,
+    #[description="discord (by default self)"] discord_user_input: Option<UserId>,
+    #[description="every record in the server instead"] everyone: Option<bool>,
+    level: Option<DisplayLevel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return_err!(ElodonError::NoGuild) };
+    ctx.defer().await?;
 
-        Some((z_lower, z_upper, chart))
-    }).sorted_by_key(|(z_lower, z_upper, chart)| {
-        (*z_lower - desired_z).abs() + (*z_upper - desired_z).abs()
-    }).take(8).collect();
+    let mut conn = get_connection().await?;
+    let everyone = everyone.unwrap_or(false);
+    let discord_user = discord_user_input.unwrap_or(ctx.author().id);
+    let users: Vec<User> = match everyone {
+        true => User::fetch_all_or_empty(&mut conn, UserFilter::new()).await?,
+        // every linked Donder ID, a record on any of them is theirs
+        false => ok_or_say_error!(ctx,
+            User::fetch_all(&mut conn, UserFilter::new().discord_id(Some(discord_user.get())))
+        )
+    };
+    let names: HashMap<i64, String> = users.into_iter().map(|user| (user.id, user.name)).collect();
+    let records: Vec<records::Record> = records::guild_records(&mut conn, guild_id.get() as i64).await?
+        .into_iter()
+        .filter(|record| names.contains_key(&record.user_id))
+        .filter(|record| level.is_none_or(|level| DisplayLevel::from(record.level()) == level))
+        .collect();
 
-    dbg!(matching_charts.clone());
+    let header = match everyone {
+        true => "### Records in this server:\n".to_string(),
+        false => format!("### Records of {}:\n", discord_user.mention()),
+    };
+    let names = if everyone { names } else { HashMap::new() };
+    drop(conn);
+    let mut pages = LazyPages::new(header, views::RecordEntries::new(pool(), records, names));
+    Paginator::new().run(ctx, &mut pages).await?;
+    Ok(())
+}
 
-    let mut matching_songs: Vec<(R32, R32, Song, Level)> = Vec::with_capacity(matching_charts.len());
+///timed score attacks between this server's members
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("challenge_create", "challenge_list", "challenge_standings", "challenge_cancel")
+)]
+pub async fn challenge(
+    ctx: Context<'_>
+) -> Result<(), Error> { Ok(()) }
 
-    for (z_lower, z_upper, chart) in matching_charts {
+///start a challenge on some charts (needs Manage Server). the biggest improvement on one of them wins
+///
+///there are no star ratings, min_elo and max_elo pick charts by their score_miyabi instead: the ELO
+///at which a 1,000,000 is predicted. plays that show up in the rankings up to 30 minutes after the end
+///still count, the results are posted after that
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "create")]
+pub async fn challenge_create(
+    ctx: Context<'_>,
+    #[description="what it's called"] name: String,
+    #[description="how many days it runs"] days: u32,
+    #[description="hours until it starts (by default now)"] starts_in_hours: Option<u32>,
+    #[description="charts as song id:difficulty, e.g. 12:oni 40:ura"] charts: Option<String>,
+    #[description="every chart of this genre"] genre: Option<Genre>,
+    #[description="every chart of this level"] level: Option<DisplayLevel>,
+    #[description="charts at least this hard, by score_miyabi (the ELO of a 1,000,000) as there are no stars"] min_elo: Option<f32>,
+    #[description="charts at most this hard, by score_miyabi (the ELO of a 1,000,000) as there are no stars"] max_elo: Option<f32>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return_err!(ElodonError::NoGuild) };
+    let pick = challenges::ChartPick {
+        charts: charts.as_deref().map(challenges::parse_charts).transpose()?.unwrap_or_default(),
+        genre,
+        level,
+        min_elo,
+        max_elo,
+    };
 
-        let song = ok_or_say_error!(ctx,
-            Song::fetch_one(&mut conn, *chart)
-        );
+    let mut conn = get_connection().await?;
+    let charts = challenges::pick_charts(&mut conn, &pick).await?;
+    let starts_at = history::now() + starts_in_hours.unwrap_or(0) as i64 * 60 * 60;
+    let ends_at = starts_at + days as i64 * 24 * 60 * 60;
+    let challenge = challenges::create_challenge(&mut conn, guild_id.get() as i64, &name, starts_at, ends_at,
+                                                 ctx.author().id.get() as i64, &charts).await?;
+
+    ctx.say(format!("Challenge #{} **{}** on {} chart{} runs from <t:{}:f> to <t:{}:f>. See /challenge standings",
+                    challenge.id, challenge.name, charts.len(), if charts.len() == 1 { "" } else { "s" },
+                    challenge.starts_at, challenge.ends_at)).await?;
+    Ok(())
+}
 
-        matching_songs.push(
-            (z_lower, z_upper, song, chart.level())
-        );
+///list the running and upcoming challenges
+#[poise::command(slash_command, guild_only, rename = "list")]
+pub async fn challenge_list(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return_err!(ElodonError::NoGuild) };
+    let mut conn = get_connection().await?;
+    let now = history::now();
+    let mut lines = vec![];
+    for challenge in challenges::guild_challenges(&mut conn, guild_id.get() as i64).await? {
+        let charts = challenges::challenge_charts(&mut conn, challenge.id).await?.len();
+        let when = match challenge.has_started(now) {
+            true => format!("ends <t:{}:R>", challenge.ends_at),
+            false => format!("starts <t:{}:R>", challenge.starts_at),
+        };
+        lines.push(format!("`#{}` **{}** ({} chart{}) {}", challenge.id, challenge.name, charts, if charts == 1 { "" } else { "s" }, when));
     }
 
-    let mut response_text = format!("### Songs that <@{}> has a 70% chance of getting a score of {} with a z value of as least {}:\n",
-                                    user.discord_id(), score,  desired_z);
+    let response = match lines.is_empty() {
+        true => "No challenges are running in this server".to_string(),
+        false => format!("### Challenges:\n{}", lines.join("\n")),
+    };
+    ctx.say(response).await?;
+    Ok(())
+}
 
-    let results_text = matching_songs.iter().map(|(z_lower, z_upper, song, level)| {
-        match dev_info {
-            true => {
-                format!("`{:+.2} to {:+.2}` {} ({})", z_lower, z_upper, song.get_name(), level)
-            }
-            false => {
-                format!("{} ({})", song.get_name(), level)
-            }
+///the live standings of a challenge
+#[poise::command(slash_command, guild_only, rename = "standings")]
+pub async fn challenge_standings(
+    ctx: Context<'_>,
+    #[description="which one (by default the one ending first)"] challenge_id: Option<i64>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return_err!(ElodonError::NoGuild) };
+    ctx.defer().await?;
+
+    let mut conn = get_connection().await?;
+    let challenge = match challenge_id {
+        Some(challenge_id) => challenges::challenge(&mut conn, guild_id.get() as i64, challenge_id).await?,
+        None => {
+            let now = history::now();
+            let running = challenges::guild_challenges(&mut conn, guild_id.get() as i64).await?
+                .into_iter()
+                .find(|challenge| challenge.has_started(now));
+            let Some(running) = running else {
+                ctx.say("No challenge is running in this server").await?;
+                return Ok(());
+            };
+            running
         }
-    }).join("\n");
+    };
+    let players = members::server_players(&mut conn, guild_id).await?;
+    let standings = challenges::current_standings(&mut conn, &challenge, &players).await?;
 
-    if results_text.is_empty() {
-         response_text.push_str("No results found");
-    } else {
-        response_text.push_str(&results_text);
+    let ending = match challenge.has_ended(history::now()) {
+        true => "ended",
+        false => "ends",
+    };
+    let header = format!("### Challenge #{} {} ({ending} <t:{}:R>):\n", challenge.id, challenge.name, challenge.ends_at);
+    drop(conn);
+    let mut pages = LazyPages::new(header, views::StandingEntries::new(pool(), standings).await?);
+    Paginator::new().run(ctx, &mut pages).await?;
+    Ok(())
+}
+
+///stop a challenge without announcing winners (needs Manage Server)
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "cancel")]
+pub async fn challenge_cancel(
+    ctx: Context<'_>,
+    #[description="which one, see /challenge list"] challenge_id: i64,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else { return_err!(ElodonError::NoGuild) };
+    let mut conn = get_connection().await?;
+    challenges::cancel_challenge(&mut conn, guild_id.get() as i64, challenge_id).await?;
+    ctx.say(format!("Challenge #{challenge_id} was cancelled")).await?;
+    Ok(())
+}
+
+///link your Donder ID to your discord account
+#[poise::command(slash_command)]
+pub async fn register(
+    ctx: Context<'_>,
+    #[description="your Donder ID (the number on your profile)"] donder_id: i64
+) -> Result<(), Error> {
+    let mut conn = get_connection().await?;
+    let request = accounts::request_link(&mut conn, donder_id, ctx.author().id, false).await?;
+    say_link_instructions(ctx, &request).await
+}
+
+///switch your discord account over to a different Donder ID
+#[poise::command(slash_command)]
+pub async fn relink(
+    ctx: Context<'_>,
+    #[description="the new Donder ID"] donder_id: i64
+) -> Result<(), Error> {
+    let mut conn = get_connection().await?;
+    let request = accounts::request_link(&mut conn, donder_id, ctx.author().id, true).await?;
+    say_link_instructions(ctx, &request).await
+}
+
+async fn say_link_instructions(ctx: Context<'_>, request: &accounts::LinkRequest) -> Result<(), Error> {
+    ctx.send(poise::CreateReply::default()
+        .ephemeral(true)
+        .content(format!(
+            "To prove Donder ID {} is yours, put `{}` somewhere in your in-game name. \
+            Once elodon has scraped your profile again, run /verify. You can change your name back afterwards",
+            request.donder_id, request.code
+        ))
+    ).await?;
+    Ok(())
+}
+
+///finish linking after putting the code in your in-game name
+#[poise::command(slash_command)]
+pub async fn verify(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let mut conn = get_connection().await?;
+    let response = match accounts::confirm_link(&mut conn, ctx.author().id).await? {
+        accounts::LinkStatus::Linked(donder_id) => {
+            format!("Linked Donder ID {donder_id}. Plays will be retrieved on the next scrape")
+        }
+        accounts::LinkStatus::Pending(request) => {
+            format!("Donder ID {} hasn't been scraped yet. Keep `{}` in your name and try again later",
+                    request.donder_id, request.code)
+        }
+        accounts::LinkStatus::CodeMissing(request) => {
+            format!("Couldn't find `{}` in the in-game name of Donder ID {} (last seen as \"{}\"). \
+                    If you just changed it, wait for the next scrape",
+                    request.code, request.donder_id, request.profile_name.unwrap_or_default())
+        }
+    };
+    ctx.send(poise::CreateReply::default().ephemeral(true).content(response)).await?;
+    Ok(())
+}
+
+///unlink your Donder ID from your discord account
+#[poise::command(slash_command)]
+pub async fn unregister(
+    ctx: Context<'_>,
+    #[description="only unlink this Donder ID (by default all of them)"] donder_id: Option<i64>,
+) -> Result<(), Error> {
+    let ctx_id = ctx.id();
+    let confirm_button_id = format!("{ctx_id}confirm");
+    ctx.send(poise::CreateReply::default()
+        .ephemeral(true)
+        .content(match donder_id {
+            Some(donder_id) => format!("This unlinks Donder ID {donder_id} from your account. Are you sure?"),
+            None => "This unlinks all of your Donder IDs from your account. Are you sure?".to_string()
+        })
+        .components(vec![serenity::CreateActionRow::Buttons(vec![
+            serenity::CreateButton::new(&confirm_button_id)
+                .style(serenity::ButtonStyle::Danger)
+                .label("Unregister")
+        ])])
+    ).await?;
+
+    let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id == confirm_button_id)
+        .timeout(std::time::Duration::from_secs(60))
+        .await else {
+        return Ok(());
+    };
+
+    let mut conn = get_connection().await?;
+    let donder_ids = accounts::unlink(&mut conn, ctx.author().id, donder_id).await?;
+    press.create_response(ctx.serenity_context(), serenity::CreateInteractionResponse::UpdateMessage(
+        serenity::CreateInteractionResponseMessage::new()
+            .content(format!("Unlinked Donder ID {}", donder_ids.iter().join(", ")))
+            .components(vec![])
+    )).await?;
+    Ok(())
+}
+
+///manage your linked Donder IDs
+#[poise::command(slash_command, subcommands("account_list", "account_primary"))]
+pub async fn account(
+    ctx: Context<'_>
+) -> Result<(), Error> { Ok(()) }
+
+///list the Donder IDs linked to your discord
+#[poise::command(slash_command, rename = "list")]
+pub async fn account_list(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let mut conn = get_connection().await?;
+    let users = accounts::linked_users(&mut conn, ctx.author().id).await?;
+    if users.is_empty() {
+        return_err!(ElodonError::NotRegistered)
     }
+    let has_primary = accounts::has_primary(&mut conn, ctx.author().id).await?;
 
+    let response = users.iter().enumerate().map(|(i, user)| {
+        let primary = if i == 0 && has_primary { " (primary)" } else { "" };
+        format!("`#{:<13}` {}{}", user.id, user.name, primary)
+    }).join("\n");
+    ctx.send(poise::CreateReply::default().ephemeral(true).content(response)).await?;
+    Ok(())
+}
+
+///pick the Donder ID commands use by default
+#[poise::command(slash_command, rename = "primary")]
+pub async fn account_primary(
+    ctx: Context<'_>,
+    #[description="one of your linked Donder IDs"] donder_id: i64,
+) -> Result<(), Error> {
+    let mut conn = get_connection().await?;
+    accounts::set_primary(&mut conn, ctx.author().id, donder_id).await?;
     ctx.send(poise::CreateReply::default()
-        .embed(serenity::CreateEmbed::default().description(&response_text))
+        .ephemeral(true)
+        .content(format!("Donder ID {donder_id} is now your primary account"))
+    ).await?;
+    Ok(())
+}
+
+//which linked account of discord_user a command is about. account picks one directly, otherwise it's
+//the primary one. if there are several and none is primary the caller gets to choose
+async fn choose_user(ctx: Context<'_>, conn: &mut SqliteConnection, discord_user: UserId, account: Option<i64>) -> Result<User, ElodonError> {
+    let users = accounts::linked_users(conn, discord_user).await?;
+    if let Some(account) = account {
+        return users.into_iter()
+            .find(|user| user.id == account)
+            .ok_or(ElodonError::NotLinkedAccount(account));
+    }
+    if users.is_empty() {
+        return Err(CoreError::NoResults {
+            search: "users".to_owned(),
+            id: format!("discord <@{}>", discord_user.get()),
+        }.into());
+    }
+    if users.len() == 1 || accounts::has_primary(conn, discord_user).await? {
+        return Ok(users.into_iter().next().unwrap());
+    }
+
+    let ctx_id = ctx.id();
+    let buttons: Vec<serenity::CreateButton> = users.iter().take(25).map(|user| {
+        serenity::CreateButton::new(format!("{ctx_id}account{}", user.id))
+            .style(serenity::ButtonStyle::Secondary)
+            .label(format!("{} (#{})", user.name, user.id))
+    }).collect();
+    let reply = ctx.send(poise::CreateReply::default()
+        .content("That discord has several Donder IDs linked. Which one? (/account primary skips this)")
+        .components(buttons.chunks(5).map(|row| serenity::CreateActionRow::Buttons(row.to_vec())).collect())
     ).await?;
 
+    let press = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&format!("{ctx_id}account")))
+        .timeout(std::time::Duration::from_secs(120))
+        .await;
+    let Some(press) = press else {
+        reply.edit(ctx, poise::CreateReply::default().content("No account was picked").components(vec![])).await?;
+        return Err(ElodonError::NoAccountChosen);
+    };
+
+    let chosen = press.data.custom_id.trim_start_matches(&format!("{ctx_id}account")).parse::<i64>().ok();
+    let user = users.into_iter()
+        .find(|user| Some(user.id) == chosen)
+        .ok_or(ElodonError::NoAccountChosen)?;
+    press.create_response(ctx.serenity_context(), serenity::CreateInteractionResponse::UpdateMessage(
+        serenity::CreateInteractionResponseMessage::new()
+            .content(format!("Showing {} (#{})", user.name, user.id))
+            .components(vec![])
+    )).await?;
+    Ok(user)
+}
+
+///manage community nicknames for songs
+#[poise::command(slash_command, subcommands("alias_add", "alias_remove", "alias_list"))]
+pub async fn alias(
+    ctx: Context<'_>
+) -> Result<(), Error> { Ok(()) }
+
+///add a nickname that song search and autocomplete will also match
+#[poise::command(slash_command, rename = "add")]
+pub async fn alias_add(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_song"]
+    #[description="song"]
+    song: String,
+    #[description="nickname e.g. Dondon"]
+    alias: String,
+) -> Result<(), Error> {
+    ensure_alias_editor(ctx).await?;
+
+    let alias = alias.trim();
+    if alias.is_empty() || alias.chars().count() > MAX_ALIAS_LENGTH {
+        return_err!(CoreError::InvalidAlias(alias.to_string()))
+    }
+
+    let mut conn = get_connection().await?;
+    let song: Song = ok_or_say_error!(ctx,
+        Song::fetch_one(&mut conn, SongFilter::new().song_id(Some(extract_song_id(song)?)))
+    );
+    song.add_alias(&mut conn, alias, ctx.author().id.get()).await?;
+    refresh_song_autocomplete(ctx, &mut conn).await?;
+
+    ctx.say(format!("\"{alias}\" now finds {song}")).await?;
     Ok(())
 }
 
-fn lower_to_higher(a: R32, b: R32) -> (R32, R32) {
-    return (
-        a.min(b),
-        a.max(b)
-        )
+///remove a nickname from a song
+#[poise::command(slash_command, rename = "remove")]
+pub async fn alias_remove(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_song"]
+    #[description="song"]
+    song: String,
+    #[description="nickname to remove"]
+    alias: String,
+) -> Result<(), Error> {
+    ensure_alias_editor(ctx).await?;
+
+    let mut conn = get_connection().await?;
+    let song: Song = ok_or_say_error!(ctx,
+        Song::fetch_one(&mut conn, SongFilter::new().song_id(Some(extract_song_id(song)?)))
+    );
+    ok_or_say_error!(ctx,
+        song.remove_alias(&mut conn, alias.trim())
+    );
+    refresh_song_autocomplete(ctx, &mut conn).await?;
+
+    ctx.say(format!("Removed \"{}\" from {song}", alias.trim())).await?;
+    Ok(())
 }
 
-async fn chart_name(conn: &mut SqliteConnection, play: &Play) -> Result<String, ElodonError>{
-    let chart = play.fetch_one_other::<Chart>(conn).await?;
-    let chart_name = chart.full_name(conn).await?;
-    return Ok(chart_name);
+///list the nicknames of a song
+#[poise::command(slash_command, rename = "list")]
+pub async fn alias_list(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_song"]
+    #[description="song"]
+    song: String,
+) -> Result<(), Error> {
+    let mut conn = get_connection().await?;
+    let song: Song = ok_or_say_error!(ctx,
+        Song::fetch_one(&mut conn, SongFilter::new().song_id(Some(extract_song_id(song)?)))
+    );
+    let aliases = song.get_aliases(&mut conn).await?;
+
+    let response = if aliases.is_empty() {
+        format!("{song} has no aliases")
+    } else {
+        format!("### Aliases for {song}:\n{}", aliases.iter().map(|alias| format!("- {alias}")).join("\n"))
+    };
+    ctx.say(response).await?;
+    Ok(())
 }
 
+//owners can always edit. everyone else needs the role set with /config alias_role
+async fn ensure_alias_editor(ctx: Context<'_>) -> Result<(), ElodonError> {
+    if ctx.framework().options().owners.contains(&ctx.author().id) {
+        return Ok(());
+    }
+    let role = ctx.data().guild_settings.get(ctx.guild_id()).await?.alias_role;
+    let has_role = match (role, ctx.author_member().await) {
+        (Some(role), Some(member)) => member.roles.contains(&role),
+        _ => false
+    };
+    match (has_role, role) {
+        (true, _) => Ok(()),
+        (false, Some(role)) => Err(ElodonError::MissingRole(role.mention().to_string())),
+        (false, None) => Err(ElodonError::MissingRole("alias editor (not configured, see /config alias_role)".to_owned())),
+    }
+}
 
-#[poise::command(slash_command)]
-pub async fn register(
+///server settings (needs Manage Server)
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    subcommands("config_show", "config_prefix", "config_reaction", "config_level", "config_scope",
+                "config_alias_role", "config_announce", "config_digest", "config_ban", "config_unban", "config_emoji")
+)]
+pub async fn config(
+    ctx: Context<'_>
+) -> Result<(), Error> { Ok(()) }
+
+///show the current settings
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "show")]
+pub async fn config_show(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let settings = ctx.data().guild_settings.get(ctx.guild_id()).await?;
+    say_settings(ctx, &settings).await
+}
+
+///prefix for text commands (empty resets to ~)
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "prefix")]
+pub async fn config_prefix(
+    ctx: Context<'_>,
+    #[description="new prefix"] prefix: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        settings.prefix = prefix.map(|prefix| prefix.trim().to_string()).filter(|prefix| !prefix.is_empty());
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+///word that makes elodon react with 💜 ("off" disables, empty resets)
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "reaction")]
+pub async fn config_reaction(
     ctx: Context<'_>,
-    donder_id: i64
+    #[description="trigger word"] trigger: Option<String>,
 ) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        settings.reaction_trigger = match trigger.map(|trigger| trigger.trim().to_ascii_lowercase()) {
+            Some(trigger) if trigger == "off" => Some(String::new()),
+            Some(trigger) if !trigger.is_empty() => Some(trigger),
+            _ => None
+        };
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+///difficulty used when a command isn't given one
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "level")]
+pub async fn config_level(
+    ctx: Context<'_>,
+    #[description="default difficulty (empty resets to Ura)"] level: Option<Level>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        settings.default_level = level;
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+///whose plays scoreboards show
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "scope")]
+pub async fn config_scope(
+    ctx: Context<'_>,
+    #[description="only this server's members, or everyone"] scope: LeaderboardScope,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        settings.leaderboard_scope = scope;
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+///role that can edit song aliases
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "alias_role")]
+pub async fn config_alias_role(
+    ctx: Context<'_>,
+    #[description="role (empty means only the bot owner)"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        settings.alias_role = role.map(|role| role.id);
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+///channel new personal bests of this server's members are posted in
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "announce")]
+pub async fn config_announce(
+    ctx: Context<'_>,
+    #[description="channel (empty turns announcements off)"] channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        settings.announce_channel = channel.map(|channel| channel.id);
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+///channel the weekly digest of this server's members is posted in
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "digest")]
+pub async fn config_digest(
+    ctx: Context<'_>,
+    #[description="channel (empty turns the digest off)"] channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        settings.digest_channel = channel.map(|channel| channel.id);
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+///stop someone from using elodon in this server
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "ban")]
+pub async fn config_ban(
+    ctx: Context<'_>,
+    #[description="discord user"] user: UserId,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        if !settings.banned.contains(&user) {
+            settings.banned.push(user);
+        }
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+///let someone use elodon again
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "unban")]
+pub async fn config_unban(
+    ctx: Context<'_>,
+    #[description="discord user"] user: UserId,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    let settings = ctx.data().guild_settings.update(guild_id, |settings| {
+        settings.banned.retain(|banned| *banned != user);
+    }).await?;
+    say_settings(ctx, &settings).await
+}
+
+async fn autocomplete_emoji_name<'a>(
+    _ctx: Context<'a>,
+    partial: &'a str,
+) -> impl Stream<Item = String> + 'a {
+    futures::stream::iter(emoji::FALLBACKS)
+        .filter(move |(name, _)| futures::future::ready(name.contains(partial)))
+        .map(|(name, _)| name.to_owned())
+}
+
+///use a different emoji in this server, e.g. when elodon can't see the default one
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", rename = "emoji")]
+pub async fn config_emoji(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_emoji_name"]
+    #[description="which emoji"] name: String,
+    #[description="custom emoji or text to use instead (empty resets)"] emoji: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+    if !emoji::is_known(&name) {
+        return_err!(ElodonError::UnknownEmoji(name))
+    }
+    let value = emoji.map(|emoji| match serenity::parse_emoji(&emoji) {
+        Some(custom) => emoji::EmojiValue::Custom { id: custom.id, animated: custom.animated },
+        None => emoji::EmojiValue::Text(emoji.trim().to_string())
+    });
+
     let mut conn = get_connection().await?;
-     match sqlx::query("INSERT INTO users (user_id, discord_id, user_name) VALUES (?,?,?);")
-         .bind(donder_id)
-         .bind(ctx.author().id.get() as i64)
-         .bind("temp_name")
-         .execute(&mut conn).await {
-         Ok(_) => {
-             ctx.say("Adding user. data will be retrieved on the next scrape").await?;
-         }
-         Err(err) => {
-             return_err!(
-                 ElodonError::DatabaseError(err)
-             )
-         }
-     }
+    emoji::set(&mut conn, Some(guild_id), &name, value).await?;
+    ctx.send(poise::CreateReply::default()
+        .ephemeral(true)
+        .content(format!("{name} is now {}", emoji::emojis(Some(guild_id)).get(&name)))
+    ).await?;
+    Ok(())
+}
+
+async fn say_settings(ctx: Context<'_>, settings: &GuildSettings) -> Result<(), Error> {
+    let response = format!(
+        "### Settings\n\
+        prefix: `{}`\n\
+        reaction trigger: {}\n\
+        default level: {}\n\
+        scoreboard scope: {}\n\
+        alias role: {}\n\
+        announcements: {}\n\
+        weekly digest: {}\n\
+        banned: {}",
+        settings.prefix(),
+        settings.reaction_trigger().map(|trigger| format!("`{trigger}`")).unwrap_or("off".to_owned()),
+        settings.default_level(),
+        settings.leaderboard_scope,
+        settings.alias_role.map(|role| role.mention().to_string()).unwrap_or("none".to_owned()),
+        settings.announce_channel.map(|channel| channel.mention().to_string()).unwrap_or("off".to_owned()),
+        settings.digest_channel.map(|channel| channel.mention().to_string()).unwrap_or("off".to_owned()),
+        if settings.banned.is_empty() { "nobody".to_owned() } else { settings.banned.iter().map(|user| user.mention()).join(", ") }
+    );
+    ctx.send(poise::CreateReply::default()
+        .ephemeral(true)
+        .allowed_mentions(serenity::CreateAllowedMentions::new())
+        .content(response)
+    ).await?;
     Ok(())
 }
 
 ///DEV USE. refreshed slash commands
 
-#[poise::command(slash_command, subcommands("kill", "sql", "register_commands"), owners_only)]
+#[poise::command(slash_command, subcommands("kill", "sql", "register_commands", "emoji", "model_eval"), owners_only)]
 pub async fn dev(
     ctx: Context<'_>
 ) -> Result<(), Error> { Ok(()) }
@@ -535,7 +1142,7 @@ pub async fn kill(
     ctx: Context<'_>
 ) -> Result<(), Error> {
     return_err!(
-    ElodonError::Shutdown(std::io::Error::new(ErrorKind::Interrupted, "Manual shutdown"))
+    ElodonError::Core(CoreError::Shutdown(std::io::Error::new(ErrorKind::Interrupted, "Manual shutdown")))
     )
 }
 
@@ -546,6 +1153,73 @@ pub async fn register_commands(
     poise::builtins::register_application_commands_buttons(ctx).await?;
     Ok(())
 }
+#[poise::command(slash_command, subcommands("emoji_sync"), owners_only)]
+pub async fn emoji(
+    ctx: Context<'_>
+) -> Result<(), Error> { Ok(()) }
+
+///upload the bundled emoji artwork that's missing and use it
+#[poise::command(slash_command, owners_only, rename = "sync")]
+pub async fn emoji_sync(
+    ctx: Context<'_>,
+    #[description="upload to this server instead of as application emojis"] to_server: Option<bool>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let assets = provision::load_assets(std::path::Path::new(provision::DEFAULT_ASSET_DIR));
+    let mut conn = get_connection().await?;
+    let http = ctx.http();
+
+    let report = match to_server.unwrap_or(false) {
+        true => {
+            let guild_id = ctx.guild_id().ok_or(ElodonError::NoGuild)?;
+            provision::provision(&mut conn, &provision::GuildEmojis { http, guild_id }, &assets).await?
+        }
+        false => provision::provision(&mut conn, &provision::ApplicationEmojis { http }, &assets).await?
+    };
+
+    let mut response = format!("{} assets: {} uploaded, {} already there",
+                               assets.len(), report.uploaded.len(), report.existing.len());
+    for (name, err) in &report.failed {
+        response.push_str(&*format!("\n{name} failed: {err}"));
+    }
+    ctx.say(response).await?;
+    Ok(())
+}
+
+///backtest elo and the latent factor model on held back plays
+#[poise::command(slash_command, owners_only, rename = "eval")]
+pub async fn model_eval(
+    ctx: Context<'_>,
+    #[description="skills learned per player (default 4)"] factors: Option<usize>,
+    #[description="passes over the training plays (default 40)"] epochs: Option<usize>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let mut conn = get_connection().await?;
+    let dataset = eval::Dataset::load(&mut conn).await?;
+    let default = elo::LatentConfig::default();
+    let config = elo::LatentConfig {
+        factors: factors.unwrap_or(default.factors),
+        epochs: epochs.unwrap_or(default.epochs),
+        ..default
+    };
+
+    // fitting takes a while with a lot of plays
+    let backtest = tokio::task::spawn_blocking(move || eval::backtest(&dataset, config)).await?;
+
+    let mut response = format!("{} plays to fit on, {} held back\n", backtest.train, backtest.test);
+    response.push_str("```\nmodel            RMSE  mean z  sd z  |z|<=1  |z|<=2   70%   plays\n");
+    for report in backtest.models {
+        response.push_str(&*format!("{:<14} {:>6.0} {:>+7.2} {:>5.2} {:>6.1}% {:>6.1}% {:>4.1}% {:>7}\n",
+            report.name, report.rmse, report.mean_z, report.sd_z,
+            report.within_one * 100., report.within_two * 100., report.coverage * 100., report.count));
+    }
+    response.push_str("```\ncalibrated z values have mean 0, sd 1, 68.3% within 1 and 95.4% within 2. \
+        70% is how often the score landed in the range suggest calls a 70% chance. \
+        scraper elo has seen the test plays");
+    ctx.say(response).await?;
+    Ok(())
+}
+
 #[poise::command(slash_command, subcommands("execute", "fetch"), owners_only)]
 pub async fn sql(
     ctx: Context<'_>
@@ -562,7 +1236,7 @@ pub async fn execute(
         }
         Err(db_err) => {
             return_err!(
-                ElodonError::DatabaseError(db_err)
+                CoreError::DatabaseError(db_err)
             )
         }
     }
@@ -595,60 +1269,65 @@ pub async fn fetch(
         Some(song) => Some(extract_song_id(song)?)
     };
 
-    let mut conn = get_connection().await?;
-
     let filter = GeneralFilter{
         user_id: donder,
-        discord_id: discord,
+        discord_id: discord.map(|discord| discord.get()),
         song_id,
         level: chart_level,
         display_level,
         genre,
+        guild_id: None,
     };
 
-    let response: String = match table{
-        FilterType::User => {
-            User::fetch_all(&mut conn, filter).await?.get_display_text()
-        }
-        FilterType::Song => {
-            Song::fetch_all(&mut conn, filter).await?.get_display_text()
-        }
-        FilterType::Chart => {
-            Chart::fetch_all(&mut conn, filter).await?.get_display_text()
-        }
-        FilterType::Play => {
-            Play::fetch_all(&mut conn, filter).await?.get_display_text()
-        }
-    };
-
-    let pages_owned: Vec<String> = response.split("\n")
-        .chunks(18)
-        .into_iter()
-        .map(|mut lines|{
-            lines.join("\n")
-        })
-        .filter(|lines| !lines.is_empty())
-        .collect();
+    match table{
+        FilterType::User => paginate_table::<User>(ctx, filter).await,
+        FilterType::Song => paginate_table::<Song>(ctx, filter).await,
+        FilterType::Chart => paginate_table::<Chart>(ctx, filter).await,
+        FilterType::Play => paginate_table::<Play>(ctx, filter).await,
+    }
+}
 
+// rows of one table, a page at a time
+struct TableEntries<R> {
+    pool: SqlitePool,
+    filter: GeneralFilter,
+    emojis: emoji::EmojiSet,
+    rows: PhantomData<R>,
+}
 
-    let pages: Vec<&str> = pages_owned.iter().map(|s| &**s).collect();
+impl<R: Filterable + ElodonDisplay> EntrySource for TableEntries<R> {
+    async fn count(&mut self) -> Result<usize, ElodonError> {
+        Ok(R::count(&mut *self.pool.acquire().await?, self.filter).await?)
+    }
 
-    paginate::<Data, Error>(ctx, &"mrrp", &*pages).await?;
+    async fn entries(&mut self, offset: usize, limit: usize) -> Result<Vec<String>, ElodonError> {
+        Ok(R::fetch_page(&mut *self.pool.acquire().await?, self.filter, offset, limit).await?
+            .iter()
+            .map(|row| row.display_with(&self.emojis))
+            .collect())
+    }
+}
 
+async fn paginate_table<R: Filterable + ElodonDisplay>(ctx: Context<'_>, filter: GeneralFilter) -> Result<(), Error> {
+    let entries = TableEntries::<R> { pool: pool(), filter, emojis: emoji::emojis(ctx.guild_id()), rows: PhantomData };
+    Paginator::new().run(ctx, &mut LazyPages::new("mrrp", entries)).await?;
     Ok(())
 }
 
 
 
-async fn get_play_info<'a>(conn: &mut SqliteConnection, elo: Option<f32>, play: &'a Play) -> Option<(R32, &'a Play, Genre, String)>{
-    let chart = play.fetch_one_other::<Chart>(conn).await.ok()?;
-    let song = play.fetch_one_other::<Song>(conn).await.ok()?;
-    let chart_name = chart.full_name(conn).await.ok()?;
-    let z_value = R32::try_new(elo::get_z_value(play.score, elo, &chart, 1f32)?)?;
-    Some((z_value, &play, song.genre(), chart_name))
-}
 
 
-pub(crate) async fn get_connection() -> Result<SqliteConnection, ElodonError>{
-    Ok(SqliteConnection::connect("./../taiko.db").await?)
+
+// shared by the commands, the background jobs and the http api. connections are opened on first use
+static POOL: Lazy<SqlitePool> = Lazy::new(|| {
+    SqlitePoolOptions::new().connect_lazy_with(SqliteConnectOptions::new().filename("./../taiko.db"))
+});
+
+pub(crate) fn pool() -> SqlitePool {
+    POOL.clone()
+}
+
+pub(crate) async fn get_connection() -> Result<PoolConnection<Sqlite>, ElodonError>{
+    Ok(POOL.acquire().await?)
 }
@@ -0,0 +1,128 @@
+pub use elodon_core::digest::*;
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use poise::serenity_prelude as serenity;
+use sqlx::SqliteConnection;
+
+use crate::commands::get_connection;
+use crate::display::Symbols;
+use crate::emoji::EmojiSet;
+use crate::error::ElodonError;
+use crate::events::PlayEvent;
+use crate::filters::*;
+use crate::history::now;
+use crate::structs::*;
+use crate::{emoji, members, settings};
+
+// embed field values can't be longer than 1024 characters
+const MAX_FIELD: usize = 1000;
+
+// posts the digest of the last week in every server that has a digest channel. a server whose
+// digest fails is skipped, the others still get theirs
+pub async fn post_digests(ctx: &serenity::Context) -> Result<(), ElodonError> {
+    let mut conn = get_connection().await?;
+    let since = now() - DIGEST_PERIOD.as_secs() as i64;
+    for (guild_id, channel_id) in settings::digest_channels(&mut conn).await? {
+        let players = members::server_players(&mut conn, guild_id).await?;
+        let week = match digest(&mut conn, &players, since).await {
+            Ok(week) => week,
+            Err(err) => {
+                println!("Collecting the weekly digest of {guild_id} failed: {err}");
+                continue;
+            }
+        };
+        if week.is_empty() {
+            continue;
+        }
+        let embed = match render(&mut conn, &week, &emoji::emojis(Some(guild_id))).await {
+            Ok(embed) => embed,
+            Err(err) => {
+                println!("Drawing up the weekly digest of {guild_id} failed: {err}");
+                continue;
+            }
+        };
+        let message = serenity::CreateMessage::new()
+            .embed(embed)
+            .allowed_mentions(serenity::CreateAllowedMentions::new());
+        if let Err(err) = channel_id.send_message(&ctx.http, message).await {
+            println!("Posting the weekly digest in {channel_id} failed: {err}");
+        }
+    }
+    Ok(())
+}
+
+async fn render(conn: &mut SqliteConnection, week: &Digest, symbols: &EmojiSet) -> Result<serenity::CreateEmbed, ElodonError> {
+    let mut songs: HashMap<u32, Song> = HashMap::new();
+    let mut names: HashMap<i64, String> = HashMap::new();
+    let mut embed = serenity::CreateEmbed::new().title("This week");
+
+    for (level, gains) in &week.elo_gains {
+        let lines = gains.iter().map(|(user, gain)| format!("**{}** +{:.0}", user.name, gain));
+        embed = embed.field(format!("Biggest ELO gains ({})", level.name()), field(lines), true);
+    }
+    if !week.most_improved.is_empty() {
+        let lines = week.most_improved.iter()
+            .map(|(user, count)| format!("**{}** {} personal best{}", user.name, count, if *count == 1 { "" } else { "s" }));
+        embed = embed.field("Most improved", field(lines), false);
+    }
+
+    let parts = [
+        ("Top plays", &week.top_plays),
+        ("New first places", &week.first_places),
+        ("Newly cleared", &week.cleared),
+    ];
+    for (title, events) in parts {
+        if events.is_empty() {
+            continue;
+        }
+        let mut lines = Vec::with_capacity(events.len());
+        for event in events {
+            match play_line(conn, event, symbols, &mut songs, &mut names).await {
+                Ok(line) => lines.push(line),
+                Err(err) => println!("Describing a play of {} on song {} for the digest failed: {err}", event.user, event.song),
+            }
+        }
+        if lines.is_empty() {
+            continue;
+        }
+        embed = embed.field(title, field(lines.into_iter()), false);
+    }
+    Ok(embed)
+}
+
+async fn play_line(conn: &mut SqliteConnection, event: &PlayEvent, symbols: &EmojiSet,
+                   songs: &mut HashMap<u32, Song>, names: &mut HashMap<i64, String>) -> Result<String, ElodonError> {
+    let song = match songs.entry(event.song) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(Song::fetch_one(conn, GeneralFilter::new().song_id(Some(event.song))).await?),
+    };
+    let name = match names.entry(event.user) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(User::fetch_one(conn, UserFilter::new().user_id(Some(event.user))).await?.name),
+    };
+    let mut line = format!("**{}** {} ({}) {} {}{}",
+                           name, song.get_name(), event.level(), event.new.score,
+                           symbols.crown(event.new.crown), symbols.rank(event.new.rank));
+    if let Some(z) = event.z {
+        line.push_str(&format!(" `z {z:+.2}`"));
+    }
+    Ok(line)
+}
+
+// as many lines as fit in a field
+fn field(lines: impl Iterator<Item = String>) -> String {
+    let mut value = String::new();
+    for line in lines {
+        if value.len() + line.len() + 1 > MAX_FIELD {
+            value.push('…');
+            break;
+        }
+        if !value.is_empty() {
+            value.push('\n');
+        }
+        value.push_str(&line);
+    }
+    value
+}
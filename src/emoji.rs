@@ -1,48 +1,39 @@
-use std::sync::OnceLock;
-use std::string::ToString;
-use lazy_static::lazy::Lazy;
-use poise::serenity_prelude::{EmojiId, ReactionType};
-
-pub const UP: ReactionType = ReactionType::Custom {
-    animated: false,
-    id: EmojiId::new(1197234127494201467),
-    name: None,
-};
-pub const DOWN: ReactionType = ReactionType::Custom {
-    animated: false,
-    id: EmojiId::new(1197234114999357550),
-    name: None,
-};
-pub const LEFT: ReactionType = ReactionType::Custom {
-    animated: false,
-    id: EmojiId::new(1197234117406896208),
-    name: None,
-};
-pub const RIGHT: ReactionType = ReactionType::Custom {
-    animated: false,
-    id: EmojiId::new(1197234122012233898),
-    name: None,
-};
-
-pub const COMBO_IDS: [u64;2] = [
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use poise::serenity_prelude::{Cache, CreateButton, EmojiId, GuildId, ReactionType};
+use sqlx::{FromRow, SqliteConnection};
+
+use crate::display::{Symbols, CROWNS, RANKS};
+use crate::error::ElodonError;
+
+// built-in defaults. these live in the original elodon server, anywhere else the
+// registry (emojis table, /config emoji) or the text fallback is used instead
+const UP: u64 = 1197234127494201467;
+const DOWN: u64 = 1197234114999357550;
+const LEFT: u64 = 1197234117406896208;
+const RIGHT: u64 = 1197234122012233898;
+
+const COMBO_IDS: [u64;2] = [
     1213193226119413810,
     1213193227629232229
 ];
 
-pub const ROLLS_IDS: [u64;3] = [
+const ROLLS_IDS: [u64;3] = [
     1213203737674252318,
     1213203739742306304,
     1213203741906444318
 ];
 
-pub const JUDGEMENT_IDS: [u64;4] = [
+const JUDGEMENT_IDS: [u64;4] = [
     1213185463419142206,
     1213185465201725520,
     1213185469207023646,
     1213185467009335427
 ];
 
-pub const CROWN_IDS: [u64;4] = [
+const CROWN_IDS: [u64;4] = [
     1213187539750486046,
     1213187542263009351,
     1213187545383575593,
@@ -50,7 +41,7 @@ pub const CROWN_IDS: [u64;4] = [
 ];
 
 
-pub const RANK_IDS: [u64;9] = [
+const RANK_IDS: [u64;9] = [
     1213187552673144903,
     1213187552673144903,
     1213187554837536788,
@@ -60,4 +51,302 @@ pub const RANK_IDS: [u64;9] = [
     1213187562559111248,
     1213187564442226688,
     1213187566220742656,
-];
\ No newline at end of file
+];
+
+// every emoji elodon uses, with the text shown when the custom emoji can't be used
+pub const FALLBACKS: [(&str, &str); 28] = [
+    ("up", "⬆️"),
+    ("down", "⬇️"),
+    ("left", "⬅️"),
+    ("right", "➡️"),
+    ("first", "⏮️"),
+    ("last", "⏭️"),
+    ("combo_0", "combo"),
+    ("combo_1", ""),
+    ("rolls_0", "rolls"),
+    ("rolls_1", ""),
+    ("rolls_2", ""),
+    ("good", "良"),
+    ("ok", "可"),
+    ("bad_0", "不可"),
+    ("bad_1", ""),
+    ("crown_0", CROWNS[0]),
+    ("crown_1", CROWNS[1]),
+    ("crown_2", CROWNS[2]),
+    ("crown_3", CROWNS[3]),
+    ("rank_0", RANKS[0]),
+    ("rank_1", RANKS[1]),
+    ("rank_2", RANKS[2]),
+    ("rank_3", RANKS[3]),
+    ("rank_4", RANKS[4]),
+    ("rank_5", RANKS[5]),
+    ("rank_6", RANKS[6]),
+    ("rank_7", RANKS[7]),
+    ("rank_8", RANKS[8]),
+];
+
+pub fn is_known(name: &str) -> bool {
+    FALLBACKS.iter().any(|(known, _)| *known == name)
+}
+
+fn fallback(name: &str) -> &'static str {
+    FALLBACKS.iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, text)| *text)
+        .unwrap_or("")
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EmojiValue {
+    Custom {
+        id: EmojiId,
+        animated: bool,
+    },
+    Text(String),
+}
+
+#[derive(Clone, Debug, FromRow)]
+struct EmojiRow {
+    guild_id: i64,
+    name: String,
+    emoji_id: Option<i64>,
+    animated: bool,
+    text: Option<String>,
+}
+
+impl From<EmojiRow> for EmojiValue {
+    fn from(row: EmojiRow) -> Self {
+        match row.emoji_id {
+            Some(id) => EmojiValue::Custom { id: EmojiId::new(id as u64), animated: row.animated },
+            None => EmojiValue::Text(row.text.unwrap_or_default())
+        }
+    }
+}
+
+pub struct EmojiRegistry {
+    // defaults overridden by the guild_id = 0 rows
+    global: HashMap<String, EmojiValue>,
+    guilds: HashMap<GuildId, HashMap<String, EmojiValue>>,
+    // custom emojis the bot has access to. None until the cache is ready
+    usable: Option<HashSet<EmojiId>>,
+    // application emojis, usable everywhere but not part of any guild
+    application: HashSet<EmojiId>,
+}
+
+impl Default for EmojiRegistry {
+    fn default() -> Self {
+        let mut global = HashMap::new();
+        let mut insert = |name: String, id: u64| {
+            global.insert(name, EmojiValue::Custom { id: EmojiId::new(id), animated: false });
+        };
+        insert("up".to_owned(), UP);
+        insert("down".to_owned(), DOWN);
+        insert("left".to_owned(), LEFT);
+        insert("right".to_owned(), RIGHT);
+        COMBO_IDS.iter().enumerate().for_each(|(i, id)| insert(format!("combo_{i}"), *id));
+        ROLLS_IDS.iter().enumerate().for_each(|(i, id)| insert(format!("rolls_{i}"), *id));
+        CROWN_IDS.iter().enumerate().for_each(|(i, id)| insert(format!("crown_{i}"), *id));
+        RANK_IDS.iter().enumerate().for_each(|(i, id)| insert(format!("rank_{i}"), *id));
+        insert("bad_0".to_owned(), JUDGEMENT_IDS[0]);
+        insert("bad_1".to_owned(), JUDGEMENT_IDS[1]);
+        insert("ok".to_owned(), JUDGEMENT_IDS[2]);
+        insert("good".to_owned(), JUDGEMENT_IDS[3]);
+
+        EmojiRegistry {
+            global,
+            guilds: HashMap::new(),
+            usable: None,
+            application: HashSet::new(),
+        }
+    }
+}
+
+static REGISTRY: Lazy<RwLock<EmojiRegistry>> = Lazy::new(|| RwLock::new(EmojiRegistry::default()));
+
+// the emojis to use for messages in guild (or in DMs for None)
+pub fn emojis(guild: Option<GuildId>) -> EmojiSet {
+    let registry = REGISTRY.read().unwrap();
+    let mut values = registry.global.clone();
+    if let Some(overrides) = guild.and_then(|guild| registry.guilds.get(&guild)) {
+        values.extend(overrides.iter().map(|(name, value)| (name.clone(), value.clone())));
+    }
+    EmojiSet {
+        values,
+        usable: registry.usable.as_ref().map(|usable| usable.union(&registry.application).copied().collect()),
+    }
+}
+
+pub async fn load(conn: &mut SqliteConnection) -> Result<(), ElodonError> {
+    let rows: Vec<EmojiRow> = sqlx::query_as("SELECT guild_id, name, emoji_id, animated, text FROM emojis")
+        .fetch_all(conn).await?;
+
+    let mut registry = REGISTRY.write().unwrap();
+    let usable = registry.usable.take();
+    let application = std::mem::take(&mut registry.application);
+    *registry = EmojiRegistry::default();
+    registry.usable = usable;
+    registry.application = application;
+    for row in rows {
+        let guild_id = row.guild_id;
+        let name = row.name.clone();
+        match guild_id {
+            0 => registry.global.insert(name, row.into()),
+            guild_id => registry.guilds.entry(GuildId::new(guild_id as u64)).or_default().insert(name, row.into()),
+        };
+    }
+    Ok(())
+}
+
+// sets the emoji for every server when guild is None
+pub async fn set(conn: &mut SqliteConnection, guild: Option<GuildId>, name: &str, value: Option<EmojiValue>) -> Result<(), ElodonError> {
+    let guild_id = guild.map(|guild| guild.get() as i64).unwrap_or(0);
+    match &value {
+        None => sqlx::query("DELETE FROM emojis WHERE guild_id = ? AND name = ?")
+            .bind(guild_id)
+            .bind(name),
+        Some(EmojiValue::Custom { id, animated }) => sqlx::query("INSERT OR REPLACE INTO emojis (guild_id, name, emoji_id, animated) VALUES (?,?,?,?)")
+            .bind(guild_id)
+            .bind(name)
+            .bind(id.get() as i64)
+            .bind(*animated),
+        Some(EmojiValue::Text(text)) => sqlx::query("INSERT OR REPLACE INTO emojis (guild_id, name, text) VALUES (?,?,?)")
+            .bind(guild_id)
+            .bind(name)
+            .bind(text),
+    }.execute(&mut *conn).await?;
+    load(conn).await
+}
+
+// rechecks which custom emojis are in servers the bot is in. run when guilds or their emojis change
+pub fn refresh_usable(cache: &Cache) {
+    let usable: HashSet<EmojiId> = cache.guilds().into_iter()
+        .filter_map(|guild_id| cache.guild(guild_id).map(|guild| guild.emojis.keys().copied().collect::<Vec<_>>()))
+        .flatten()
+        .collect();
+    REGISTRY.write().unwrap().usable = Some(usable);
+}
+
+pub fn mark_usable(application_emojis: impl IntoIterator<Item = EmojiId>) {
+    REGISTRY.write().unwrap().application.extend(application_emojis);
+}
+
+#[derive(Clone)]
+pub struct EmojiSet {
+    values: HashMap<String, EmojiValue>,
+    usable: Option<HashSet<EmojiId>>,
+}
+
+impl EmojiSet {
+    fn custom(&self, name: &str) -> Option<(EmojiId, bool)> {
+        match self.values.get(name)? {
+            EmojiValue::Custom { id, animated } => {
+                match &self.usable {
+                    Some(usable) if !usable.contains(id) => None,
+                    _ => Some((*id, *animated))
+                }
+            }
+            EmojiValue::Text(_) => None
+        }
+    }
+
+    // message text for the emoji
+    pub fn get(&self, name: &str) -> String {
+        if let Some((id, animated)) = self.custom(name) {
+            return format!("<{}:{}:{}>", if animated { "a" } else { "" }, name, id);
+        }
+        match self.values.get(name) {
+            Some(EmojiValue::Text(text)) => text.clone(),
+            _ => fallback(name).to_owned()
+        }
+    }
+
+    // text shown instead of the emoji where no custom emoji can be used
+    fn text(&self, name: &str) -> String {
+        match self.values.get(name) {
+            Some(EmojiValue::Text(text)) if !text.is_empty() => text.clone(),
+            _ => fallback(name).to_owned()
+        }
+    }
+
+    // for reactions, which only take real emojis. None when the text isn't one
+    pub fn reaction(&self, name: &str) -> Option<ReactionType> {
+        if let Some((id, animated)) = self.custom(name) {
+            return Some(ReactionType::Custom { animated, id, name: None });
+        }
+        let text = self.text(name);
+        is_emoji(&text).then_some(ReactionType::Unicode(text))
+    }
+
+    // the emoji on button, or its text as the label if discord wouldn't take it as an emoji
+    pub fn on_button(&self, button: CreateButton, name: &str) -> CreateButton {
+        match self.reaction(name) {
+            Some(reaction) => button.emoji(reaction),
+            None => match self.text(name) {
+                text if text.is_empty() => button.label(name),
+                text => button.label(text),
+            }
+        }
+    }
+}
+
+// whether discord would take text as a unicode emoji: only emoji codepoints, joined by
+// zero width joiners and variation selectors at most
+fn is_emoji(text: &str) -> bool {
+    let is_joiner = |c: char| matches!(c, '\u{200D}' | '\u{FE0F}' | '\u{20E3}');
+    let is_pictograph = |c: char| matches!(c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x2190..=0x21FF
+        | 0x2300..=0x23FF | 0x25A0..=0x25FF | 0x2934..=0x2935 | 0x3030 | 0x303D
+        | 0x3297 | 0x3299 | 0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139
+        | 0xE0020..=0xE007F);
+    // keycaps are a digit, # or * followed by U+20E3
+    let is_keycap_base = |c: char| c.is_ascii_digit() || c == '#' || c == '*';
+    let starts_right = match text.chars().next() {
+        Some(first) if is_pictograph(first) => true,
+        Some(first) => is_keycap_base(first) && text.contains('\u{20E3}'),
+        None => false,
+    };
+    starts_right && text.chars().all(|c| is_pictograph(c) || is_joiner(c) || is_keycap_base(c))
+}
+
+impl Symbols for EmojiSet {
+    fn crown(&self, crown: u32) -> String {
+        self.get(&format!("crown_{crown}"))
+    }
+
+    // ranks below 2 don't get an emoji
+    fn rank(&self, rank: u32) -> String {
+        if rank < 2 { String::new() } else { self.get(&format!("rank_{rank}")) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_emojis_are_emojis() {
+        for emoji in ["⬆️", "⏮️", "🥇", "🌈", "1️⃣", "👨‍👩‍👧"] {
+            assert!(is_emoji(emoji), "{emoji}");
+        }
+        for text in ["良", "不可", "·", "", "combo", "1", "⬆️a"] {
+            assert!(!is_emoji(text), "{text}");
+        }
+    }
+
+    #[test]
+    fn text_that_isnt_an_emoji_is_no_reaction() {
+        let set = EmojiSet {
+            values: HashMap::from([
+                ("good".to_owned(), EmojiValue::Text("良".to_owned())),
+                ("left".to_owned(), EmojiValue::Text("⬅️".to_owned())),
+                ("right".to_owned(), EmojiValue::Custom { id: EmojiId::new(5), animated: false }),
+            ]),
+            // the custom emoji isn't in any server the bot is in
+            usable: Some(HashSet::new()),
+        };
+        assert!(set.reaction("good").is_none());
+        assert!(set.reaction("crown_0").is_none());
+        assert_eq!(set.reaction("left"), Some(ReactionType::Unicode("⬅️".to_owned())));
+        assert_eq!(set.reaction("right"), Some(ReactionType::Unicode("➡️".to_owned())));
+    }
+}
@@ -1,66 +1,45 @@
-use std::fmt::{Display, Formatter};
-use std::ops::{Deref, DerefMut};
+pub use elodon_core::error::ElodonError as CoreError;
 
 use thiserror::Error;
 
+// elodon_core's errors and the ones only the bot has: discord, accounts, emojis and images.
+// the messages are shown to whoever ran the command
 #[derive(Error, Debug)]
-pub enum ElodonError{
-    #[error("There were no results found in {search} where {id}. Either those {search} don't exist or elodon hasn't unlocked them")]
-    NoResults {
-        search: String,
-        id: String
-    },
-    #[error("Level_ids are from 1 - 5. Level id given was {0} which doesn't correspond to a level")]
-    WrongLevelId(u32),
-    #[error("could not extract level id from {0}")]
-    ParseError(String),
-    #[error("Genre_ids are from 1 - 9. Genre id given was {0} which doesn't correspond to a level")]
-    WrongGenreId(u32),
+pub enum ElodonError {
     #[error(transparent)]
-    List(#[from] ElodonErrorList),
-    #[error("Database error: {0}")]
-    DatabaseError(#[from] sqlx::Error),
+    Core(#[from] CoreError),
+    #[error("Discord error: {0}")]
+    DiscordError(#[from] poise::serenity_prelude::Error),
     #[error("Command that required server was not run in server")]
     NoGuild,
-    #[error("Irrecoverable error (likely bad state or manual shutdown). More info: {0}")]
-    Shutdown(#[from] std::io::Error),
+    #[error("You need the {0} role to do that")]
+    MissingRole(String),
+    #[error("Donder ID {0} is already linked to another discord account. If it's yours, ask an admin to unlink it")]
+    DonderIdClaimed(i64),
+    #[error("You are already linked to Donder ID {0}")]
+    AlreadyRegistered(i64),
+    #[error("Donder ID {0} isn't linked to that discord account")]
+    NotLinkedAccount(i64),
+    #[error("No account was picked")]
+    NoAccountChosen,
+    #[error("You don't have a linked Donder ID. Use /register first")]
+    NotRegistered,
+    #[error("You have no pending link. Use /register or /relink first")]
+    NoLinkRequest,
+    #[error("There is no emoji called \"{0}\"")]
+    UnknownEmoji(String),
+    #[error("Couldn't draw the image: {0}")]
+    RenderError(String),
 }
 
-impl From<ElodonError> for String {
-    fn from(value: ElodonError) -> Self {
-        format!("{}", value) //NOT AN ERROR. rustrover just dumb
+impl From<sqlx::Error> for ElodonError {
+    fn from(error: sqlx::Error) -> Self {
+        ElodonError::Core(error.into())
     }
 }
 
-#[derive(Error, Debug)]
-pub struct ElodonErrorList(Vec<ElodonError>);
-
-impl Display for ElodonErrorList {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{{ \n")?;
-        for error in &self.0 {
-            write!(f, "{error}\n")?;
-        }
-        write!(f, "}}")?;
-        Ok(())
-    }
-}
-
-impl ElodonErrorList{
-    pub fn new() -> ElodonErrorList {
-        ElodonErrorList(vec![])
+impl From<std::io::Error> for ElodonError {
+    fn from(error: std::io::Error) -> Self {
+        ElodonError::Core(error.into())
     }
 }
-
-impl Deref for ElodonErrorList {
-    type Target = Vec<ElodonError>;
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl DerefMut for ElodonErrorList{
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
-}
\ No newline at end of file
@@ -0,0 +1 @@
+pub use elodon_core::history::*;
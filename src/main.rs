@@ -10,23 +10,37 @@ use std::{
     sync::Arc,
     time::Duration,
 };
-use std::sync::OnceLock;
+use std::sync::{OnceLock, RwLock};
 
 use poise::serenity_prelude as serenity;
 use poise::serenity_prelude::{FullEvent, ShardManager};
 use sqlx::SqliteConnection;
-use crate::commands::get_connection;
-use crate::error::ElodonError;
+use crate::commands::{get_connection, load_song_autocomplete};
+use crate::error::{CoreError, ElodonError};
 
+use crate::settings::GuildSettingsCache;
 use crate::structs::Song;
 
-mod commands;
-mod structs;
+use elodon_core::{display, elo, eval, events, filters, schema, structs};
+
 mod error;
-mod filters;
+mod commands;
+mod accounts;
 mod paginate;
-mod elo;
 mod emoji;
+mod settings;
+mod provision;
+mod render;
+mod history;
+mod views;
+mod announce;
+mod digest;
+mod scheduler;
+mod records;
+mod members;
+mod challenges;
+#[cfg(feature = "http-api")]
+mod api;
 
 static SONG_NAMES: OnceLock<Vec<Song>> = OnceLock::new();
 
@@ -36,7 +50,8 @@ type Context<'a> = poise::Context<'a, Data, Error>;
 
 // Custom user data passed to all command functions
 pub struct Data {
-    songs_autocomplete: Vec<String>
+    songs_autocomplete: RwLock<Vec<String>>,
+    guild_settings: GuildSettingsCache,
 }
 
 async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
@@ -50,7 +65,7 @@ async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
             ctx.say(format!("Error in command:\n{error}")).await.expect("failed to send error msg");
             if let Ok(elodon_error) = error.downcast::<ElodonError>() {
                 match *elodon_error {
-                    ElodonError::Shutdown(error) => {
+                    ElodonError::Core(CoreError::Shutdown(error)) => {
                         ctx.say(format!("{error}")).await.expect("failed to send error msg");
                         ctx.framework().shard_manager.shutdown_all().await;
                     }
@@ -67,6 +82,25 @@ async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
 
 }
 
+// EMOJI_SYNC=application uploads missing emojis as application emojis, EMOJI_SYNC=<guild id> to that guild
+async fn sync_emojis_on_startup(http: &serenity::Http) -> Result<(), ElodonError> {
+    let Ok(target) = var("EMOJI_SYNC") else {
+        return Ok(());
+    };
+    let assets = provision::load_assets(std::path::Path::new(provision::DEFAULT_ASSET_DIR));
+    let mut conn = get_connection().await?;
+    let report = match target.parse::<u64>() {
+        Ok(guild_id) => {
+            let host = provision::GuildEmojis { http, guild_id: serenity::GuildId::new(guild_id) };
+            provision::provision(&mut conn, &host, &assets).await?
+        }
+        Err(_) => provision::provision(&mut conn, &provision::ApplicationEmojis { http }, &assets).await?
+    };
+    println!("emojis synced: {} uploaded, {} existing, {} failed",
+             report.uploaded.len(), report.existing.len(), report.failed.len());
+    Ok(())
+}
+
 //noinspection RsUnresolvedReference
 #[tokio::main]
 async fn main() {
@@ -78,14 +112,30 @@ async fn main() {
         commands: vec![
             commands::help(),
             commands::song_info(),
+            commands::song(),
+            commands::chart(),
             commands::scores(),
             commands::player(),
             commands::suggest(),
             commands::dev(),
-            commands::register()
+            commands::register(),
+            commands::relink(),
+            commands::verify(),
+            commands::unregister(),
+            commands::account(),
+            commands::config(),
+            commands::alias(),
+            commands::records(),
+            commands::challenge()
         ],
         prefix_options: poise::PrefixFrameworkOptions {
-            prefix: Some("~".to_string()),
+            // set per server with /config prefix
+            dynamic_prefix: Some(|ctx| {
+                Box::pin(async move {
+                    let settings = ctx.data.guild_settings.get(ctx.guild_id).await?;
+                    Ok(Some(settings.prefix().to_owned()))
+                })
+            }),
             edit_tracker: Some(Arc::new(poise::EditTracker::for_timespan(
                 Duration::from_secs(3600),
             ))),
@@ -109,7 +159,8 @@ async fn main() {
         // Every command invocation must pass this check to continue execution
         command_check: Some(|ctx| {
             Box::pin(async move {
-                if ctx.author().id == 123456789 {
+                let settings = ctx.data().guild_settings.get(ctx.guild_id()).await?;
+                if settings.is_banned(ctx.author().id) {
                     return Ok(false);
                 }
                 Ok(true)
@@ -118,17 +169,29 @@ async fn main() {
         // Enforce command checks even for owners (enforced by default)
         // Set to true to bypass checks, which is useful for testing
         skip_checks_for_owners: false,
-        event_handler: |ctx, event, _framework, _data| {
+        event_handler: |ctx, event, _framework, data| {
             Box::pin(async move {
                 // println!(
                 //     "Got an event in event handler: {:?}",
                 //     event
                 // );
 
+                if let Err(err) = members::handle_event(ctx, event).await {
+                    println!("Updating server members failed: {err}");
+                }
                 match event {
+                    FullEvent::CacheReady { .. }
+                    | FullEvent::GuildCreate { .. }
+                    | FullEvent::GuildDelete { .. }
+                    | FullEvent::GuildEmojisUpdate { .. } => {
+                        emoji::refresh_usable(&ctx.cache);
+                    }
                     FullEvent::Message {new_message: msg} => {
-                        if msg.content.to_ascii_lowercase().contains("elodon") && !msg.author.bot{
-                            let _ = msg.react(ctx, '💜').await;
+                        let settings = data.guild_settings.get(msg.guild_id).await?;
+                        if let Some(trigger) = settings.reaction_trigger() {
+                            if msg.content.to_ascii_lowercase().contains(trigger) && !msg.author.bot{
+                                let _ = msg.react(ctx, '💜').await;
+                            }
                         }
                     }
                     _ => {}
@@ -148,20 +211,24 @@ async fn main() {
                 println!("slash commands registered");
                 let song_names: Vec<String> = match get_connection().await {
                     Ok(mut conn) => {
-                        match Song::fetch_all(&mut conn, SongFilter::new()).await {
-                            Ok(songs) => {
-                                let song_names: Vec<String> = songs.iter().map(|song: &Song| {
-                                    format!("{}: {} > {}", song.id, song.genre(), song.get_name())
-                                }).collect();
-                                song_names
-                            },
-                            Err(_) => { vec![] }
+                        schema::create_tables(&mut conn).await?;
+                        emoji::load(&mut conn).await?;
+                        if let Err(err) = provision::mark_application_emojis(&provision::ApplicationEmojis { http: &ctx.http }).await {
+                            println!("Loading the application emojis failed: {err}");
                         }
+                        load_song_autocomplete(&mut conn).await.unwrap_or_default()
                     }
                     Err(_) => { vec![] }
                 };
+                tokio::spawn(scheduler::run(ctx.clone()));
+                #[cfg(feature = "http-api")]
+                tokio::spawn(api::serve(commands::pool()));
+                if let Err(err) = sync_emojis_on_startup(&ctx.http).await {
+                    println!("Emoji sync failed: {err}");
+                }
                 Ok(Data {
-                    songs_autocomplete: song_names
+                    songs_autocomplete: RwLock::new(song_names),
+                    guild_settings: GuildSettingsCache::default(),
                 })
             })
         })
@@ -173,8 +240,8 @@ async fn main() {
     let intents =
         serenity::GatewayIntents::non_privileged()
             | serenity::GatewayIntents::MESSAGE_CONTENT
-            | serenity::GatewayIntents::GUILD_MEMBERS
-            | serenity::GatewayIntents::GUILD_PRESENCES;
+            // member events and chunks for guild_members
+            | serenity::GatewayIntents::GUILD_MEMBERS;
 
     let client = serenity::ClientBuilder::new(token, intents)
         .framework(framework)
@@ -0,0 +1,176 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{FullEvent, GuildId, UserId};
+use sqlx::{Connection, SqliteConnection};
+
+use crate::commands::get_connection;
+use crate::error::ElodonError;
+use crate::filters::*;
+use crate::structs::*;
+
+// guild_members mirrors who is in each server. the gateway sends part of the members with the
+// guild, the rest is requested in chunks, and joins and leaves arrive as events after that.
+// chunks are handled concurrently and in any order, so every request gets a sync time (its nonce)
+// that its rows are stamped with. the chunks are counted as they're stored, and only once all of
+// them are in are the rows from before the sync dropped
+
+// chunks stored so far of each member request, by server and sync time
+static STORED_CHUNKS: Lazy<Mutex<HashMap<(GuildId, i64), u32>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// milliseconds, a join during a sync is newer than the sync and survives it
+fn sync_time() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_millis() as i64).unwrap_or(0)
+}
+
+pub async fn add_members(conn: &mut SqliteConnection, guild_id: GuildId, members: impl IntoIterator<Item = UserId>, synced_at: i64) -> Result<(), ElodonError> {
+    let mut transaction = conn.begin().await?;
+    for member in members {
+        sqlx::query("INSERT INTO guild_members (guild_id, discord_id, synced_at) VALUES (?,?,?) \
+                ON CONFLICT (guild_id, discord_id) DO UPDATE SET synced_at = MAX(synced_at, excluded.synced_at)")
+            .bind(guild_id.get() as i64)
+            .bind(member.get() as i64)
+            .bind(synced_at)
+            .execute(&mut *transaction).await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+// everyone who wasn't in the member list requested at synced_at and didn't join after it left
+pub async fn remove_stale_members(conn: &mut SqliteConnection, guild_id: GuildId, synced_at: i64) -> Result<(), ElodonError> {
+    sqlx::query("DELETE FROM guild_members WHERE guild_id = ? AND synced_at < ?")
+        .bind(guild_id.get() as i64)
+        .bind(synced_at)
+        .execute(conn).await?;
+    Ok(())
+}
+
+// one of the chunk_count chunks of the member list requested at synced_at. the last one of them
+// to be stored drops everyone who wasn't in any
+pub async fn store_chunk(conn: &mut SqliteConnection, guild_id: GuildId, members: impl IntoIterator<Item = UserId>,
+                         synced_at: i64, chunk_count: u32) -> Result<(), ElodonError> {
+    add_members(conn, guild_id, members, synced_at).await?;
+    let complete = {
+        let mut stored = STORED_CHUNKS.lock().unwrap();
+        let count = stored.entry((guild_id, synced_at)).or_insert(0);
+        *count += 1;
+        let complete = *count >= chunk_count;
+        if complete {
+            stored.remove(&(guild_id, synced_at));
+        }
+        complete
+    };
+    if complete {
+        remove_stale_members(conn, guild_id, synced_at).await?;
+    }
+    Ok(())
+}
+
+pub async fn remove_member(conn: &mut SqliteConnection, guild_id: GuildId, member: UserId) -> Result<(), ElodonError> {
+    sqlx::query("DELETE FROM guild_members WHERE guild_id = ? AND discord_id = ?")
+        .bind(guild_id.get() as i64)
+        .bind(member.get() as i64)
+        .execute(conn).await?;
+    Ok(())
+}
+
+// the bot left the server
+pub async fn forget_guild(conn: &mut SqliteConnection, guild_id: GuildId) -> Result<(), ElodonError> {
+    sqlx::query("DELETE FROM guild_members WHERE guild_id = ?")
+        .bind(guild_id.get() as i64)
+        .execute(conn).await?;
+    Ok(())
+}
+
+// donder ids of everyone in a server
+pub async fn server_players(conn: &mut SqliteConnection, guild_id: GuildId) -> Result<HashSet<i64>, ElodonError> {
+    Ok(User::fetch_all_or_empty(conn, UserFilter::new().guild_id(Some(guild_id.get()))).await?
+        .into_iter()
+        .map(|user| user.id)
+        .collect())
+}
+
+// keeps guild_members up to date, called with every gateway event
+pub async fn handle_event(ctx: &serenity::Context, event: &FullEvent) -> Result<(), ElodonError> {
+    match event {
+        FullEvent::GuildCreate { guild, .. } => {
+            let mut conn = get_connection().await?;
+            let synced_at = sync_time();
+            add_members(&mut conn, guild.id, guild.members.keys().copied(), synced_at).await?;
+            // large servers only come with some of their members
+            ctx.shard.chunk_guild(guild.id, None, false, serenity::ChunkGuildFilter::None, Some(synced_at.to_string()));
+        }
+        FullEvent::GuildMembersChunk { chunk } => {
+            let mut conn = get_connection().await?;
+            let members = chunk.members.keys().copied();
+            match chunk.nonce.as_deref().and_then(|nonce| nonce.parse().ok()) {
+                // anyone missing from the full list left while the bot wasn't looking
+                Some(synced_at) => store_chunk(&mut conn, chunk.guild_id, members, synced_at, chunk.chunk_count).await?,
+                // chunks the bot didn't ask for count as joins
+                None => add_members(&mut conn, chunk.guild_id, members, sync_time()).await?,
+            }
+        }
+        FullEvent::GuildMemberAddition { new_member } => {
+            let mut conn = get_connection().await?;
+            add_members(&mut conn, new_member.guild_id, [new_member.user.id], sync_time()).await?;
+        }
+        FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
+            let mut conn = get_connection().await?;
+            remove_member(&mut conn, *guild_id, user.id).await?;
+        }
+        // unavailable guilds are outages, not the bot being removed
+        FullEvent::GuildDelete { incomplete, .. } if !incomplete.unavailable => {
+            let mut conn = get_connection().await?;
+            forget_guild(&mut conn, incomplete.id).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::fixture_connection;
+
+    #[tokio::test]
+    async fn members_come_and_go() {
+        let mut conn = fixture_connection().await;
+        let guild = GuildId::new(1);
+        // fixture players 0 and 1, and someone without a Donder ID
+        let discord = |n: u64| UserId::new(900000000000000000 + n);
+        add_members(&mut conn, guild, [discord(0), discord(1), UserId::new(5)], 1).await.unwrap();
+        add_members(&mut conn, guild, [discord(0)], 1).await.unwrap();
+        assert_eq!(server_players(&mut conn, guild).await.unwrap(), HashSet::from([100000000000, 100000000000 + 7919]));
+
+        remove_member(&mut conn, guild, discord(1)).await.unwrap();
+        assert_eq!(server_players(&mut conn, guild).await.unwrap(), HashSet::from([100000000000]));
+        assert!(server_players(&mut conn, GuildId::new(2)).await.unwrap().is_empty());
+
+        forget_guild(&mut conn, guild).await.unwrap();
+        assert!(server_players(&mut conn, guild).await.unwrap().is_empty());
+    }
+    #[tokio::test]
+    async fn chunks_in_any_order_keep_everyone() {
+        let mut conn = fixture_connection().await;
+        let guild = GuildId::new(1);
+        let discord = |n: u64| UserId::new(900000000000000000 + n);
+        // player 2 left before the sync at 10, player 3 joined during it
+        add_members(&mut conn, guild, [discord(0), discord(1), discord(2)], 5).await.unwrap();
+        add_members(&mut conn, guild, [discord(3)], 11).await.unwrap();
+
+        // the last chunk finishes first and the one with player 0 after it
+        store_chunk(&mut conn, guild, [discord(1)], 10, 2).await.unwrap();
+        // nobody is dropped while a chunk is missing, not even player 2
+        let players = server_players(&mut conn, guild).await.unwrap();
+        assert_eq!(players, HashSet::from([100000000000, 100000007919, 100000015838, 100000023757]));
+        store_chunk(&mut conn, guild, [discord(0)], 10, 2).await.unwrap();
+
+        let players = server_players(&mut conn, guild).await.unwrap();
+        assert_eq!(players, HashSet::from([100000000000, 100000007919, 100000023757]));
+    }
+}
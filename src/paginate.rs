@@ -1,89 +1,502 @@
-use futures::{Stream, StreamExt};
-use std::fmt::Write as _;
+use std::time::Duration;
 
 use poise::serenity_prelude as serenity;
-use poise::serenity_prelude::{ButtonStyle, EmojiId, ReactionType};
+use poise::serenity_prelude::{ButtonStyle, ComponentInteraction, ComponentInteractionDataKind, CreateActionRow, CreateAttachment, CreateEmbed, Mentionable};
 
-use crate::{Context, emoji, Error};
+use crate::{Context, emoji};
+use crate::emoji::EmojiSet;
+use crate::error::ElodonError;
 
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60 * 15);
+pub const DEFAULT_ENTRIES_PER_PAGE: usize = 10;
+pub const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+// discord's limit for select menu options
+const MAX_JUMP_OPTIONS: usize = 25;
 
-pub async fn paginate<U, E>(
-    ctx: Context<'_>,
-    header: &str,
-    pages: &[&str],
-) -> Result<(), serenity::Error> {
-    // Define some unique identifiers for the navigation buttons
-    let ctx_id = ctx.id();
-    let prev_button_id = format!("{}prev", ctx_id);
-    let next_button_id = format!("{}next", ctx_id);
+// what a paginated message shows for one page
+#[derive(Clone, Default)]
+pub struct Page {
+    pub content: String,
+    pub embed: Option<CreateEmbed>,
+}
 
-    // Send the embed with the first page as content
-    let reply = {
+impl Page {
+    pub fn new(content: impl Into<String>) -> Page {
+        Page {
+            content: content.into(),
+            embed: None,
+        }
+    }
 
-        let mut reply = poise::CreateReply::default()
-            .content(header);
-
-        if pages.len() > 1 {
-            let components = serenity::CreateActionRow::Buttons(
-                vec![
-                    serenity::CreateButton::new(&prev_button_id)
-                        .style(ButtonStyle::Primary)
-                        .emoji(emoji::LEFT),
-                    serenity::CreateButton::new(&next_button_id)
-                        .style(ButtonStyle::Primary)
-                        .emoji(emoji::RIGHT),
-                ]
-            );
-            reply = reply.components(vec![components]);
+    pub fn embed(self, embed: CreateEmbed) -> Page {
+        Page {
+            embed: Some(embed),
+            ..self
         }
+    }
+
+    // the usual header + text in an embed
+    pub fn description(content: impl Into<String>, description: impl Into<String>) -> Page {
+        Page::new(content).embed(CreateEmbed::new().description(description))
+    }
+}
+
+// what the paginator should do after a PageSource handled a press
+pub enum Control {
+    // not one of the source's components
+    Ignore,
+    // the current page changed
+    Rerender,
+    // everything changed (e.g. sorting), go back to the first page
+    Reset,
+    // stop paginating and give the press back to the caller
+    Finish,
+}
+
+pub trait PageSource {
+    async fn page_count(&mut self) -> Result<usize, ElodonError>;
+
+    async fn page(&mut self, index: usize) -> Result<Page, ElodonError>;
+
+    // shown instead when there are no pages
+    fn empty_page(&self) -> Page {
+        Page::new("No results :c")
+    }
+
+    // extra rows below the navigation. custom ids have to start with id_prefix
+    fn components(&self, _id_prefix: &str, _page: usize, _disabled: bool) -> Vec<CreateActionRow> {
+        vec![]
+    }
+
+    // the author pressed one of the source's components. action is the custom id without the prefix
+    async fn handle(&mut self, _action: &str, _press: &ComponentInteraction) -> Result<Control, ElodonError> {
+        Ok(Control::Ignore)
+    }
+
+    // files shown with the pages next to the paginator's own, asked for again after every handled press
+    async fn attachments(&mut self) -> Result<Vec<CreateAttachment>, ElodonError> {
+        Ok(vec![])
+    }
+}
+
+// a list that can be loaded a few entries at a time
+pub trait EntrySource {
+    async fn count(&mut self) -> Result<usize, ElodonError>;
+
+    // the rendered entries offset..offset + limit (fewer at the end). entries can span several lines
+    async fn entries(&mut self, offset: usize, limit: usize) -> Result<Vec<String>, ElodonError>;
+
+    fn per_page(&self) -> usize {
+        DEFAULT_ENTRIES_PER_PAGE
+    }
+
+    // replaces the header given to LazyPages, for sources whose controls change what is listed
+    fn header(&self) -> Option<String> {
+        None
+    }
+
+    // same as PageSource::components, but the same rows on every page
+    fn components(&self, _id_prefix: &str, _disabled: bool) -> Vec<CreateActionRow> {
+        vec![]
+    }
+
+    // anything but Control::Ignore drops the loaded pages
+    async fn handle(&mut self, _action: &str, _press: &ComponentInteraction) -> Result<Control, ElodonError> {
+        Ok(Control::Ignore)
+    }
 
-        if pages.len() > 0 {
-            reply = reply.embed(serenity::CreateEmbed::default().description(pages[0]));
-        } else {
-            reply.content.as_mut().unwrap().push_str("\n No results :c");
+    // same as PageSource::attachments
+    async fn attachments(&mut self) -> Result<Vec<CreateAttachment>, ElodonError> {
+        Ok(vec![])
+    }
+}
+
+// pages of up to per_page entries, loaded when they are shown. entries are never split between
+// pages, a page just ends early when the next entry wouldn't fit into the embed
+pub struct LazyPages<E: EntrySource> {
+    header: String,
+    preamble: String,
+    code_block: bool,
+    source: E,
+    count: Option<usize>,
+    // first entry of every page seen so far
+    page_starts: Vec<usize>,
+}
+
+impl<E: EntrySource> LazyPages<E> {
+    pub fn new(header: impl Into<String>, source: E) -> LazyPages<E> {
+        LazyPages {
+            header: header.into(),
+            preamble: String::new(),
+            code_block: false,
+            source,
+            count: None,
+            page_starts: vec![0],
         }
+    }
 
-        reply
-    };
+    // shown above the entries on every page
+    pub fn preamble(self, preamble: impl Into<String>) -> LazyPages<E> {
+        LazyPages { preamble: preamble.into(), ..self }
+    }
+
+    pub fn code_block(self, code_block: bool) -> LazyPages<E> {
+        LazyPages { code_block, ..self }
+    }
+
+    // forget the loaded pages, e.g. after the source changed its sorting
+    fn reset(&mut self) {
+        self.count = None;
+        self.page_starts = vec![0];
+    }
+
+    fn header(&self) -> String {
+        self.source.header().unwrap_or_else(|| self.header.clone())
+    }
+
+    async fn count(&mut self) -> Result<usize, ElodonError> {
+        if let Some(count) = self.count {
+            return Ok(count);
+        }
+        let count = self.source.count().await?;
+        self.count = Some(count);
+        Ok(count)
+    }
+
+    // the description for the page starting at start and the first entry of the next one
+    async fn fill(&mut self, start: usize) -> Result<(String, usize), ElodonError> {
+        let (open, close) = match self.code_block {
+            true => ("```\n", "```"),
+            false => ("", ""),
+        };
+        let mut description = format!("{}{open}", self.preamble);
+        let budget = EMBED_DESCRIPTION_LIMIT.saturating_sub(close.chars().count());
+        let mut used = description.chars().count();
+        let mut taken = 0;
+        let per_page = self.source.per_page().max(1);
+        for entry in self.source.entries(start, per_page).await? {
+            let entry = format!("{entry}\n");
+            let length = entry.chars().count();
+            if used + length > budget {
+                if taken == 0 {
+                    // a single entry that's too long on its own gets cut
+                    let room = budget.saturating_sub(used + 1);
+                    description.push_str(&truncate(entry.trim_end(), room, !self.code_block));
+                    description.push('\n');
+                    taken = 1;
+                }
+                break;
+            }
+            description.push_str(&entry);
+            used += length;
+            taken += 1;
+        }
+        description.push_str(close);
+        Ok((description, start + taken))
+    }
+}
+
+// text cut to at most max chars with an ellipsis. with markdown, code spans and bold left open by
+// the cut are closed first so the rest of the page isn't swallowed by them
+fn truncate(text: &str, max: usize, markdown: bool) -> String {
+    if text.chars().count() <= max {
+        return text.to_owned();
+    }
+    // room for the ellipsis and "**", "`" or "```" closers
+    let reserved = if markdown { 7 } else { 1 };
+    let mut kept: String = text.chars().take(max.saturating_sub(reserved)).collect();
+    if !markdown {
+        kept.push('…');
+        return kept;
+    }
+    // don't end on half of a marker, or on a space discord wouldn't close bold after
+    kept.truncate(kept.trim_end_matches(|c: char| c == '*' || c == '`' || c.is_whitespace()).len());
+
+    let mut open: Vec<&str> = vec![];
+    let mut rest = kept.as_str();
+    while let Some(next) = rest.chars().next() {
+        let in_code = matches!(open.last(), Some(&"`") | Some(&"```"));
+        let marker = ["```", "`", "**"].into_iter().find(|marker| rest.starts_with(marker));
+        match marker {
+            Some(marker) if open.last() == Some(&marker) => { open.pop(); }
+            Some("**") if in_code => {}
+            Some("**") if open.contains(&"**") => open.retain(|open| *open != "**"),
+            Some(marker) if !in_code => open.push(marker),
+            _ => {}
+        }
+        rest = &rest[marker.map_or(next.len_utf8(), str::len)..];
+    }
+    open.iter().rev().for_each(|marker| kept.push_str(marker));
+    kept.push('…');
+    kept
+}
+
+impl<E: EntrySource> PageSource for LazyPages<E> {
+    // exact up to the furthest page seen, estimated with full pages after that
+    async fn page_count(&mut self) -> Result<usize, ElodonError> {
+        let count = self.count().await?;
+        if count == 0 {
+            return Ok(0);
+        }
+        let known = self.page_starts.len() - 1;
+        let last_start = self.page_starts[known];
+        Ok(known + (count - last_start).div_ceil(self.source.per_page().max(1)).max(1))
+    }
 
-    let _reply_handle = ctx.send(reply).await?;
-
-    // Loop through incoming interactions with the navigation buttons
-    let mut current_page = 0;
-    while let Some(press) = serenity::collector::ComponentInteractionCollector::new(ctx)
-        // We defined our button IDs to start with `ctx_id`. If they don't, some other command's
-        // button was pressed
-        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
-        // Timeout when no navigation button has been pressed for 24 hours
-        .timeout(std::time::Duration::from_secs(3600 * 24))
-        .await
-    {
-        if *ctx.author() == press.user {
-            // Depending on which button was pressed, go to next or previous page
-            let id = &press.data.custom_id;
-            match id {
-                _ if id == &next_button_id => {
-                    current_page = (current_page + 1) % pages.len();
-                },
-                _ if id == &prev_button_id  => {
-                    current_page = current_page.checked_sub(1).unwrap_or(pages.len() - 1);
-                },
-                _ => {}
+    async fn page(&mut self, index: usize) -> Result<Page, ElodonError> {
+        let count = self.count().await?;
+        // walk up to the page, the start of a page is only known once the previous one is filled
+        while self.page_starts.len() <= index {
+            let start = self.page_starts[self.page_starts.len() - 1];
+            let (_, next) = self.fill(start).await?;
+            if next >= count {
+                break;
             }
+            self.page_starts.push(next);
+        }
+        let start = self.page_starts[index.min(self.page_starts.len() - 1)];
+        let (description, next) = self.fill(start).await?;
+        if next < count && self.page_starts.len() == index + 1 {
+            self.page_starts.push(next);
+        }
+        Ok(Page::description(self.header(), description))
+    }
+
+    fn empty_page(&self) -> Page {
+        Page::new(format!("{}\n No results :c", self.header()))
+    }
+
+    fn components(&self, id_prefix: &str, _page: usize, disabled: bool) -> Vec<CreateActionRow> {
+        self.source.components(id_prefix, disabled)
+    }
+
+    async fn handle(&mut self, action: &str, press: &ComponentInteraction) -> Result<Control, ElodonError> {
+        let control = self.source.handle(action, press).await?;
+        if !matches!(control, Control::Ignore) {
+            self.reset();
         }
+        Ok(control)
+    }
+
+    async fn attachments(&mut self) -> Result<Vec<CreateAttachment>, ElodonError> {
+        self.source.attachments().await
+    }
+}
+
+pub struct Paginator {
+    timeout: Duration,
+    attachments: Vec<CreateAttachment>,
+}
 
-        // Update the message with the new page contents
-        press
-            .create_response(
-                ctx.serenity_context(),
-                serenity::CreateInteractionResponse::UpdateMessage(
+impl Default for Paginator {
+    fn default() -> Self {
+        Paginator { timeout: DEFAULT_TIMEOUT, attachments: vec![] }
+    }
+}
+
+impl Paginator {
+    pub fn new() -> Paginator {
+        Self::default()
+    }
+
+    // how long after the last press the buttons stop working
+    pub fn timeout(self, timeout: Duration) -> Paginator {
+        Paginator { timeout, ..self }
+    }
+
+    // files sent with every page
+    pub fn attachment(mut self, attachment: CreateAttachment) -> Paginator {
+        self.attachments.push(attachment);
+        self
+    }
+
+    // sends the first page and handles presses until the timeout. returns the press if the source finished it
+    pub async fn run<S: PageSource>(&self, ctx: Context<'_>, source: &mut S) -> Result<Option<ComponentInteraction>, ElodonError> {
+        let id_prefix = ctx.id().to_string();
+        let emojis = emoji::emojis(ctx.guild_id());
+
+        let mut current = 0;
+        let mut page_count = source.page_count().await?;
+        // updates replace the files of the message, so they're sent again every time
+        let mut files = self.files(source).await?;
+        let mut page = render(source, current, page_count).await?;
+        let components = navigation(&emojis, &id_prefix, current, page_count, false)
+            .into_iter()
+            .chain(source.components(&id_prefix, current, false))
+            .collect();
+
+        let mut reply = poise::CreateReply::default()
+            .content(&page.content)
+            .components(components);
+        if let Some(embed) = &page.embed {
+            reply = reply.embed(embed.clone());
+        }
+        for file in &files {
+            reply = reply.attachment(file.clone());
+        }
+        let reply_handle = ctx.send(reply).await?;
+
+        loop {
+            let filter_prefix = id_prefix.clone();
+            let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+                // our ids start with the ctx id. if they don't, some other command's button was pressed
+                .filter(move |press| press.data.custom_id.starts_with(&filter_prefix))
+                .timeout(self.timeout)
+                .await else {
+                break;
+            };
+
+            if press.user.id != ctx.author().id {
+                press.create_response(ctx.serenity_context(), serenity::CreateInteractionResponse::Message(
                     serenity::CreateInteractionResponseMessage::new()
-                        .content(header)
-                        .embed(serenity::CreateEmbed::new().description(pages[current_page])),
-                ),
-            )
-            .await?;
+                        .ephemeral(true)
+                        .content(format!("This menu belongs to {}. Run the command yourself to get your own", ctx.author().mention()))
+                )).await?;
+                continue;
+            }
+
+            let action = &press.data.custom_id[id_prefix.len()..];
+            let last = page_count.saturating_sub(1);
+            match action {
+                "first" => current = 0,
+                "prev" => current = current.checked_sub(1).unwrap_or(last),
+                "next" => current = if current >= last { 0 } else { current + 1 },
+                "last" => current = last,
+                "jump" => {
+                    if let ComponentInteractionDataKind::StringSelect { values } = &press.data.kind {
+                        current = values.first()
+                            .and_then(|value| value.parse().ok())
+                            .unwrap_or(current)
+                            .min(last);
+                    }
+                }
+                action => match source.handle(action, &press).await? {
+                    Control::Ignore => {}
+                    Control::Rerender => {
+                        page_count = source.page_count().await?;
+                        current = current.min(page_count.saturating_sub(1));
+                        files = self.files(source).await?;
+                    }
+                    Control::Reset => {
+                        page_count = source.page_count().await?;
+                        current = 0;
+                        files = self.files(source).await?;
+                    }
+                    Control::Finish => return Ok(Some(press)),
+                }
+            }
+
+            page = render(source, current, page_count).await?;
+            // lazy sources only know how many pages there are once they got there
+            let mut new_count = source.page_count().await?;
+            while action == "last" && new_count > page_count {
+                page_count = new_count;
+                current = page_count - 1;
+                page = render(source, current, page_count).await?;
+                new_count = source.page_count().await?;
+            }
+            page_count = new_count;
+            let response = serenity::CreateInteractionResponseMessage::new()
+                .content(&page.content)
+                .embeds(page.embed.iter().cloned().collect())
+                .files(files.clone())
+                .components(navigation(&emojis, &id_prefix, current, page_count, false)
+                    .into_iter()
+                    .chain(source.components(&id_prefix, current, false))
+                    .collect());
+            press.create_response(ctx.serenity_context(), serenity::CreateInteractionResponse::UpdateMessage(response)).await?;
+        }
+
+        // leave the last page up but make it obvious the buttons don't work anymore
+        let mut expired = poise::CreateReply::default()
+            .content(&page.content)
+            .components(navigation(&emojis, &id_prefix, current, page_count, true)
+                .into_iter()
+                .chain(source.components(&id_prefix, current, true))
+                .collect());
+        if let Some(embed) = page.embed {
+            expired = expired.embed(embed);
+        }
+        for file in files {
+            expired = expired.attachment(file);
+        }
+        reply_handle.edit(ctx, expired).await?;
+        Ok(None)
+    }
+
+    async fn files<S: PageSource>(&self, source: &mut S) -> Result<Vec<CreateAttachment>, ElodonError> {
+        let mut files = self.attachments.clone();
+        files.extend(source.attachments().await?);
+        Ok(files)
     }
+}
 
-    Ok(())
-}
\ No newline at end of file
+async fn render<S: PageSource>(source: &mut S, current: usize, page_count: usize) -> Result<Page, ElodonError> {
+    match page_count {
+        0 => Ok(source.empty_page()),
+        _ => source.page(current).await
+    }
+}
+
+fn navigation(emojis: &EmojiSet, id_prefix: &str, current: usize, page_count: usize, disabled: bool) -> Vec<CreateActionRow> {
+    if page_count < 2 {
+        return vec![];
+    }
+    let button = |action: &str| {
+        serenity::CreateButton::new(format!("{id_prefix}{action}"))
+            .style(ButtonStyle::Primary)
+            .disabled(disabled)
+    };
+    let mut rows = vec![serenity::CreateActionRow::Buttons(vec![
+        emojis.on_button(button("first"), "first"),
+        emojis.on_button(button("prev"), "left"),
+        serenity::CreateButton::new(format!("{id_prefix}counter"))
+            .style(ButtonStyle::Secondary)
+            .label(format!("{} / {}", current + 1, page_count))
+            .disabled(true),
+        emojis.on_button(button("next"), "right"),
+        emojis.on_button(button("last"), "last"),
+    ])];
+
+    if page_count > 2 {
+        // a window of pages around the current one
+        let start = current.saturating_sub(MAX_JUMP_OPTIONS / 2)
+            .min(page_count.saturating_sub(MAX_JUMP_OPTIONS));
+        let options = (start..page_count.min(start + MAX_JUMP_OPTIONS))
+            .map(|page| {
+                serenity::CreateSelectMenuOption::new(format!("Page {}", page + 1), page.to_string())
+                    .default_selection(page == current)
+            })
+            .collect();
+        rows.push(serenity::CreateActionRow::SelectMenu(
+            serenity::CreateSelectMenu::new(format!("{id_prefix}jump"), serenity::CreateSelectMenuKind::String { options })
+                .placeholder("Jump to page")
+                .disabled(disabled)
+        ));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_text_is_left_alone() {
+        assert_eq!(truncate("**name** `1000000`", 50, true), "**name** `1000000`");
+    }
+
+    #[test]
+    fn cut_markdown_is_closed() {
+        let cut = truncate("`#1` **a very long name** `1000000`", 20, true);
+        assert_eq!(cut, "`#1` **a very**…");
+        assert!(cut.chars().count() <= 20);
+        assert_eq!(truncate("**name** `1000000 and more`", 20, true), "**name** `100`…");
+        assert_eq!(truncate("`a **b** c d e f g h i j`", 14, true), "`a **b`…");
+    }
+
+    #[test]
+    fn code_blocks_are_cut_plainly() {
+        assert_eq!(truncate("1000000 on **song**", 10, false), "1000000 o…");
+    }
+}
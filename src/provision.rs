@@ -0,0 +1,239 @@
+use std::path::Path;
+
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{CreateAttachment, EmojiId, GuildId, Http};
+use sqlx::SqliteConnection;
+
+use crate::emoji;
+use crate::emoji::EmojiValue;
+use crate::error::ElodonError;
+
+pub const DEFAULT_ASSET_DIR: &str = "./assets/emoji";
+
+// artwork for one emoji, named like the registry entry it provides (crown_3.png -> crown_3)
+#[derive(Clone, Debug)]
+pub struct EmojiAsset {
+    pub name: String,
+    pub image: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HostedEmoji {
+    pub name: String,
+    pub id: EmojiId,
+    pub animated: bool,
+}
+
+impl From<serenity::Emoji> for HostedEmoji {
+    fn from(emoji: serenity::Emoji) -> Self {
+        HostedEmoji {
+            name: emoji.name,
+            id: emoji.id,
+            animated: emoji.animated,
+        }
+    }
+}
+
+// somewhere emojis can be uploaded to. split out so sync can run against a fake discord
+pub trait EmojiHost {
+    async fn list(&self) -> Result<Vec<HostedEmoji>, ElodonError>;
+    async fn upload(&self, asset: &EmojiAsset) -> Result<HostedEmoji, ElodonError>;
+    // application emojis work in every server, guild emojis only where the registry points at them
+    fn scope(&self) -> Option<GuildId>;
+}
+
+pub struct GuildEmojis<'a> {
+    pub http: &'a Http,
+    pub guild_id: GuildId,
+}
+
+impl EmojiHost for GuildEmojis<'_> {
+    async fn list(&self) -> Result<Vec<HostedEmoji>, ElodonError> {
+        Ok(self.http.get_emojis(self.guild_id).await?.into_iter().map(HostedEmoji::from).collect())
+    }
+
+    async fn upload(&self, asset: &EmojiAsset) -> Result<HostedEmoji, ElodonError> {
+        let image = CreateAttachment::bytes(asset.image.clone(), format!("{}.png", asset.name)).to_base64();
+        Ok(self.guild_id.create_emoji(self.http, &asset.name, &image).await?.into())
+    }
+
+    fn scope(&self) -> Option<GuildId> {
+        Some(self.guild_id)
+    }
+}
+
+pub struct ApplicationEmojis<'a> {
+    pub http: &'a Http,
+}
+
+impl EmojiHost for ApplicationEmojis<'_> {
+    async fn list(&self) -> Result<Vec<HostedEmoji>, ElodonError> {
+        Ok(self.http.get_application_emojis().await?.into_iter().map(HostedEmoji::from).collect())
+    }
+
+    async fn upload(&self, asset: &EmojiAsset) -> Result<HostedEmoji, ElodonError> {
+        let image = CreateAttachment::bytes(asset.image.clone(), format!("{}.png", asset.name)).to_base64();
+        let map = serenity::json::json!({
+            "name": asset.name,
+            "image": image,
+        });
+        Ok(self.http.create_application_emoji(&map).await?.into())
+    }
+
+    fn scope(&self) -> Option<GuildId> {
+        None
+    }
+}
+
+// reads <name>.png for every emoji the registry knows about. missing files are skipped
+pub fn load_assets(dir: &Path) -> Vec<EmojiAsset> {
+    emoji::FALLBACKS.iter()
+        .filter_map(|(name, _)| {
+            let image = std::fs::read(dir.join(format!("{name}.png"))).ok()?;
+            Some(EmojiAsset { name: (*name).to_owned(), image })
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub uploaded: Vec<HostedEmoji>,
+    pub existing: Vec<HostedEmoji>,
+    pub failed: Vec<(String, ElodonError)>,
+}
+
+impl SyncReport {
+    pub fn hosted(&self) -> impl Iterator<Item = &HostedEmoji> {
+        self.existing.iter().chain(self.uploaded.iter())
+    }
+}
+
+// uploads every asset the host doesn't have an emoji of the same name for yet
+pub async fn sync(host: &impl EmojiHost, assets: &[EmojiAsset]) -> Result<SyncReport, ElodonError> {
+    let hosted = host.list().await?;
+    let mut report = SyncReport::default();
+    for asset in assets {
+        match hosted.iter().find(|hosted| hosted.name == asset.name) {
+            Some(existing) => report.existing.push(existing.clone()),
+            None => match host.upload(asset).await {
+                Ok(uploaded) => report.uploaded.push(uploaded),
+                Err(err) => report.failed.push((asset.name.clone(), err)),
+            }
+        }
+    }
+    Ok(report)
+}
+
+// syncs and points the registry at the hosted emojis
+pub async fn provision(conn: &mut SqliteConnection, host: &impl EmojiHost, assets: &[EmojiAsset]) -> Result<SyncReport, ElodonError> {
+    let report = sync(host, assets).await?;
+    for hosted in report.hosted() {
+        let value = EmojiValue::Custom { id: hosted.id, animated: hosted.animated };
+        emoji::set(conn, host.scope(), &hosted.name, Some(value)).await?;
+    }
+    // application emojis never show up in the guild cache
+    if host.scope().is_none() {
+        emoji::mark_usable(report.hosted().map(|hosted| hosted.id));
+    }
+    Ok(report)
+}
+
+// marks the application emojis usable without syncing, on startup the registry only has their ids
+pub async fn mark_application_emojis(host: &ApplicationEmojis<'_>) -> Result<(), ElodonError> {
+    emoji::mark_usable(host.list().await?.into_iter().map(|hosted| hosted.id));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::schema::fixture_connection;
+
+    struct MockHost {
+        emojis: Mutex<Vec<HostedEmoji>>,
+        next_id: Mutex<u64>,
+    }
+
+    impl MockHost {
+        fn with(names: &[&str]) -> MockHost {
+            MockHost {
+                emojis: Mutex::new(names.iter().enumerate().map(|(i, name)| HostedEmoji {
+                    name: (*name).to_owned(),
+                    id: EmojiId::new(100 + i as u64),
+                    animated: false,
+                }).collect()),
+                next_id: Mutex::new(1000),
+            }
+        }
+    }
+
+    impl EmojiHost for MockHost {
+        async fn list(&self) -> Result<Vec<HostedEmoji>, ElodonError> {
+            Ok(self.emojis.lock().unwrap().clone())
+        }
+
+        async fn upload(&self, asset: &EmojiAsset) -> Result<HostedEmoji, ElodonError> {
+            let mut next_id = self.next_id.lock().unwrap();
+            *next_id += 1;
+            let hosted = HostedEmoji { name: asset.name.clone(), id: EmojiId::new(*next_id), animated: false };
+            self.emojis.lock().unwrap().push(hosted.clone());
+            Ok(hosted)
+        }
+
+        fn scope(&self) -> Option<GuildId> {
+            Some(GuildId::new(1))
+        }
+    }
+
+    #[test]
+    fn every_emoji_has_artwork() {
+        let assets = load_assets(Path::new(DEFAULT_ASSET_DIR));
+        for (name, _) in emoji::FALLBACKS {
+            assert!(assets.iter().any(|asset| asset.name == name && !asset.image.is_empty()), "{name}.png is missing");
+        }
+    }
+
+    fn asset(name: &str) -> EmojiAsset {
+        EmojiAsset { name: name.to_owned(), image: vec![] }
+    }
+
+    #[tokio::test]
+    async fn uploads_only_missing_emojis() {
+        let host = MockHost::with(&["crown_1", "unrelated"]);
+        let report = sync(&host, &[asset("crown_1"), asset("crown_2")]).await.unwrap();
+
+        assert_eq!(report.existing.len(), 1);
+        assert_eq!(report.existing[0].id, EmojiId::new(100));
+        assert_eq!(report.uploaded.len(), 1);
+        assert_eq!(report.uploaded[0].name, "crown_2");
+        assert_eq!(host.emojis.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn second_sync_uploads_nothing() {
+        let host = MockHost::with(&[]);
+        let assets = [asset("good"), asset("ok")];
+        sync(&host, &assets).await.unwrap();
+        let report = sync(&host, &assets).await.unwrap();
+
+        assert!(report.uploaded.is_empty());
+        assert_eq!(report.existing.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn provisioning_points_the_registry_at_the_uploads() {
+        let mut conn = fixture_connection().await;
+        let host = MockHost::with(&["crown_1"]);
+        let report = provision(&mut conn, &host, &[asset("crown_1"), asset("crown_2")]).await.unwrap();
+        assert_eq!(report.hosted().count(), 2);
+
+        let stored: Vec<(String, i64)> = sqlx::query_as("SELECT name, emoji_id FROM emojis WHERE guild_id = 1 ORDER BY name")
+            .fetch_all(&mut conn).await.unwrap();
+        assert_eq!(stored, vec![("crown_1".to_owned(), 100), ("crown_2".to_owned(), 1001)]);
+        let emojis = emoji::emojis(Some(GuildId::new(1)));
+        assert_eq!(emojis.get("crown_1"), "<:crown_1:100>");
+        assert_eq!(emojis.get("crown_2"), "<:crown_2:1001>");
+    }
+}
@@ -0,0 +1,51 @@
+pub use elodon_core::records::*;
+
+use std::collections::HashMap;
+
+use poise::serenity_prelude as serenity;
+use sqlx::SqliteConnection;
+
+use crate::commands::get_connection;
+use crate::error::ElodonError;
+use crate::filters::*;
+use crate::structs::*;
+use crate::{announce, members, settings};
+
+// every server the bot is in has its records updated, the ones with an announcement channel
+// also hear about records that were beaten
+pub async fn update_all(ctx: &serenity::Context) -> Result<(), ElodonError> {
+    let mut conn = get_connection().await?;
+    let plays = Play::fetch_all_or_empty(&mut conn, GeneralFilter::new()).await?;
+    let channels: HashMap<_, _> = settings::announce_channels(&mut conn).await?.into_iter().collect();
+    for guild_id in ctx.cache.guilds() {
+        let players = members::server_players(&mut conn, guild_id).await?;
+        let taken = update_records(&mut conn, guild_id.get() as i64, &plays, &players).await?;
+        let Some(channel_id) = channels.get(&guild_id) else { continue };
+        if taken.is_empty() {
+            continue;
+        }
+
+        let mut lines = Vec::with_capacity(taken.len());
+        for taken in &taken {
+            // the other records still get announced
+            match describe(&mut conn, taken).await {
+                Ok(line) => lines.push((false, line)),
+                Err(err) => println!("Describing the record on song {} for {guild_id} failed: {err}", taken.record.song_id),
+            }
+        }
+        if lines.is_empty() {
+            continue;
+        }
+        announce::post(ctx, *channel_id, "Records taken", lines).await;
+    }
+    Ok(())
+}
+
+async fn describe(conn: &mut SqliteConnection, taken: &TakenRecord) -> Result<String, ElodonError> {
+    let holder = User::fetch_one(conn, UserFilter::new().user_id(Some(taken.record.user_id))).await?;
+    let previous = User::fetch_one(conn, UserFilter::new().user_id(Some(taken.previous.user_id))).await?;
+    let song = Song::fetch_one(conn, GeneralFilter::new().song_id(Some(taken.record.song_id))).await?;
+    Ok(format!("**{}** <@{}> took {} ({}) from **{}** with {} (+{})",
+               holder.name, holder.discord_id(), song.get_name(), taken.record.level(), previous.name,
+               taken.record.score, taken.record.score - taken.previous.score))
+}
@@ -0,0 +1,342 @@
+use std::io::Cursor;
+use std::sync::OnceLock;
+
+use image::{ImageOutputFormat, RgbImage};
+use plotters::prelude::*;
+use plotters::style::FontStyle;
+use poise::serenity_prelude::CreateAttachment;
+
+use crate::error::ElodonError;
+use crate::structs::DisplayLevel;
+
+// DejaVu Sans, see assets/fonts/LICENSE. any ttf works, set ELODON_FONT to the path of another one
+const DEFAULT_FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+const FONT: &str = "sans-serif";
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 450;
+const HISTOGRAM_BINS: usize = 20;
+
+static FONT_LOADED: OnceLock<Result<(), String>> = OnceLock::new();
+
+// plotters has no fonts of its own without system font lookup, so one is registered before drawing
+fn load_font() -> Result<(), ElodonError> {
+    FONT_LOADED.get_or_init(|| {
+        let (name, bytes) = match std::env::var("ELODON_FONT") {
+            Ok(path) => {
+                let bytes = std::fs::read(&path).map_err(|err| format!("couldn't read font {path}: {err}"))?;
+                // registered fonts have to live forever anyway
+                let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+                (path, bytes)
+            }
+            Err(_) => ("the bundled font".to_owned(), DEFAULT_FONT),
+        };
+        for style in [FontStyle::Normal, FontStyle::Bold] {
+            plotters::style::register_font(FONT, style, bytes).map_err(|_| format!("{name} isn't a valid font"))?;
+        }
+        Ok(())
+    }).clone().map_err(ElodonError::RenderError)
+}
+
+fn render_err(err: impl std::fmt::Display) -> ElodonError {
+    ElodonError::RenderError(err.to_string())
+}
+
+// draws onto a width x height canvas and encodes it as png
+pub(crate) fn draw_png(
+    width: u32,
+    height: u32,
+    draw: impl FnOnce(&DrawingArea<BitMapBackend, plotters::coord::Shift>) -> Result<(), ElodonError>
+) -> Result<Vec<u8>, ElodonError> {
+    load_font()?;
+    let mut buffer = vec![0u8; (width * height * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root.fill(&WHITE).map_err(render_err)?;
+        draw(&root)?;
+        root.present().map_err(render_err)?;
+    }
+    let image = RgbImage::from_raw(width, height, buffer)
+        .ok_or(ElodonError::RenderError("image buffer has the wrong size".to_owned()))?;
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png).map_err(render_err)?;
+    Ok(png)
+}
+
+pub fn attachment(png: Vec<u8>, name: &str) -> CreateAttachment {
+    CreateAttachment::bytes(png, format!("{name}.png"))
+}
+
+// how the scores on a chart are spread out, with the caller's own and predicted score marked
+pub fn score_histogram(title: &str, scores: &[u32], yours: Option<u32>, predicted: Option<u32>) -> Result<Vec<u8>, ElodonError> {
+    let marked = yours.iter().chain(predicted.iter()).copied();
+    let (Some(min), Some(max)) = (
+        scores.iter().copied().chain(marked.clone()).min(),
+        scores.iter().copied().chain(marked).max()
+    ) else {
+        return Err(ElodonError::RenderError("no scores to draw".to_owned()));
+    };
+    // in thousands, like the rest of elodon
+    let low = (min / 1000) as f64;
+    let high = ((max / 1000) as f64 + 1.).max(low + 1.);
+    let bin_width = (high - low) / HISTOGRAM_BINS as f64;
+
+    let mut bins = [0u32; HISTOGRAM_BINS];
+    for score in scores {
+        let bin = ((*score as f64 / 1000. - low) / bin_width) as usize;
+        bins[bin.min(HISTOGRAM_BINS - 1)] += 1;
+    }
+    let top = *bins.iter().max().unwrap_or(&1) as f64 * 1.1 + 1.;
+
+    draw_png(WIDTH, HEIGHT, |root| {
+        let mut chart = ChartBuilder::on(root)
+            .caption(title, (FONT, 24))
+            .margin(15)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(low..high, 0f64..top)
+            .map_err(render_err)?;
+        chart.configure_mesh()
+            .x_desc("score (k)")
+            .y_desc("plays")
+            .x_label_formatter(&|score| format!("{:.0}", score))
+            .label_style((FONT, 14))
+            .disable_x_mesh()
+            .draw()
+            .map_err(render_err)?;
+
+        chart.draw_series(bins.iter().enumerate().map(|(i, count)| {
+            let start = low + i as f64 * bin_width;
+            Rectangle::new([(start, 0.), (start + bin_width, *count as f64)], BLUE.mix(0.5).filled())
+        })).map_err(render_err)?;
+
+        for (score, label, color) in [(yours, "your score", RED), (predicted, "predicted", GREEN)] {
+            let Some(score) = score else { continue };
+            let x = score as f64 / 1000.;
+            chart.draw_series(LineSeries::new([(x, 0.), (x, top)], color.stroke_width(3)))
+                .map_err(render_err)?
+                .label(label)
+                .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(3)));
+        }
+        chart.configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .label_font((FONT, 14))
+            .draw()
+            .map_err(render_err)?;
+        Ok(())
+    })
+}
+
+// one line per level, x is days before now
+pub fn elo_history(title: &str, history: &[(f64, DisplayLevel, f32)]) -> Result<Vec<u8>, ElodonError> {
+    if history.is_empty() {
+        return Err(ElodonError::RenderError("no elo history to draw".to_owned()));
+    }
+    let oldest = history.iter().map(|(days, _, _)| *days).fold(0f64, f64::max).max(1.);
+    let (low, high) = history.iter()
+        .fold((f32::MAX, f32::MIN), |(low, high), (_, _, elo)| (low.min(*elo), high.max(*elo)));
+    let padding = ((high - low) * 0.1).max(10.);
+
+    draw_png(WIDTH, HEIGHT, |root| {
+        let mut chart = ChartBuilder::on(root)
+            .caption(title, (FONT, 24))
+            .margin(15)
+            .x_label_area_size(40)
+            .y_label_area_size(50)
+            .build_cartesian_2d(-oldest..0f64, (low - padding)..(high + padding))
+            .map_err(render_err)?;
+        chart.configure_mesh()
+            .x_desc("days ago")
+            .y_desc("ELO")
+            .x_label_formatter(&|days| format!("{:.0}", days.abs()))
+            .label_style((FONT, 14))
+            .draw()
+            .map_err(render_err)?;
+
+        let levels = [DisplayLevel::Easy, DisplayLevel::Med, DisplayLevel::Hard, DisplayLevel::OniPlus];
+        for (level, color) in levels.into_iter().zip([GREEN, BLUE, MAGENTA, RED]) {
+            let points: Vec<(f64, f32)> = history.iter()
+                .filter(|(_, point_level, _)| *point_level == level)
+                .map(|(days, _, elo)| (-days, *elo))
+                .collect();
+            if points.is_empty() {
+                continue;
+            }
+            chart.draw_series(LineSeries::new(points.iter().copied(), color.stroke_width(2)).point_size(3))
+                .map_err(render_err)?
+                .label(level.name())
+                .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2)));
+        }
+        chart.configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .label_font((FONT, 14))
+            .draw()
+            .map_err(render_err)?;
+        Ok(())
+    })
+}
+
+// mean z value per axis on a -2..2 scale, the middle ring is z = 0
+pub fn radar(title: &str, axes: &[(String, f32)]) -> Result<Vec<u8>, ElodonError> {
+    const SIZE: u32 = 500;
+    const Z_RANGE: f32 = 2.;
+    if axes.len() < 3 {
+        return Err(ElodonError::RenderError("a radar needs at least 3 axes".to_owned()));
+    }
+    let center = (SIZE as i32 / 2, SIZE as i32 / 2 + 15);
+    let radius = SIZE as f32 * 0.33;
+    let point = |axis: usize, fraction: f32| {
+        let angle = std::f32::consts::TAU * axis as f32 / axes.len() as f32 - std::f32::consts::FRAC_PI_2;
+        (
+            center.0 + (angle.cos() * radius * fraction) as i32,
+            center.1 + (angle.sin() * radius * fraction) as i32,
+        )
+    };
+    let fraction = |z: f32| (z.clamp(-Z_RANGE, Z_RANGE) + Z_RANGE) / (2. * Z_RANGE);
+
+    draw_png(SIZE, SIZE, |root| {
+        root.draw(&Text::new(title.to_owned(), (15, 10), (FONT, 22).into_font()))
+            .map_err(render_err)?;
+
+        for ring in [0.25, 0.5, 0.75, 1.] {
+            let outline: Vec<(i32, i32)> = (0..=axes.len()).map(|axis| point(axis % axes.len(), ring)).collect();
+            let style = if ring == 0.5 { BLACK.stroke_width(2) } else { BLACK.mix(0.3).stroke_width(1) };
+            root.draw(&PathElement::new(outline, style)).map_err(render_err)?;
+        }
+        for (axis, (name, _)) in axes.iter().enumerate() {
+            root.draw(&PathElement::new([center, point(axis, 1.)], BLACK.mix(0.3))).map_err(render_err)?;
+            let (x, y) = point(axis, 1.15);
+            let style = TextStyle::from((FONT, 14).into_font())
+                .pos(plotters::style::text_anchor::Pos::new(
+                    plotters::style::text_anchor::HPos::Center,
+                    plotters::style::text_anchor::VPos::Center,
+                ));
+            root.draw(&Text::new(name.clone(), (x, y), style)).map_err(render_err)?;
+        }
+
+        let shape: Vec<(i32, i32)> = axes.iter().enumerate().map(|(axis, (_, z))| point(axis, fraction(*z))).collect();
+        root.draw(&Polygon::new(shape.clone(), BLUE.mix(0.3).filled())).map_err(render_err)?;
+        root.draw(&PathElement::new(shape.iter().chain(shape.first()).copied().collect::<Vec<_>>(), BLUE.stroke_width(2)))
+            .map_err(render_err)?;
+        Ok(())
+    })
+}
+
+// what the /player card shows
+pub struct ProfileCard {
+    pub name: String,
+    // png of the discord avatar
+    pub avatar: Option<Vec<u8>>,
+    pub elo: [(DisplayLevel, Option<f32>); 4],
+    // plays per crown (index 1 = clear, 2 = full combo, 3 = donderful combo)
+    pub crowns: [u32; 4],
+    // plays per rank (2 = white iki .. 8 = kiwami)
+    pub ranks: [u32; 9],
+    // (z value, score, chart name)
+    pub notable: Vec<(f32, u32, String)>,
+}
+
+const CARD_WIDTH: u32 = 900;
+const CARD_HEIGHT: u32 = 420;
+const AVATAR_SIZE: u32 = 160;
+
+const DARK: RGBColor = RGBColor(40, 30, 60);
+const MUTED: RGBColor = RGBColor(110, 100, 130);
+const POSITIVE: RGBColor = RGBColor(30, 140, 60);
+const NEGATIVE: RGBColor = RGBColor(190, 40, 40);
+
+const CROWNS: [(&str, RGBColor); 3] = [
+    ("Clears", RGBColor(170, 170, 180)),
+    ("Full combos", RGBColor(230, 180, 30)),
+    ("Donderfuls", RGBColor(220, 60, 160)),
+];
+
+const RANKS: [(&str, RGBColor); 7] = [
+    ("White iki", RGBColor(210, 210, 210)),
+    ("Bronze iki", RGBColor(190, 120, 60)),
+    ("Silver iki", RGBColor(160, 170, 190)),
+    ("Gold miyabi", RGBColor(230, 190, 40)),
+    ("Pink miyabi", RGBColor(240, 130, 180)),
+    ("Purple miyabi", RGBColor(150, 80, 200)),
+    ("Kiwami", RGBColor(40, 40, 40)),
+];
+
+pub fn profile_card(card: &ProfileCard) -> Result<Vec<u8>, ElodonError> {
+    draw_png(CARD_WIDTH, CARD_HEIGHT, |root| {
+        root.fill(&RGBColor(245, 242, 250)).map_err(render_err)?;
+        let text = |size: u32, color: &'static RGBColor| TextStyle::from((FONT, size).into_font()).color(color);
+
+        // avatar, cut into a circle
+        let avatar = card.avatar.as_deref().and_then(|avatar| image::load_from_memory(avatar).ok());
+        let (avatar_x, avatar_y) = (30, 30);
+        let radius = AVATAR_SIZE as i32 / 2;
+        match avatar {
+            Some(avatar) => {
+                let avatar = image::imageops::resize(&avatar.to_rgba8(), AVATAR_SIZE, AVATAR_SIZE, image::imageops::FilterType::Triangle);
+                for (x, y, pixel) in avatar.enumerate_pixels() {
+                    let (dx, dy) = (x as i32 - radius, y as i32 - radius);
+                    if dx * dx + dy * dy > radius * radius || pixel[3] < 128 {
+                        continue;
+                    }
+                    root.draw_pixel((avatar_x + x as i32, avatar_y + y as i32), &RGBColor(pixel[0], pixel[1], pixel[2]))
+                        .map_err(render_err)?;
+                }
+            }
+            None => {
+                root.draw(&Circle::new((avatar_x + radius, avatar_y + radius), radius, MUTED.filled())).map_err(render_err)?;
+            }
+        }
+
+        // name and elo per level
+        let left = 220;
+        root.draw(&Text::new(card.name.clone(), (left, 35), text(34, &DARK))).map_err(render_err)?;
+        for (i, (level, elo)) in card.elo.iter().enumerate() {
+            let x = left + i as i32 * 165;
+            root.draw(&Rectangle::new([(x, 90), (x + 150, 160)], WHITE.filled())).map_err(render_err)?;
+            root.draw(&Text::new(level.name(), (x + 12, 98), text(16, &MUTED))).map_err(render_err)?;
+            let elo = elo.map(|elo| format!("{elo:.0}")).unwrap_or("-".to_owned());
+            root.draw(&Text::new(elo, (x + 12, 120), text(28, &DARK))).map_err(render_err)?;
+        }
+
+        // crown and rank counts as coloured chips
+        let chip = |x: i32, y: i32, label: &str, color: &RGBColor, count: u32| -> Result<(), ElodonError> {
+            root.draw(&Circle::new((x + 8, y + 9), 8, color.filled())).map_err(render_err)?;
+            root.draw(&Text::new(format!("{count} {label}"), (x + 22, y), text(16, &DARK))).map_err(render_err)?;
+            Ok(())
+        };
+        for (i, (label, color)) in CROWNS.iter().enumerate() {
+            chip(left + i as i32 * 165, 180, label, color, card.crowns[i + 1])?;
+        }
+        for (i, (label, color)) in RANKS.iter().enumerate() {
+            let (column, row) = (i as i32 % 4, i as i32 / 4);
+            chip(left + column * 165, 210 + row * 26, label, color, card.ranks[i + 2])?;
+        }
+
+        // best plays by z value
+        root.draw(&Text::new("Most notable plays", (30, 275), text(20, &DARK))).map_err(render_err)?;
+        if card.notable.is_empty() {
+            root.draw(&Text::new("No rated plays yet", (30, 305), text(16, &MUTED))).map_err(render_err)?;
+        }
+        for (i, (z, score, chart_name)) in card.notable.iter().take(4).enumerate() {
+            let y = 305 + i as i32 * 27;
+            root.draw(&Text::new(format!("{z:+.2}"), (30, y), text(18, if *z >= 0. { &POSITIVE } else { &NEGATIVE })))
+                .map_err(render_err)?;
+            root.draw(&Text::new(format!("{score:>7}"), (100, y), text(18, &DARK))).map_err(render_err)?;
+            root.draw(&Text::new(chart_name.clone(), (200, y), text(18, &DARK))).map_err(render_err)?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histograms_draw_with_the_bundled_font() {
+        let png = score_histogram("Yawaraka Tank (Oni)", &[950000, 980000, 1000000], Some(980000), Some(960000)).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use poise::serenity_prelude as serenity;
+use sqlx::SqliteConnection;
+
+use crate::commands::get_connection;
+use crate::error::ElodonError;
+use crate::history::now;
+use crate::{announce, challenges, digest, history, records};
+
+// the bot's background work. each job runs once per interval, and when it last ran is kept in
+// scheduled_runs so a restart doesn't post the weekly digest again
+
+// how often the scheduler looks for jobs that are due
+const TICK: Duration = Duration::from_secs(30);
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Job {
+    EloHistory,
+    Announcements,
+    Records,
+    Challenges,
+    WeeklyDigest,
+}
+
+// announcements first, they find the plays the others go by
+const JOBS: [Job; 5] = [Job::EloHistory, Job::Announcements, Job::Records, Job::Challenges, Job::WeeklyDigest];
+
+impl Job {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Job::EloHistory => "elo_history",
+            Job::Announcements => "announcements",
+            Job::Records => "records",
+            Job::Challenges => "challenges",
+            Job::WeeklyDigest => "weekly_digest",
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        match self {
+            Job::EloHistory => history::SNAPSHOT_INTERVAL,
+            Job::Announcements | Job::Records | Job::Challenges => announce::ANNOUNCE_INTERVAL,
+            Job::WeeklyDigest => digest::DIGEST_PERIOD,
+        }
+    }
+
+    // whether a job that never ran starts right away. the first digest waits a week instead
+    // of summing up a week the bot wasn't watching
+    fn runs_at_start(&self) -> bool {
+        *self != Job::WeeklyDigest
+    }
+
+    async fn run(&self, ctx: &serenity::Context) -> Result<(), ElodonError> {
+        match self {
+            Job::EloHistory => {
+                let mut conn = get_connection().await?;
+                history::record_elo(&mut conn).await?;
+                Ok(())
+            }
+            Job::Announcements => announce::announce(ctx).await,
+            Job::Records => records::update_all(ctx).await,
+            Job::Challenges => challenges::announce_winners(ctx).await,
+            Job::WeeklyDigest => digest::post_digests(ctx).await,
+        }
+    }
+}
+
+// runs for as long as the bot does
+pub async fn run(ctx: serenity::Context) {
+    loop {
+        for job in JOBS {
+            match is_due(job).await {
+                Ok(true) => {
+                    if let Err(err) = job.run(&ctx).await {
+                        println!("Scheduled job {} failed: {err}", job.name());
+                    }
+                    // failed jobs wait for their next turn too, instead of retrying every tick
+                    if let Err(err) = record_run(job).await {
+                        println!("Recording scheduled job {} failed: {err}", job.name());
+                    }
+                }
+                Ok(false) => {}
+                Err(err) => println!("Checking scheduled job {} failed: {err}", job.name()),
+            }
+        }
+        tokio::time::sleep(TICK).await;
+    }
+}
+
+async fn is_due(job: Job) -> Result<bool, ElodonError> {
+    let mut conn = get_connection().await?;
+    let last_run: Option<(i64,)> = sqlx::query_as("SELECT last_run FROM scheduled_runs WHERE job = ?")
+        .bind(job.name())
+        .fetch_optional(&mut *conn).await?;
+    match last_run {
+        Some((last_run,)) => Ok(now() - last_run >= job.interval().as_secs() as i64),
+        None if job.runs_at_start() => Ok(true),
+        None => {
+            mark_run(&mut conn, job).await?;
+            Ok(false)
+        }
+    }
+}
+
+async fn record_run(job: Job) -> Result<(), ElodonError> {
+    let mut conn = get_connection().await?;
+    mark_run(&mut conn, job).await
+}
+
+async fn mark_run(conn: &mut SqliteConnection, job: Job) -> Result<(), ElodonError> {
+    sqlx::query("INSERT OR REPLACE INTO scheduled_runs (job, last_run) VALUES (?,?)")
+        .bind(job.name())
+        .bind(now())
+        .execute(conn).await?;
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex, RwLock};
+
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use poise::ChoiceParameter;
+use poise::serenity_prelude::{ChannelId, GuildId, RoleId, UserId};
+use sqlx::{Connection, FromRow, SqliteConnection};
+
+use crate::commands::get_connection;
+use crate::error::ElodonError;
+use crate::structs::*;
+
+pub const DEFAULT_PREFIX: &str = "~";
+pub const DEFAULT_REACTION_TRIGGER: &str = "elodon";
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, ChoiceParameter, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum LeaderboardScope {
+    // only members of the server the command was run in
+    #[default]
+    Server = 0,
+    Global = 1,
+}
+
+impl Display for LeaderboardScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, Default, FromRow)]
+struct GuildSettingsRow {
+    prefix: Option<String>,
+    reaction_trigger: Option<String>,
+    default_level: Option<u32>,
+    leaderboard_scope: Option<u32>,
+    alias_role_id: Option<i64>,
+    announce_channel_id: Option<i64>,
+    digest_channel_id: Option<i64>,
+}
+
+// what /config changes. unset values fall back to the defaults
+#[derive(Clone, Debug, Default)]
+pub struct GuildSettings {
+    pub prefix: Option<String>,
+    // empty string turns the reaction off
+    pub reaction_trigger: Option<String>,
+    pub default_level: Option<Level>,
+    pub leaderboard_scope: LeaderboardScope,
+    pub alias_role: Option<RoleId>,
+    // where new personal bests of the server's members are posted, None for nowhere
+    pub announce_channel: Option<ChannelId>,
+    // where the weekly digest is posted
+    pub digest_channel: Option<ChannelId>,
+    pub banned: Vec<UserId>,
+}
+
+impl GuildSettings {
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_deref().unwrap_or(DEFAULT_PREFIX)
+    }
+
+    pub fn reaction_trigger(&self) -> Option<&str> {
+        match self.reaction_trigger.as_deref() {
+            None => Some(DEFAULT_REACTION_TRIGGER),
+            Some("") => None,
+            Some(trigger) => Some(trigger),
+        }
+    }
+
+    pub fn default_level(&self) -> Level {
+        self.default_level.unwrap_or(Level::Ura)
+    }
+
+    pub fn default_display_level(&self) -> DisplayLevel {
+        self.default_level.map(DisplayLevel::from).unwrap_or(DisplayLevel::OniPlus)
+    }
+
+    pub fn is_banned(&self, user: UserId) -> bool {
+        self.banned.contains(&user)
+    }
+
+    async fn load(conn: &mut SqliteConnection, guild_id: GuildId) -> Result<GuildSettings, ElodonError> {
+        let row: GuildSettingsRow = sqlx::query_as(
+            "SELECT prefix, reaction_trigger, default_level, leaderboard_scope, alias_role_id, announce_channel_id, digest_channel_id \
+                FROM guild_settings WHERE guild_id = ?"
+        ).bind(guild_id.get() as i64)
+            .fetch_optional(&mut *conn).await?
+            .unwrap_or_default();
+        let banned: Vec<(i64,)> = sqlx::query_as("SELECT discord_id FROM guild_bans WHERE guild_id = ?")
+            .bind(guild_id.get() as i64)
+            .fetch_all(&mut *conn).await?;
+
+        Ok(GuildSettings {
+            prefix: row.prefix,
+            reaction_trigger: row.reaction_trigger,
+            default_level: row.default_level.and_then(|level| Level::try_from(level).ok()),
+            leaderboard_scope: row.leaderboard_scope
+                .and_then(|scope| LeaderboardScope::try_from(scope).ok())
+                .unwrap_or_default(),
+            alias_role: row.alias_role_id.map(|role| RoleId::new(role as u64)),
+            announce_channel: row.announce_channel_id.map(|channel| ChannelId::new(channel as u64)),
+            digest_channel: row.digest_channel_id.map(|channel| ChannelId::new(channel as u64)),
+            banned: banned.into_iter().map(|(discord_id,)| UserId::new(discord_id as u64)).collect(),
+        })
+    }
+
+    async fn save(&self, conn: &mut SqliteConnection, guild_id: GuildId) -> Result<(), ElodonError> {
+        let mut transaction = conn.begin().await?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO guild_settings \
+                (guild_id, prefix, reaction_trigger, default_level, leaderboard_scope, alias_role_id, announce_channel_id, digest_channel_id) \
+                VALUES (?,?,?,?,?,?,?,?)"
+        ).bind(guild_id.get() as i64)
+            .bind(&self.prefix)
+            .bind(&self.reaction_trigger)
+            .bind(self.default_level.map(|level| level.id()))
+            .bind(u32::from(self.leaderboard_scope))
+            .bind(self.alias_role.map(|role| role.get() as i64))
+            .bind(self.announce_channel.map(|channel| channel.get() as i64))
+            .bind(self.digest_channel.map(|channel| channel.get() as i64))
+            .execute(&mut *transaction).await?;
+
+        sqlx::query("DELETE FROM guild_bans WHERE guild_id = ?")
+            .bind(guild_id.get() as i64)
+            .execute(&mut *transaction).await?;
+        for banned in &self.banned {
+            sqlx::query("INSERT INTO guild_bans (guild_id, discord_id) VALUES (?,?)")
+                .bind(guild_id.get() as i64)
+                .bind(banned.get() as i64)
+                .execute(&mut *transaction).await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+// every server that has the channel in column set, and which channel
+async fn channels(conn: &mut SqliteConnection, column: &str) -> Result<Vec<(GuildId, ChannelId)>, ElodonError> {
+    let rows: Vec<(i64, i64)> = sqlx::query_as(&format!(
+        "SELECT guild_id, {column} FROM guild_settings WHERE {column} IS NOT NULL"
+    )).fetch_all(conn).await?;
+    Ok(rows.into_iter()
+        .map(|(guild_id, channel_id)| (GuildId::new(guild_id as u64), ChannelId::new(channel_id as u64)))
+        .collect())
+}
+
+pub async fn announce_channels(conn: &mut SqliteConnection) -> Result<Vec<(GuildId, ChannelId)>, ElodonError> {
+    channels(conn, "announce_channel_id").await
+}
+
+pub async fn digest_channels(conn: &mut SqliteConnection) -> Result<Vec<(GuildId, ChannelId)>, ElodonError> {
+    channels(conn, "digest_channel_id").await
+}
+
+// settings are read on every message (prefix, reaction) so they're cached after the first load
+#[derive(Default)]
+pub struct GuildSettingsCache {
+    settings: RwLock<HashMap<GuildId, GuildSettings>>,
+    // held across the read, change and save of update so concurrent /config commands don't lose changes
+    updates: Mutex<HashMap<GuildId, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl GuildSettingsCache {
+    // defaults outside of servers
+    pub async fn get(&self, guild_id: Option<GuildId>) -> Result<GuildSettings, ElodonError> {
+        let Some(guild_id) = guild_id else {
+            return Ok(GuildSettings::default());
+        };
+        if let Some(settings) = self.settings.read().unwrap().get(&guild_id) {
+            return Ok(settings.clone());
+        }
+        let mut conn = get_connection().await?;
+        let settings = GuildSettings::load(&mut conn, guild_id).await?;
+        // an update may have cached newer settings in the meantime
+        Ok(self.settings.write().unwrap().entry(guild_id).or_insert(settings).clone())
+    }
+
+    pub async fn update(&self, guild_id: GuildId, change: impl FnOnce(&mut GuildSettings)) -> Result<GuildSettings, ElodonError> {
+        let lock = self.updates.lock().unwrap().entry(guild_id).or_default().clone();
+        let _guard = lock.lock().await;
+        let mut settings = self.get(Some(guild_id)).await?;
+        change(&mut settings);
+        let mut conn = get_connection().await?;
+        settings.save(&mut conn, guild_id).await?;
+        self.settings.write().unwrap().insert(guild_id, settings.clone());
+        Ok(settings)
+    }
+}
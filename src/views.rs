@@ -0,0 +1,1037 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use noisy_float::types::{R32, r32};
+use poise::ChoiceParameter;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{GuildId, UserId};
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+
+use crate::{accounts, elo, emoji, render};
+use crate::Context;
+use crate::challenges::Standing;
+use crate::display::Symbols;
+use crate::elo::get_predicted_score;
+use crate::error::{CoreError, ElodonError};
+use crate::filters::*;
+use crate::paginate::{Control, EntrySource};
+use crate::records::Record;
+use crate::settings::{GuildSettings, LeaderboardScope};
+use crate::structs::*;
+
+// what /scores, /player and /suggest show, worked out from the database alone. the commands
+// collect their options from discord, build one of these and send it
+
+// the parts of a poise::Context the views depend on
+pub struct Invocation {
+    // primary Donder ID of whoever ran the command
+    pub author: Option<User>,
+    pub settings: GuildSettings,
+    // None outside of servers
+    pub guild_id: Option<GuildId>,
+    pub emojis: emoji::EmojiSet,
+}
+
+impl Invocation {
+    pub async fn new(ctx: Context<'_>, conn: &mut SqliteConnection) -> Result<Invocation, ElodonError> {
+        let author = accounts::primary_user(conn, ctx.author().id).await?;
+        let settings = ctx.data().guild_settings.get(ctx.guild_id()).await?;
+        Ok(Invocation {
+            author,
+            settings,
+            guild_id: ctx.guild_id(),
+            emojis: emoji::emojis(ctx.guild_id()),
+        })
+    }
+
+    // the server leaderboards are limited to, None for everyone. scope overrides the server's
+    // setting, and outside of servers everyone is the default
+    fn leaderboard_guild(&self, scope: Option<LeaderboardScope>) -> Result<Option<u64>, ElodonError> {
+        let scope = match (scope, self.guild_id) {
+            (Some(scope), _) => scope,
+            (None, Some(_)) => self.settings.leaderboard_scope,
+            (None, None) => LeaderboardScope::Global,
+        };
+        match scope {
+            LeaderboardScope::Server => Ok(Some(self.guild_id.ok_or(ElodonError::NoGuild)?.get())),
+            LeaderboardScope::Global => Ok(None)
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, ChoiceParameter)]
+pub enum ScoreSort {
+    #[default]
+    Score,
+    #[name = "z value"]
+    ZValue,
+    Combo,
+    #[name = "fewest bads"]
+    Bads,
+}
+
+// one line of a leaderboard: the play, its place by score (None for estimates) and who played it
+#[derive(Clone, Debug, FromRow)]
+struct ScoreRow {
+    #[sqlx(flatten)]
+    play: Play,
+    place: Option<i64>,
+    user_name: Option<String>,
+    // of the player at the leaderboard's level, the author's for estimates
+    elo: Option<f32>,
+}
+
+// the leaderboard of one chart, re-sorted and reloaded from the controls below it. only the shown
+// page is fetched, ranking, merging and sorting happen in sql
+pub struct ScoreEntries {
+    pool: SqlitePool,
+    song: Song,
+    chart: Chart,
+    level: Level,
+    levels: Vec<Level>,
+    author_user: Option<User>,
+    // only members of this server, None for a global leaderboard
+    guild_id: Option<u64>,
+    merged: bool,
+    detailed: bool,
+    estimates: bool,
+    sort: ScoreSort,
+    emojis: emoji::EmojiSet,
+    // drawn for this level, redrawn when the level changes
+    histogram: Option<(Level, Vec<serenity::CreateAttachment>)>,
+}
+
+// the /scores options besides the song
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ScoreOptions {
+    pub level: Option<Level>,
+    pub detailed: bool,
+    pub estimates: bool,
+    pub merged: bool,
+    pub sort: ScoreSort,
+    // None for the server's setting
+    pub scope: Option<LeaderboardScope>,
+}
+
+// sql for an optional number
+fn sql_value(value: Option<impl std::fmt::Display>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_else(|| "NULL".to_owned())
+}
+
+impl ScoreEntries {
+    // the leaderboard at options.level, or the next easier level with plays if it has none
+    pub async fn new(pool: SqlitePool, invocation: &Invocation, song_id: u32, options: ScoreOptions) -> Result<ScoreEntries, ElodonError> {
+        let song_filter = GeneralFilter::new().song_id(Some(song_id));
+        let mut conn = pool.acquire().await?;
+        let song = Song::fetch_one(&mut conn, song_filter).await?;
+        let charts: Vec<Chart> = Chart::fetch_all(&mut conn, song_filter).await?;
+        drop(conn);
+        let levels: Vec<Level> = charts.iter().map(|chart| chart.level()).sorted_by_key(|level| level.id()).collect();
+
+        let mut entries = ScoreEntries {
+            pool,
+            song,
+            chart: charts[0],
+            level: options.level.unwrap_or(invocation.settings.default_level()),
+            levels,
+            author_user: invocation.author.clone(),
+            guild_id: invocation.leaderboard_guild(options.scope)?,
+            merged: options.merged,
+            detailed: options.detailed,
+            estimates: options.estimates,
+            sort: options.sort,
+            emojis: invocation.emojis.clone(),
+            histogram: None,
+        };
+
+        // fall back to easier difficulties until one has plays
+        loop {
+            match entries.load().await {
+                Ok(()) if entries.count().await? > 0 => return Ok(entries),
+                Ok(()) | Err(ElodonError::Core(CoreError::NoResults { .. })) => {}
+                Err(err) => return Err(err)
+            }
+            match entries.level.decrease() {
+                Some(level) => entries.level = level,
+                None => break
+            }
+        }
+        Err(CoreError::NoResults { search: "plays".to_owned(), id: format!("song_id is {song_id}") }.into())
+    }
+
+    // title, ranked scores, the author's score and their predicted score for render::score_histogram
+    async fn histogram(&mut self) -> Result<(String, Vec<u32>, Option<u32>, Option<u32>), ElodonError> {
+        let ranked: Vec<(u32, i64)> = sqlx::query_as(&format!("{} SELECT score, user_id FROM entries WHERE place IS NOT NULL", self.entries_query()))
+            .fetch_all(&mut *self.pool.acquire().await?).await?;
+        let your_score = self.author_user.as_ref()
+            .and_then(|author_user| ranked.iter().find(|(_, user_id)| *user_id == author_user.id))
+            .map(|(score, _)| *score);
+        let predicted_score = self.author_user.as_ref()
+            .and_then(|author_user| get_predicted_score(author_user.elo(self.level.into()), &self.chart, 0.));
+        let scores = ranked.into_iter().map(|(score, _)| score).collect();
+        Ok((format!("{} ({})", self.song.get_name(), self.level), scores, your_score, predicted_score))
+    }
+
+    // the chart of the current level
+    async fn load(&mut self) -> Result<(), ElodonError> {
+        let filter = GeneralFilter::new()
+            .song_id(Some(self.song.id))
+            .level(Some(self.level));
+        self.chart = Chart::fetch_one(&mut *self.pool.acquire().await?, filter).await?;
+        Ok(())
+    }
+
+    // the author's scores at z values -1 to 2, if they have an elo at this level
+    fn estimated_scores(&self) -> Vec<u32> {
+        const Z_SCORES: [f32; 4] = [-1., 0., 1., 2.];
+        let (Some(author_user), Some(sd), true) = (&self.author_user, self.chart.sd_mean, self.estimates) else {
+            return vec![];
+        };
+        match get_predicted_score(author_user.elo(self.level.into()), &self.chart, 0.) {
+            Some(average_score) => Z_SCORES.iter().map(|z| (average_score as f32 + sd * z) as u32).collect(),
+            None => vec![]
+        }
+    }
+
+    // "WITH entries AS (...)": the plays of the current level, scoped, merged and ranked by score,
+    // with the estimates as rows without a place
+    fn entries_query(&self) -> String {
+        let elo_column = match DisplayLevel::from(self.level) {
+            DisplayLevel::Easy => "elo1",
+            DisplayLevel::Med => "elo2",
+            DisplayLevel::Hard => "elo3",
+            DisplayLevel::OniPlus => "elo4",
+        };
+        let scope = match self.guild_id {
+            Some(guild_id) => format!(" AND top_plays.user_id IN (SELECT users.user_id FROM users \
+                JOIN guild_members ON guild_members.discord_id = users.discord_id WHERE guild_members.guild_id = {guild_id})"),
+            None => String::new(),
+        };
+        // the best play of every person, plays of unlinked donder ids count as their own person
+        let merged = if self.merged { " WHERE nth = 1" } else { "" };
+        let author_elo = sql_value(self.author_user.as_ref().and_then(|author_user| author_user.elo(self.level.into())));
+        let estimates: String = self.estimated_scores().iter()
+            .map(|score| format!(" UNION ALL SELECT 0, {}, {}, {score}, 0, 0, 0, 0, 0, 0, 0, NULL, {author_elo}, NULL", self.song.id, self.level.id()))
+            .collect();
+        format!(
+            "WITH scoped AS (\
+                SELECT top_plays.user_id, song_id, level_id, score, good_cnt, ok_cnt, bad_cnt, combo_cnt, roll_cnt, rank, crown, \
+                    users.user_name, users.{elo_column} AS elo, \
+                    ROW_NUMBER() OVER (PARTITION BY COALESCE(users.discord_id, -top_plays.user_id) ORDER BY score DESC, top_plays.user_id) AS nth \
+                FROM top_plays LEFT JOIN users ON users.user_id = top_plays.user_id \
+                WHERE song_id = {} AND level_id = {}{scope}\
+            ), entries AS (\
+                SELECT user_id, song_id, level_id, score, good_cnt, ok_cnt, bad_cnt, combo_cnt, roll_cnt, rank, crown, user_name, elo, \
+                    ROW_NUMBER() OVER (ORDER BY score DESC, user_id) - 1 AS place \
+                FROM scoped{merged}{estimates}\
+            )",
+            self.song.id, self.level.id()
+        )
+    }
+
+    // ORDER BY for the sort. z values are elo::get_z_value at sd_z 1
+    fn order(&self) -> String {
+        match self.sort {
+            ScoreSort::Score => "score DESC, place".to_owned(),
+            ScoreSort::ZValue => {
+                let chart = &self.chart;
+                let z_value = format!(
+                    "(score - MAX(0, CAST({} * (elo + {} * 0.001 - {}) AS INTEGER) + 1000000)) / ({} + {})",
+                    sql_value(chart.score_slope), sql_value(chart.sd_sd), sql_value(chart.score_miyabi),
+                    sql_value(chart.sd_mean), sql_value(chart.sd_sd)
+                );
+                // plays without a z value go last
+                format!("{z_value} IS NULL, {z_value} DESC, score DESC")
+            }
+            ScoreSort::Combo => "combo_cnt DESC, score DESC".to_owned(),
+            ScoreSort::Bads => "bad_cnt, score DESC".to_owned(),
+        }
+    }
+
+    async fn rows(&mut self, offset: usize, limit: usize) -> Result<Vec<ScoreRow>, ElodonError> {
+        let query = format!("{} SELECT * FROM entries ORDER BY {} LIMIT ? OFFSET ?", self.entries_query(), self.order());
+        Ok(sqlx::query_as(&query)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&mut *self.pool.acquire().await?).await?)
+    }
+
+    fn user_name(&self, row: &ScoreRow) -> String {
+        match (&row.user_name, &self.author_user) {
+            (Some(user_name), _) => user_name.clone(),
+            //is a generated play
+            (None, Some(author_user)) if row.play.user == 0 => format!("{} (estimated)", author_user.name),
+            // plays of unlinked donder ids
+            (None, _) => format!("#{}", row.play.user),
+        }
+    }
+
+    fn render(&self, row: &ScoreRow) -> String {
+        let play = &row.play;
+        let ranking = match row.place {
+            None => {format!("")}
+            Some(place) => { format!("#{})", place + 1) }
+        };
+        let user_name = self.user_name(row);
+
+        let emojis = &self.emojis;
+        match self.detailed {
+            true => {
+                let z_value_txt = match elo::get_z_value(play.score, row.elo, &self.chart, 1f32) {
+                    Some(z_value) => format!("{:+.1}", z_value),
+                    None => "????".to_owned()
+                };
+
+                let ur = elo::get_sd(25., play.good_cnt as f64/ (play.good_cnt + play.ok_cnt + 1) as f64);
+
+                let crown_emoji = emojis.crown(play.crown);
+                let rank_emoji = emojis.rank(play.rank);
+                let good_emoji = emojis.get("good");
+                let ok_emoji = emojis.get("ok");
+                let bad_emoji = format!("{}{}", emojis.get("bad_0"), emojis.get("bad_1"));
+                let combo_emoji = format!("{}{}", emojis.get("combo_0"), emojis.get("combo_1"));
+                let rolls_emoji = format!("{}{}{}", emojis.get("rolls_0"), emojis.get("rolls_1"), emojis.get("rolls_2"));
+                format!("`{:>4}` `{}` **{}**\n⮱`{:>7}` `{:>4}`{} {} {}\t σ<`{:+>3.1}ms`\n⮱`{:>4}`{} `{:>3}`{}` {:>3}`{} `{:>3}`{}",
+                        ranking, z_value_txt, user_name,
+                        play.score,
+                        play.combo_cnt, combo_emoji,
+                        crown_emoji, rank_emoji, ur,
+                        play.good_cnt, good_emoji,
+                        play.ok_cnt, ok_emoji,
+                        play.bad_cnt, bad_emoji,
+                        play.roll_cnt, rolls_emoji
+                )
+            }
+            false => {
+                format!("`{:>4} {:>7}` by {}", ranking, play.score, user_name)
+            }
+        }
+    }
+}
+
+impl EntrySource for ScoreEntries {
+    async fn count(&mut self) -> Result<usize, ElodonError> {
+        let (count,): (i64,) = sqlx::query_as(&format!("{} SELECT COUNT(*) FROM entries", self.entries_query()))
+            .fetch_one(&mut *self.pool.acquire().await?).await?;
+        Ok(count as usize)
+    }
+
+    async fn entries(&mut self, offset: usize, limit: usize) -> Result<Vec<String>, ElodonError> {
+        Ok(self.rows(offset, limit).await?
+            .iter()
+            .map(|row| self.render(row))
+            .collect())
+    }
+
+    fn per_page(&self) -> usize {
+        if self.detailed { 9 } else { 27 }
+    }
+
+    fn header(&self) -> Option<String> {
+        Some(format!("### Results for {} ({:?}):\n", self.song, self.level))
+    }
+
+    fn components(&self, id_prefix: &str, disabled: bool) -> Vec<serenity::CreateActionRow> {
+        let sort_options = ScoreSort::list().into_iter()
+            .enumerate()
+            .map(|(index, choice)| {
+                serenity::CreateSelectMenuOption::new(format!("Sort by {}", choice.name), index.to_string())
+                    .default_selection(ScoreSort::from_index(index) == Some(self.sort))
+            })
+            .collect();
+        let mut rows = vec![serenity::CreateActionRow::SelectMenu(
+            serenity::CreateSelectMenu::new(format!("{id_prefix}sort"), serenity::CreateSelectMenuKind::String { options: sort_options })
+                .disabled(disabled)
+        )];
+
+        if self.levels.len() > 1 {
+            let level_options = self.levels.iter()
+                .map(|level| {
+                    serenity::CreateSelectMenuOption::new(level.to_string(), level.id().to_string())
+                        .default_selection(*level == self.level)
+                })
+                .collect();
+            rows.push(serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(format!("{id_prefix}level"), serenity::CreateSelectMenuKind::String { options: level_options })
+                    .disabled(disabled)
+            ));
+        }
+
+        let toggle = |action: &str, label: &str, on: bool| {
+            serenity::CreateButton::new(format!("{id_prefix}{action}"))
+                .style(if on { serenity::ButtonStyle::Success } else { serenity::ButtonStyle::Secondary })
+                .label(label)
+                .disabled(disabled)
+        };
+        let mut buttons = vec![toggle("detail", "Details", self.detailed)];
+        if self.author_user.is_some() {
+            buttons.push(toggle("estimates", "Your estimates", self.estimates));
+        }
+        rows.push(serenity::CreateActionRow::Buttons(buttons));
+        rows
+    }
+
+    async fn handle(&mut self, action: &str, press: &serenity::ComponentInteraction) -> Result<Control, ElodonError> {
+        let selected = match &press.data.kind {
+            serenity::ComponentInteractionDataKind::StringSelect { values } => values.first().and_then(|value| value.parse::<u32>().ok()),
+            _ => None
+        };
+        match action {
+            "sort" => {
+                let Some(sort) = selected.and_then(|index| ScoreSort::from_index(index as usize)) else {
+                    return Ok(Control::Ignore);
+                };
+                self.sort = sort;
+                Ok(Control::Reset)
+            }
+            "level" => {
+                let Some(level) = selected.and_then(|id| Level::try_from(id).ok()) else {
+                    return Ok(Control::Ignore);
+                };
+                self.level = level;
+                self.load().await?;
+                Ok(Control::Reset)
+            }
+            "detail" => {
+                self.detailed = !self.detailed;
+                Ok(Control::Rerender)
+            }
+            "estimates" => {
+                self.estimates = !self.estimates;
+                Ok(Control::Reset)
+            }
+            _ => Ok(Control::Ignore)
+        }
+    }
+
+    async fn attachments(&mut self) -> Result<Vec<serenity::CreateAttachment>, ElodonError> {
+        if let Some((level, files)) = &self.histogram {
+            if *level == self.level {
+                return Ok(files.clone());
+            }
+        }
+        let (title, scores, your_score, predicted_score) = self.histogram().await?;
+        let files = match render::score_histogram(&title, &scores, your_score, predicted_score) {
+            Ok(png) => vec![render::attachment(png, "scores")],
+            Err(err) => {
+                println!("Couldn't draw score histogram: {err}");
+                vec![]
+            }
+        };
+        self.histogram = Some((self.level, files.clone()));
+        Ok(files)
+    }
+}
+
+// everything /player shows apart from what needs discord, i.e. the avatar on the card
+pub struct PlayerProfile {
+    pub name: String,
+    pub preamble: String,
+    pub genre_stats: Vec<elo::GenreStats>,
+    pub card: render::ProfileCard,
+}
+
+impl PlayerProfile {
+    // users are Donder IDs of discord_user, several when merging
+    pub async fn new(conn: &mut SqliteConnection, users: &[User], discord_user: UserId, level: Option<DisplayLevel>) -> Result<PlayerProfile, ElodonError> {
+        let filter = GeneralFilter::new()
+            .discord_id(Some(discord_user.get()))
+            .display_level(level);
+        let mut plays: Vec<Play> = vec![];
+        for user in users {
+            match Play::fetch_all(conn, filter.user_id(Some(user.id))).await {
+                Ok(user_plays) => plays.extend(user_plays),
+                Err(CoreError::NoResults { .. }) => {}
+                Err(err) => return Err(err.into())
+            }
+        }
+        // only the best play of each chart when merging several accounts
+        let plays: Vec<Play> = plays.into_iter()
+            .sorted_by_key(|play| (play.song, play.level, -(play.score as i64)))
+            .unique_by(|play| (play.song, play.level))
+            .collect();
+        let user_name = users.iter().map(|user| &user.name).join(" + ");
+        let info_filter = filter.discord_id(None).user_id(None);
+
+        let mut ranked_plays: Vec<(R32, &Play, Genre, String, Option<f32>)> = vec![];
+        for play in &plays {
+            let elo = users.iter()
+                .find(|user| user.id == play.user)
+                .and_then(|user| user.elo(play.level().into()));
+            if let Some(ranked_play) = get_play_info(conn, elo, play).await {
+                match level {
+                    Some(level) if (level != ranked_play.1.level().into()) => continue,
+                    _ => {}
+                }
+                ranked_plays.push(ranked_play);
+            }
+        }
+        ranked_plays.sort_by_key(|(z,_,_,_,_)| *z * r32(-1f32));
+        let genre_stats = elo::genre_stats(ranked_plays.iter().map(|(z, _, genre, _, shift)| (*genre, z.raw(), *shift)));
+
+        let mut preamble: String = format!("## User <@{}> ({})\n Showing plays{}.\n### Most notable plays\n",
+                                         discord_user, user_name, info_filter);
+        if ranked_plays.is_empty() {
+            preamble.push_str("No plays found. Player has no ELO\n");
+        } else {
+            for (z, play, _, chart_name, _) in ranked_plays.iter().take(5) {
+                let z_value: f32 = z.raw();
+                preamble.push_str(&*format!("{:+.2}. {:>7} on {}\n", z_value, play.score, chart_name));
+            }
+        }
+        let rated_genres: Vec<&elo::GenreStats> = genre_stats.iter()
+            .filter(|stats| stats.count >= elo::MIN_GENRE_PLAYS)
+            .collect();
+        if rated_genres.len() >= 2 {
+            preamble.push_str("### Strengths and weaknesses\n");
+            for stats in rated_genres {
+                let shift = stats.elo_shift.map(|shift| format!(", {:+.0} ELO", shift)).unwrap_or_default();
+                preamble.push_str(&*format!("`{:+.2}` {} ({} plays{})\n", stats.mean_z, stats.genre, stats.count, shift));
+            }
+        }
+        preamble.push_str("### Filtered plays\n\n");
+
+        let mut crowns = [0u32; 4];
+        let mut ranks = [0u32; 9];
+        for play in &plays {
+            crowns[(play.crown as usize).min(3)] += 1;
+            ranks[(play.rank as usize).min(8)] += 1;
+        }
+        let elo_of = |level| users.iter().find_map(|user| user.elo(level));
+        let card = render::ProfileCard {
+            name: user_name.clone(),
+            avatar: None,
+            elo: [DisplayLevel::Easy, DisplayLevel::Med, DisplayLevel::Hard, DisplayLevel::OniPlus].map(|level| (level, elo_of(level))),
+            crowns,
+            ranks,
+            notable: ranked_plays.iter().take(5).map(|(z, play, _, chart_name, _)| (z.raw(), play.score, chart_name.clone())).collect(),
+        };
+
+        Ok(PlayerProfile {
+            name: user_name,
+            preamble,
+            genre_stats,
+            card,
+        })
+    }
+}
+
+// a player's plays, a page at a time. chart names are looked up for the shown page only
+pub struct PlayerEntries {
+    pool: SqlitePool,
+    // several when merging, only the best play of every chart is listed then
+    user_ids: Vec<i64>,
+    level: Option<DisplayLevel>,
+}
+
+impl PlayerEntries {
+    pub fn new(pool: SqlitePool, user_ids: Vec<i64>, level: Option<DisplayLevel>) -> PlayerEntries {
+        PlayerEntries { pool, user_ids, level }
+    }
+
+    fn query(&self, columns: &str) -> String {
+        let level = match self.level {
+            Some(level) => format!(" AND level_id BETWEEN {} AND {}", level.min_value(), level.max_value()),
+            None => String::new(),
+        };
+        format!(
+            "SELECT {columns} FROM (\
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY song_id, level_id ORDER BY score DESC, user_id) AS nth \
+                FROM top_plays WHERE user_id IN ({}){level}\
+            ) WHERE nth = 1",
+            self.user_ids.iter().join(", ")
+        )
+    }
+}
+
+impl EntrySource for PlayerEntries {
+    async fn count(&mut self) -> Result<usize, ElodonError> {
+        let (count,): (i64,) = sqlx::query_as(&self.query("COUNT(*)"))
+            .fetch_one(&mut *self.pool.acquire().await?).await?;
+        Ok(count as usize)
+    }
+
+    async fn entries(&mut self, offset: usize, limit: usize) -> Result<Vec<String>, ElodonError> {
+        let query = format!(
+            "{} ORDER BY song_id, level_id LIMIT ? OFFSET ?",
+            self.query("user_id, song_id, level_id, score, good_cnt, ok_cnt, bad_cnt, combo_cnt, roll_cnt, rank, crown")
+        );
+        let mut conn = self.pool.acquire().await?;
+        let plays: Vec<Play> = sqlx::query_as(&query)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&mut *conn).await?;
+        let mut entries = vec![];
+        for play in &plays {
+            match chart_name(&mut conn, play).await{
+                Ok(chart_name) => {
+                    entries.push(format!("{:>7} on {}", play.score, chart_name));
+                }
+                Err(_) => {
+                    entries.push(format!("{:>7} on song_id={}", play.score, play.song));}
+            }
+        }
+        Ok(entries)
+    }
+}
+
+// server records, chart names are looked up for the shown page only. names holds the holders to
+// show next to each record, empty when they're all the same player
+pub struct RecordEntries {
+    pool: SqlitePool,
+    records: Vec<Record>,
+    names: HashMap<i64, String>,
+}
+
+impl RecordEntries {
+    pub fn new(pool: SqlitePool, records: Vec<Record>, names: HashMap<i64, String>) -> RecordEntries {
+        RecordEntries { pool, records, names }
+    }
+}
+
+impl EntrySource for RecordEntries {
+    async fn count(&mut self) -> Result<usize, ElodonError> {
+        Ok(self.records.len())
+    }
+
+    async fn entries(&mut self, offset: usize, limit: usize) -> Result<Vec<String>, ElodonError> {
+        let mut conn = self.pool.acquire().await?;
+        let mut entries = vec![];
+        for record in self.records.iter().skip(offset).take(limit) {
+            let chart_name = match Chart::fetch_one(&mut conn, record.chart()).await {
+                Ok(chart) => chart.full_name(&mut conn).await?,
+                Err(_) => format!("song_id={} ({})", record.song_id, record.level()),
+            };
+            let holder = match self.names.get(&record.user_id) {
+                Some(name) => format!(" by **{name}**"),
+                None => String::new(),
+            };
+            entries.push(format!("`{:>7}` on {}{} since <t:{}:d>", record.score, chart_name, holder, record.held_since));
+        }
+        Ok(entries)
+    }
+}
+
+// the standings of a challenge, chart names are looked up for the shown page only
+pub struct StandingEntries {
+    pool: SqlitePool,
+    standings: Vec<Standing>,
+    names: HashMap<i64, String>,
+}
+
+impl StandingEntries {
+    pub async fn new(pool: SqlitePool, standings: Vec<Standing>) -> Result<StandingEntries, ElodonError> {
+        let names = User::fetch_all_or_empty(&mut *pool.acquire().await?, GeneralFilter::new()).await?
+            .into_iter()
+            .map(|user| (user.id, user.name))
+            .collect();
+        Ok(StandingEntries { pool, standings, names })
+    }
+}
+
+impl EntrySource for StandingEntries {
+    async fn count(&mut self) -> Result<usize, ElodonError> {
+        Ok(self.standings.len())
+    }
+
+    async fn entries(&mut self, offset: usize, limit: usize) -> Result<Vec<String>, ElodonError> {
+        let mut conn = self.pool.acquire().await?;
+        let mut entries = vec![];
+        for (index, standing) in self.standings.iter().enumerate().skip(offset).take(limit) {
+            entries.push(standing_line(&mut conn, index, standing, &self.names).await?);
+        }
+        Ok(entries)
+    }
+}
+
+// "#1 name +gain on chart (before → score)". the first three get medals
+pub async fn standing_line(conn: &mut SqliteConnection, index: usize, standing: &Standing, names: &HashMap<i64, String>) -> Result<String, ElodonError> {
+    let place = match index {
+        0 => "🥇".to_owned(),
+        1 => "🥈".to_owned(),
+        2 => "🥉".to_owned(),
+        _ => format!("`#{}`", index + 1),
+    };
+    let name = names.get(&standing.user).cloned().unwrap_or_else(|| format!("#{}", standing.user));
+    let song = Song::fetch_one(conn, GeneralFilter::new().song_id(Some(standing.chart.song_id()))).await?;
+    Ok(format!("{place} **{name}** +{} on {} ({}) `{} → {}`",
+               standing.gain(), song.get_name(), standing.chart.level(), standing.before, standing.score))
+}
+
+const SUGGESTIONS: usize = 8;
+
+// the /suggest embed: the charts on which score is closest to a z value of desired_z for user
+pub async fn suggestions(conn: &mut SqliteConnection, user: &User, level: DisplayLevel, score: u32, desired_z: f32, weak_genres: bool, dev_info: bool) -> Result<String, ElodonError> {
+    let filter = GeneralFilter::new()
+        .discord_id(Some(user.discord_id()))
+        .user_id(Some(user.id))
+        .display_level(Some(level));
+    let mut charts: Vec<Chart> = Chart::fetch_all(conn, filter).await?;
+
+    let mut genre_note = String::new();
+    if weak_genres {
+        let genres = weakest_genres(conn, user, level).await;
+        if genres.is_empty() {
+            return Err(CoreError::NoResults {
+                search: "genres".to_owned(),
+                id: format!("you have at least {} plays and a negative mean z value", elo::MIN_GENRE_PLAYS)
+            }.into())
+        }
+        let song_genres: HashMap<u32, Genre> = Song::fetch_all(conn, GeneralFilter::new()).await?
+            .into_iter()
+            .map(|song| (song.id, song.genre()))
+            .collect();
+        charts.retain(|chart| song_genres.get(&chart.id).is_some_and(|genre| genres.contains(genre)));
+        genre_note = format!(" in {}", genres.iter().join(" and "));
+    }
+
+    let matching_charts = elo::suggest(&charts, user.elo(level), score, desired_z, SUGGESTIONS);
+
+    let mut matching_songs: Vec<(f32, f32, Song, Level)> = Vec::with_capacity(matching_charts.len());
+
+    for (z_lower, z_upper, chart) in matching_charts {
+        let song = Song::fetch_one(conn, *chart).await?;
+        matching_songs.push(
+            (z_lower, z_upper, song, chart.level())
+        );
+    }
+
+    let mut response_text = format!("### Songs{} that <@{}> has a 70% chance of getting a score of {} with a z value of as least {}:\n",
+                                    genre_note, user.discord_id(), score,  desired_z);
+
+    let results_text = matching_songs.iter().map(|(z_lower, z_upper, song, level)| {
+        match dev_info {
+            true => {
+                format!("`{:+.2} to {:+.2}` {} ({})", z_lower, z_upper, song.get_name(), level)
+            }
+            false => {
+                format!("{} ({})", song.get_name(), level)
+            }
+        }
+    }).join("\n");
+
+    if results_text.is_empty() {
+         response_text.push_str("No results found");
+    } else {
+        response_text.push_str(&results_text);
+    }
+    Ok(response_text)
+}
+
+// the two genres the user does worst in at level, judged by their plays
+async fn weakest_genres(conn: &mut SqliteConnection, user: &User, level: DisplayLevel) -> Vec<Genre> {
+    let plays = Play::fetch_all(conn, GeneralFilter::new().user_id(Some(user.id)).display_level(Some(level)))
+        .await
+        .unwrap_or_default();
+    let mut rated = vec![];
+    for play in &plays {
+        if let Some((z, _, genre, _, shift)) = get_play_info(conn, user.elo(play.level().into()), play).await {
+            rated.push((genre, z.raw(), shift));
+        }
+    }
+    elo::weakest_genres(&elo::genre_stats(rated), 2)
+}
+
+async fn chart_name(conn: &mut SqliteConnection, play: &Play) -> Result<String, ElodonError>{
+    let chart = play.fetch_one_other::<Chart>(conn).await?;
+    let chart_name = chart.full_name(conn).await?;
+    return Ok(chart_name);
+}
+
+// (z value, play, genre, chart name, how much higher than elo the play's implied elo is)
+async fn get_play_info<'a>(conn: &mut SqliteConnection, elo: Option<f32>, play: &'a Play) -> Option<(R32, &'a Play, Genre, String, Option<f32>)>{
+    let chart = play.fetch_one_other::<Chart>(conn).await.ok()?;
+    let song = play.fetch_one_other::<Song>(conn).await.ok()?;
+    let chart_name = chart.full_name(conn).await.ok()?;
+    let z_value = R32::try_new(elo::get_z_value(play.score, elo, &chart, 1f32)?)?;
+    let elo_shift = elo::get_implied_elo(play.score, &chart).map(|implied| implied - elo.unwrap_or(implied));
+    Some((z_value, &play, song.genre(), chart_name, elo_shift))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::members;
+    use crate::paginate::{LazyPages, PageSource};
+    use crate::schema::{fixture_connection, fixture_pool};
+
+    // discord id of fixture player n
+    fn discord(n: u64) -> u64 {
+        900000000000000000 + n
+    }
+
+    async fn fixture_user(conn: &mut SqliteConnection, n: u64) -> User {
+        accounts::primary_user(conn, UserId::new(discord(n))).await.unwrap().unwrap()
+    }
+
+    fn global(author: Option<User>) -> Invocation {
+        Invocation {
+            author,
+            settings: GuildSettings { leaderboard_scope: LeaderboardScope::Global, ..Default::default() },
+            guild_id: None,
+            emojis: emoji::emojis(None),
+        }
+    }
+
+    fn options(level: Level) -> ScoreOptions {
+        ScoreOptions { level: Some(level), ..Default::default() }
+    }
+
+    // user_id -> discord_id of every fixture player
+    async fn discord_ids(conn: &mut SqliteConnection) -> HashMap<i64, u64> {
+        User::fetch_all(conn, GeneralFilter::new()).await.unwrap()
+            .into_iter()
+            .map(|user| (user.id, user.discord_id()))
+            .collect()
+    }
+
+    async fn all_rows(entries: &mut ScoreEntries) -> Vec<ScoreRow> {
+        let count = entries.count().await.unwrap();
+        entries.rows(0, count).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn scores_are_ranked() {
+        let mut entries = ScoreEntries::new(fixture_pool().await, &global(None), 1, options(Level::Oni)).await.unwrap();
+        assert_eq!(entries.level, Level::Oni);
+        let rows = all_rows(&mut entries).await;
+        assert!(rows.windows(2).all(|pair| pair[0].play.score >= pair[1].play.score));
+        assert!(rows.iter().enumerate().all(|(index, row)| row.place == Some(index as i64) && row.play.level == 4));
+
+        let mut pages = LazyPages::new("", entries);
+        let page = pages.page(0).await.unwrap();
+        assert!(page.content.starts_with("### Results for Yawaraka Tank (#1) (Oni)"));
+    }
+
+    #[tokio::test]
+    async fn scores_render_one_line_per_play() {
+        let mut entries = ScoreEntries::new(fixture_pool().await, &global(None), 1, options(Level::Oni)).await.unwrap();
+        let best = entries.rows(0, 1).await.unwrap().remove(0);
+        let rendered = entries.entries(0, 3).await.unwrap();
+        assert_eq!(rendered.len(), 3);
+        assert_eq!(rendered[0], format!("` #1) {:>7}` by {}", best.play.score, best.user_name.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn score_pages_add_up_to_the_leaderboard() {
+        let mut entries = ScoreEntries::new(fixture_pool().await, &global(None), 1, options(Level::Oni)).await.unwrap();
+        let rows = all_rows(&mut entries).await;
+        let mut paged = vec![];
+        for offset in (0..rows.len()).step_by(4) {
+            paged.extend(entries.rows(offset, 4).await.unwrap());
+        }
+        assert_eq!(paged.iter().map(|row| row.play).collect::<Vec<_>>(), rows.iter().map(|row| row.play).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn scores_sort_by_z_value() {
+        let sort = ScoreOptions { sort: ScoreSort::ZValue, ..options(Level::Oni) };
+        let mut entries = ScoreEntries::new(fixture_pool().await, &global(None), 1, sort).await.unwrap();
+        let z_values: Vec<Option<f32>> = all_rows(&mut entries).await.iter()
+            .map(|row| elo::get_z_value(row.play.score, row.elo, &entries.chart, 1f32))
+            .collect();
+        assert!(z_values.iter().any(Option::is_some));
+        // the same order as the z values the lines show, the ones without last
+        assert!(z_values.windows(2).all(|pair| match (pair[0], pair[1]) {
+            (Some(first), Some(second)) => first >= second - 1e-3,
+            (_, None) => true,
+            (None, Some(_)) => false,
+        }));
+    }
+
+    #[tokio::test]
+    async fn scores_sort_by_fewest_bads() {
+        let sort = ScoreOptions { sort: ScoreSort::Bads, ..options(Level::Oni) };
+        let mut entries = ScoreEntries::new(fixture_pool().await, &global(None), 1, sort).await.unwrap();
+        assert!(all_rows(&mut entries).await.windows(2).all(|pair| pair[0].play.bad_cnt <= pair[1].play.bad_cnt));
+    }
+
+    #[tokio::test]
+    async fn merged_scores_list_every_person_once() {
+        let mut unmerged = ScoreEntries::new(fixture_pool().await, &global(None), 1, options(Level::Oni)).await.unwrap();
+        let unmerged = all_rows(&mut unmerged).await;
+        let (best, second) = (unmerged[0].play.user, unmerged[1].play.user);
+
+        // the runner-up becomes a second donder id of the winner
+        let pool = fixture_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+        sqlx::query("UPDATE users SET discord_id = (SELECT discord_id FROM users WHERE user_id = ?) WHERE user_id = ?")
+            .bind(best)
+            .bind(second)
+            .execute(&mut *conn).await.unwrap();
+        let discord_ids = discord_ids(&mut conn).await;
+
+        let merged = ScoreOptions { merged: true, ..options(Level::Oni) };
+        drop(conn);
+        let mut entries = ScoreEntries::new(pool, &global(None), 1, merged).await.unwrap();
+        let rows = all_rows(&mut entries).await;
+        assert_eq!(rows.len(), unmerged.len() - 1);
+        assert!(rows.iter().map(|row| discord_ids[&row.play.user]).all_unique());
+        assert_eq!(rows[0].play.user, best);
+        assert!(rows.iter().enumerate().all(|(index, row)| row.place == Some(index as i64)));
+    }
+
+    #[tokio::test]
+    async fn scores_fall_back_to_easier_levels() {
+        // song 1 has no ura chart
+        let entries = ScoreEntries::new(fixture_pool().await, &global(None), 1, options(Level::Ura)).await.unwrap();
+        assert_eq!(entries.level, Level::Oni);
+        assert_eq!(entries.levels, vec![Level::Hard, Level::Oni]);
+    }
+
+    #[tokio::test]
+    async fn scores_only_list_server_members() {
+        let pool = fixture_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+        let guild_id = GuildId::new(1);
+        members::add_members(&mut conn, guild_id, [0, 1, 2].map(|n| UserId::new(discord(n))), 0).await.unwrap();
+        let discord_ids = discord_ids(&mut conn).await;
+        let invocation = Invocation {
+            settings: GuildSettings::default(),
+            guild_id: Some(guild_id),
+            ..global(None)
+        };
+        drop(conn);
+        let mut entries = ScoreEntries::new(pool, &invocation, 1, options(Level::Oni)).await.unwrap();
+        let rows = all_rows(&mut entries).await;
+        assert!(!rows.is_empty());
+        assert!(rows.iter().all(|row| [discord(0), discord(1), discord(2)].contains(&discord_ids[&row.play.user])));
+        // ranked within the server
+        assert_eq!(rows[0].place, Some(0));
+
+        let everyone = ScoreOptions { scope: Some(LeaderboardScope::Global), ..options(Level::Oni) };
+        let mut entries = ScoreEntries::new(fixture_pool().await, &invocation, 1, everyone).await.unwrap();
+        assert!(all_rows(&mut entries).await.iter().any(|row| discord_ids[&row.play.user] == discord(3)));
+    }
+
+    #[tokio::test]
+    async fn scores_are_global_outside_of_servers() {
+        let direct_message = Invocation { settings: GuildSettings::default(), ..global(None) };
+        let entries = ScoreEntries::new(fixture_pool().await, &direct_message, 1, options(Level::Oni)).await.unwrap();
+        assert!(entries.guild_id.is_none());
+
+        let server = ScoreOptions { scope: Some(LeaderboardScope::Server), ..options(Level::Oni) };
+        assert!(matches!(
+            ScoreEntries::new(fixture_pool().await, &direct_message, 1, server).await,
+            Err(ElodonError::NoGuild)
+        ));
+    }
+
+    #[tokio::test]
+    async fn scores_estimate_the_author() {
+        let pool = fixture_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+        let author = fixture_user(&mut conn, 5).await;
+        let estimates = ScoreOptions { estimates: true, ..options(Level::Oni) };
+        drop(conn);
+        let mut entries = ScoreEntries::new(pool, &global(Some(author.clone())), 1, estimates).await.unwrap();
+
+        let rows = all_rows(&mut entries).await;
+        let count = rows.len();
+        assert_eq!(rows.iter().filter(|row| row.place.is_none()).count(), 4);
+        let rendered = entries.entries(0, count).await.unwrap().join("\n");
+        assert_eq!(rendered.matches(&format!("{} (estimated)", author.name)).count(), 4);
+    }
+
+    #[tokio::test]
+    async fn unknown_song_has_no_scores() {
+        assert!(matches!(
+            ScoreEntries::new(fixture_pool().await, &global(None), 999, options(Level::Oni)).await,
+            Err(ElodonError::Core(CoreError::NoResults { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn player_profile() {
+        let pool = fixture_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+        let user = fixture_user(&mut conn, 3).await;
+        let profile = PlayerProfile::new(&mut conn, &[user.clone()], UserId::new(user.discord_id()), Some(DisplayLevel::OniPlus)).await.unwrap();
+
+        let plays = Play::fetch_all(&mut conn, GeneralFilter::new().user_id(Some(user.id)).display_level(Some(DisplayLevel::OniPlus))).await.unwrap();
+        assert_eq!(profile.name, user.name);
+        assert_eq!(profile.card.crowns.iter().sum::<u32>() as usize, plays.len());
+        assert_eq!(profile.card.notable.len(), 5);
+        assert!(profile.preamble.starts_with(&format!("## User <@{}> ({})\n Showing plays where level is Oni+.\n", discord(3), user.name)));
+        assert!(profile.preamble.contains("### Strengths and weaknesses\n"));
+
+        drop(conn);
+        let mut entries = PlayerEntries::new(pool, vec![user.id], Some(DisplayLevel::OniPlus));
+        assert_eq!(entries.count().await.unwrap(), plays.len());
+        let rendered = entries.entries(0, 2).await.unwrap();
+        let first = plays.iter().filter(|play| play.song == 1).max_by_key(|play| play.score).unwrap();
+        assert_eq!(rendered[0], format!("{:>7} on {}", first.score, "Yawaraka Tank (Oni)"));
+    }
+
+    #[tokio::test]
+    async fn player_without_plays() {
+        let pool = fixture_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+        let user = fixture_user(&mut conn, 3).await;
+        let profile = PlayerProfile::new(&mut conn, &[user.clone()], UserId::new(user.discord_id()), Some(DisplayLevel::Easy)).await.unwrap();
+        assert!(profile.card.notable.is_empty());
+        drop(conn);
+        assert_eq!(PlayerEntries::new(pool, vec![user.id], Some(DisplayLevel::Easy)).count().await.unwrap(), 0);
+        assert!(profile.preamble.contains("No plays found. Player has no ELO\n"));
+    }
+
+    #[tokio::test]
+    async fn suggest_lists_eight_charts() {
+        let mut conn = fixture_connection().await;
+        let user = fixture_user(&mut conn, 6).await;
+        let text = suggestions(&mut conn, &user, DisplayLevel::OniPlus, 900000, 0., false, true).await.unwrap();
+
+        let mut lines = text.lines();
+        assert_eq!(lines.next().unwrap(), format!(
+            "### Songs that <@{}> has a 70% chance of getting a score of 900000 with a z value of as least 0:", discord(6)
+        ));
+        let results: Vec<&str> = lines.collect();
+        assert_eq!(results.len(), 8);
+        assert!(results.iter().all(|line| line.starts_with('`') && line.contains(" to ")));
+    }
+
+    #[tokio::test]
+    async fn suggest_weak_genres() {
+        let mut conn = fixture_connection().await;
+        let user = fixture_user(&mut conn, 6).await;
+        let genres = weakest_genres(&mut conn, &user, DisplayLevel::OniPlus).await;
+        let text = suggestions(&mut conn, &user, DisplayLevel::OniPlus, 900000, 0., true, false).await.unwrap();
+
+        assert!(text.starts_with(&format!("### Songs in {} that", genres.iter().join(" and "))));
+        let songs = Song::fetch_all(&mut conn, GeneralFilter::new()).await.unwrap();
+        for line in text.lines().skip(1) {
+            let song = songs.iter().find(|song| line.starts_with(&song.get_name())).unwrap();
+            assert!(genres.contains(&song.genre()));
+        }
+    }
+
+    #[tokio::test]
+    async fn records_show_their_holder() {
+        let pool = fixture_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+        let user = fixture_user(&mut conn, 0).await;
+        let song = Song::fetch_one(&mut conn, GeneralFilter::new().song_id(Some(1))).await.unwrap();
+        let record = Record { guild_id: 1, song_id: 1, level_id: 4, user_id: user.id, score: 1000000, held_since: 1700000000 };
+
+        drop(conn);
+        let mut entries = RecordEntries::new(pool, vec![record], HashMap::new());
+        assert_eq!(entries.entries(0, 10).await.unwrap(), vec![
+            format!("`1000000` on {} (Oni) since <t:1700000000:d>", song.get_name())
+        ]);
+        entries.names.insert(user.id, user.name.clone());
+        assert!(entries.entries(0, 10).await.unwrap()[0].contains(&format!(" by **{}** ", user.name)));
+    }
+
+    #[tokio::test]
+    async fn standings_get_medals() {
+        let mut conn = fixture_connection().await;
+        let user = fixture_user(&mut conn, 0).await;
+        let song = Song::fetch_one(&mut conn, GeneralFilter::new().song_id(Some(1))).await.unwrap();
+        let standing = Standing { user: user.id, chart: ChartId(1, Level::Oni), before: 900000, score: 950000 };
+        let names = HashMap::from([(user.id, user.name.clone())]);
+
+        assert_eq!(standing_line(&mut conn, 0, &standing, &names).await.unwrap(),
+                   format!("🥇 **{}** +50000 on {} (Oni) `900000 → 950000`", user.name, song.get_name()));
+        assert!(standing_line(&mut conn, 3, &standing, &HashMap::new()).await.unwrap().starts_with(&format!("`#4` **#{}**", user.id)));
+    }
+}